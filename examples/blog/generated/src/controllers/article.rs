@@ -1,9 +1,10 @@
 // @generated by via-core. DO NOT EDIT BY HAND.
-// source: /Users/ndbroadbent/code/viaduct/examples/blog/app/resources/articles.via
+// source: app/resources/articles.via
 
 use loco_rs::prelude::*;
 use serde_json::json;
-use crate::models::{ArticleCreateParams, ArticleUpdateParams};
+use crate::models::{Article, ArticleCreateParams, ArticleUpdateParams};
+use crate::views;
 
 pub const RESPOND_WITH: &[&str] = &["html", "json"];
 
@@ -14,14 +15,28 @@ pub fn routes() -> Routes {
         .add("/{id}", get(show))
         .add("/", post(create))
         .add("/{id}", put(update))
+        .add("/{id}", patch(partial_update))
         .add("/{id}", delete(destroy))
+        .add("/new", get(new))
+        .add("/{id}/edit", get(edit))
 }
 
-pub async fn index(State(_ctx): State<AppContext>) -> Result<Response> {
+pub async fn index(State(_ctx): State<AppContext>, ViewEngine(v): ViewEngine<TeraView>, Format(respond_to): Format) -> Result<Response> {
+    if matches!(respond_to, RespondTo::Html) {
+        return views::article::list(&v, &[]);
+    }
     format::json(json!({"todo": "Article#index"}))
 }
 
-pub async fn show(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {
+pub async fn show(State(_ctx): State<AppContext>, ViewEngine(v): ViewEngine<TeraView>, Format(respond_to): Format, Path(id): Path<String>) -> Result<Response> {
+    if matches!(respond_to, RespondTo::Html) {
+        // TODO: load the record by `id`; until then this renders a 404.
+        let item: Option<Article> = None;
+        return match item {
+            Some(item) => views::article::show(&v, &item),
+            None => not_found(),
+        };
+    }
     format::json(json!({"todo": "Article#show", "id": id}))
 }
 
@@ -29,14 +44,31 @@ pub async fn create(State(_ctx): State<AppContext>, Json(payload): Json<ArticleC
     format::json(json!({"todo": "Article#create", "payload": payload}))
 }
 
-pub async fn update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<ArticleUpdateParams>) -> Result<Response> {
+pub async fn update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<ArticleCreateParams>) -> Result<Response> {
     format::json(json!({"todo": "Article#update", "id": id, "payload": payload}))
 }
 
+pub async fn partial_update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<ArticleUpdateParams>) -> Result<Response> {
+    format::json(json!({"todo": "Article#partial_update", "id": id, "payload": payload}))
+}
+
 pub async fn destroy(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {
     format::json(json!({"todo": "Article#destroy", "id": id}))
 }
 
+pub async fn new(ViewEngine(v): ViewEngine<TeraView>) -> Result<Response> {
+    views::article::create(&v)
+}
+
+pub async fn edit(ViewEngine(v): ViewEngine<TeraView>, Path(_id): Path<String>) -> Result<Response> {
+    // TODO: load the record by `id`; until then this renders a 404.
+    let item: Option<Article> = None;
+    match item {
+        Some(item) => views::article::edit(&v, &item),
+        None => not_found(),
+    }
+}
+
 // Associated model fields
 // - title: String
 // - body: String
@@ -1,5 +1,5 @@
 // @generated by via-core. DO NOT EDIT BY HAND.
-// source: /Users/ndbroadbent/code/viaduct/examples/blog/app/resources/comments.via
+// source: app/resources/comments.via
 
 use loco_rs::prelude::*;
 use serde_json::json;
@@ -14,6 +14,7 @@ pub fn routes() -> Routes {
         .add("/{id}", get(show))
         .add("/", post(create))
         .add("/{id}", put(update))
+        .add("/{id}", patch(partial_update))
         .add("/{id}", delete(destroy))
 }
 
@@ -29,10 +30,14 @@ pub async fn create(State(_ctx): State<AppContext>, Json(payload): Json<CommentC
     format::json(json!({"todo": "Comment#create", "payload": payload}))
 }
 
-pub async fn update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<CommentUpdateParams>) -> Result<Response> {
+pub async fn update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<CommentCreateParams>) -> Result<Response> {
     format::json(json!({"todo": "Comment#update", "id": id, "payload": payload}))
 }
 
+pub async fn partial_update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<CommentUpdateParams>) -> Result<Response> {
+    format::json(json!({"todo": "Comment#partial_update", "id": id, "payload": payload}))
+}
+
 pub async fn destroy(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {
     format::json(json!({"todo": "Comment#destroy", "id": id}))
 }
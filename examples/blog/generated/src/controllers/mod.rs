@@ -5,3 +5,14 @@ pub mod comment;
 
 pub use article::*;
 pub use comment::*;
+
+use loco_rs::prelude::Routes;
+
+/// Every generated controller's routes, ordered by resource name, for `Hooks::routes` to
+/// `add_route` one by one.
+pub fn routes() -> Vec<Routes> {
+    vec![
+        article::routes(),
+        comment::routes(),
+    ]
+}
@@ -1,5 +1,5 @@
 // @generated by via-core. DO NOT EDIT BY HAND.
-// source: /Users/ndbroadbent/code/viaduct/examples/blog/app/resources/articles.via
+// source: app/resources/articles.via
 
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +12,16 @@ pub struct Article {
     pub author_name: String,
 }
 
+impl Article {
+    /// Database table backing this model.
+    pub const TABLE_NAME: &str = "articles";
+
+    /// Label for admin and debug views: `title`, the first string field.
+    pub fn summary(&self) -> String {
+        self.title.clone()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArticleCreateParams {
     pub title: String,
@@ -34,3 +44,21 @@ pub struct ArticleUpdateParams {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub author_name: Option<String>,
 }
+
+impl ArticleUpdateParams {
+    /// Applies the fields present in a PATCH body to `model`; absent fields keep their value.
+    pub fn apply_to(self, model: &mut Article) {
+        if let Some(value) = self.title {
+            model.title = value;
+        }
+        if let Some(value) = self.body {
+            model.body = value;
+        }
+        if let Some(value) = self.published_at {
+            model.published_at = Some(value);
+        }
+        if let Some(value) = self.author_name {
+            model.author_name = value;
+        }
+    }
+}
@@ -1,5 +1,5 @@
 // @generated by via-core. DO NOT EDIT BY HAND.
-// source: /Users/ndbroadbent/code/viaduct/examples/blog/app/resources/comments.via
+// source: app/resources/comments.via
 
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +10,16 @@ pub struct Comment {
     pub article_id: String,
 }
 
+impl Comment {
+    /// Database table backing this model.
+    pub const TABLE_NAME: &str = "comments";
+
+    /// Label for admin and debug views: `body`, the first string field.
+    pub fn summary(&self) -> String {
+        self.body.clone()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentCreateParams {
     pub body: String,
@@ -28,3 +38,18 @@ pub struct CommentUpdateParams {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub article_id: Option<String>,
 }
+
+impl CommentUpdateParams {
+    /// Applies the fields present in a PATCH body to `model`; absent fields keep their value.
+    pub fn apply_to(self, model: &mut Comment) {
+        if let Some(value) = self.body {
+            model.body = value;
+        }
+        if let Some(value) = self.author_name {
+            model.author_name = value;
+        }
+        if let Some(value) = self.article_id {
+            model.article_id = value;
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod article;
 pub mod comment;
+pub mod prelude;
 
 pub use article::*;
 pub use comment::*;
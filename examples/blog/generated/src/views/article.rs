@@ -0,0 +1,42 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+// source: app/resources/articles.via
+
+use loco_rs::prelude::*;
+
+use crate::models::Article;
+
+/// Render a list view of `articles`.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn list(v: &impl ViewRenderer, items: &[Article]) -> Result<Response> {
+    format::render().view(v, "articles/list.html", data!({"items": items}))
+}
+
+/// Render a single `articles` view.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn show(v: &impl ViewRenderer, item: &Article) -> Result<Response> {
+    format::render().view(v, "articles/show.html", data!({"item": item}))
+}
+
+/// Render a `articles` create form.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn create(v: &impl ViewRenderer) -> Result<Response> {
+    format::render().view(v, "articles/create.html", data!({}))
+}
+
+/// Render a `articles` edit form.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn edit(v: &impl ViewRenderer, item: &Article) -> Result<Response> {
+    format::render().view(v, "articles/edit.html", data!({"item": item}))
+}
@@ -0,0 +1,3 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+pub mod article;
@@ -8,6 +8,11 @@ export interface Article {
   author_name: string;
 }
 
+// Label for admin and debug views, as `Article::summary()` builds it in Rust.
+export function articleSummary(record: Article): string {
+  return record.title;
+}
+
 export type ArticleCreateParams = {
   title: string;
   body: string;
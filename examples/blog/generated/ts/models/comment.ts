@@ -7,6 +7,11 @@ export interface Comment {
   article_id: string;
 }
 
+// Label for admin and debug views, as `Comment::summary()` builds it in Rust.
+export function commentSummary(record: Comment): string {
+  return record.body;
+}
+
 export type CommentCreateParams = {
   body: string;
   author_name: string;
@@ -0,0 +1,46 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+// source: /tmp/v1app/a.via
+
+use loco_rs::prelude::*;
+use serde_json::json;
+
+pub const RESPOND_WITH: &[&str] = &["html", "json"];
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("authors/")
+        .add("/", get(index))
+        .add("/{id}", get(show))
+        .add("/", post(create))
+        .add("/{id}", put(update))
+        .add("/{id}", patch(partial_update))
+        .add("/{id}", delete(destroy))
+}
+
+pub async fn index(State(_ctx): State<AppContext>) -> Result<Response> {
+    format::json(json!({"todo": "Author#index"}))
+}
+
+pub async fn show(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {
+    format::json(json!({"todo": "Author#show", "id": id}))
+}
+
+pub async fn create(State(_ctx): State<AppContext>, Json(payload): Json<serde_json::Value>) -> Result<Response> {
+    format::json(json!({"todo": "Author#create", "payload": payload}))
+}
+
+pub async fn update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<serde_json::Value>) -> Result<Response> {
+    format::json(json!({"todo": "Author#update", "id": id, "payload": payload}))
+}
+
+pub async fn partial_update(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<serde_json::Value>) -> Result<Response> {
+    format::json(json!({"todo": "Author#partial_update", "id": id, "payload": payload}))
+}
+
+pub async fn destroy(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {
+    format::json(json!({"todo": "Author#destroy", "id": id}))
+}
+
+// Associated model fields
+// - id: String (UUID string; use uuid::Uuid for strong typing)
+// - name: String
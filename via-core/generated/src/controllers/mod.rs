@@ -0,0 +1,7 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+pub mod author;
+pub mod post;
+
+pub use author::*;
+pub use post::*;
@@ -0,0 +1,4 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+pub mod controllers;
+pub mod models;
@@ -0,0 +1,20 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+// source: /tmp/v1app/a.via
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub id: String,
+    pub name: String,
+}
+
+impl Author {
+    /// Database table backing this model.
+    pub const TABLE_NAME: &str = "authors";
+
+    /// Label for admin and debug views: `name`, the first string field.
+    pub fn summary(&self) -> String {
+        self.name.clone()
+    }
+}
@@ -0,0 +1,8 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+pub mod author;
+pub mod post;
+pub mod prelude;
+
+pub use author::*;
+pub use post::*;
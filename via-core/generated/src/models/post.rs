@@ -0,0 +1,20 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+// source: /tmp/v1app/a.via
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Post {
+    pub id: String,
+    pub title: String,
+}
+
+impl Post {
+    /// Database table backing this model.
+    pub const TABLE_NAME: &str = "posts";
+
+    /// Label for admin and debug views: `title`, the first string field.
+    pub fn summary(&self) -> String {
+        self.title.clone()
+    }
+}
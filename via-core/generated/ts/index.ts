@@ -0,0 +1,4 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+export * from './models/author';
+export * from './models/post';
@@ -0,0 +1,12 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+export interface Author {
+  // UUID string
+  id: string;
+  name: string;
+}
+
+// Label for admin and debug views, as `Author::summary()` builds it in Rust.
+export function authorSummary(record: Author): string {
+  return record.name;
+}
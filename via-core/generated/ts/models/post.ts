@@ -0,0 +1,12 @@
+// @generated by via-core. DO NOT EDIT BY HAND.
+
+export interface Post {
+  // UUID string
+  id: string;
+  title: string;
+}
+
+// Label for admin and debug views, as `Post::summary()` builds it in Rust.
+export function postSummary(record: Post): string {
+  return record.title;
+}
@@ -1,35 +1,397 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::inflect;
+
+/// Everything declared across one or more `.via` files. Parsing produces one per file; the CLI
+/// merges them before resolve and codegen, and the IR is this struct serialized.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Program {
+    pub resources: Vec<Resource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enums: Vec<EnumDecl>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scalars: Vec<ScalarAlias>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<Import>,
+}
+
+impl Program {
+    /// Drops resource and field spans so the serialized IR stays compact.
+    pub fn strip_spans(&mut self) {
+        for resource in &mut self.resources {
+            resource.span = None;
+            for field in resource
+                .model
+                .iter_mut()
+                .flat_map(|model| &mut model.fields)
+            {
+                field.span = None;
+            }
+        }
+    }
+
+    /// Appends every declaration from `other` (typically the next parsed file).
+    pub fn extend(&mut self, other: Program) {
+        self.resources.extend(other.resources);
+        self.enums.extend(other.enums);
+        self.scalars.extend(other.scalars);
+        self.imports.extend(other.imports);
+    }
+}
+
+/// A named, closed set of values usable as a field type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumDecl {
+    pub name: String,
+    pub variants: Vec<String>,
+    pub file_path: String,
+    /// Where the enum was declared, for diagnostics. Not part of the IR.
+    #[serde(skip)]
+    pub location: Option<SourceLocation>,
+}
+
+/// A new type name standing for an existing one (e.g. `Email` for `String`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalarAlias {
+    pub name: String,
+    pub target: TypeRef,
+    pub file_path: String,
+}
+
+/// Another `.via` file whose declarations the importing file refers to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Import {
+    pub path: String,
+    pub file_path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resource {
     pub name: String,
+    /// `table "..."`: overrides the table name derived from the resource name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    /// `sti: kind`: the model field whose variants split this resource into a TypeScript
+    /// discriminated union.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sti: Option<String>,
+    /// `default_order: created_at desc`: the ordering `index` applies when the request has no
+    /// `?order=`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_order: Option<DefaultOrder>,
+    /// `default_scope { where published == true }`: conditions, all of which must hold, that
+    /// `index` and `show` apply to every query.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_scope: Vec<ScopeCondition>,
+    /// `pagination: cursor` pages `index` by keyset instead of returning every row.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<String>,
+    /// `derives: [PartialEq, Hash]`: extra derives appended to the model struct, each one of
+    /// [`MODEL_DERIVES`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derives: Vec<String>,
+    /// `resource Name @deprecated("...")`: the note shown wherever the resource is generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// Cross-field rules from `validate { ... }`, in declaration order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validations: Vec<RequiredWhen>,
     pub model: Option<Model>,
     pub controller: Option<Controller>,
     pub file_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl Resource {
+    /// The explicit `table "..."` override, or the pluralized snake_case name (`people` for
+    /// `Person`).
+    pub fn table_name(&self) -> String {
+        self.table
+            .clone()
+            .unwrap_or_else(|| inflect::pluralize(&self.name))
+    }
+
+    /// Whether `index` uses keyset pagination (`pagination: cursor`).
+    pub fn cursor_paginated(&self) -> bool {
+        self.pagination.as_deref() == Some("cursor")
+    }
+}
+
+/// `require shipping_address when method == "ship"`: `field` must be present whenever `when`
+/// holds `equals`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequiredWhen {
+    pub field: String,
+    pub when: String,
+    /// The literal's text without quotes; interpreted by the type of `when`, like `@in` values.
+    pub equals: String,
+}
+
+/// `where published == true` in a `default_scope`: rows are only visible while `field` equals
+/// `equals`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeCondition {
+    pub field: String,
+    /// The literal's text without quotes; interpreted by the type of `field`, like `@in` values.
+    pub equals: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultOrder {
+    pub field: String,
+    /// `asc` or `desc` (checked during resolution); ascending when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+}
+
+impl DefaultOrder {
+    pub fn direction(&self) -> &str {
+        self.direction.as_deref().unwrap_or("asc")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub fields: Vec<Field>,
+    /// `belongs_to name: (A | B)` relations. Their `<name>_type`/`<name>_id` columns are also
+    /// in `fields`, where the declaration stood.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub polymorphic: Vec<PolymorphicRelation>,
+    /// `belongs_to post` and `has_many comments` declarations. A `belongs_to` also puts its
+    /// `<name>_id` column in `fields`, where the declaration stood.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    BelongsTo,
+    HasMany,
+}
+
+impl RelationKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            RelationKind::BelongsTo => "belongs_to",
+            RelationKind::HasMany => "has_many",
+        }
+    }
+}
+
+/// `belongs_to post` (a `post_id` key pointing at `Post`) or `has_many comments` (`Comment`
+/// rows holding a key pointing back here).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relation {
+    pub kind: RelationKind,
+    /// The name as written: singular for `belongs_to`, plural for `has_many`.
+    pub name: String,
+    /// The resource the relation points at.
+    pub target: String,
+    /// `belongs_to post?`: the key column is nullable.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl Relation {
+    /// The `belongs_to` key column on the declaring resource.
+    pub fn key_field(&self) -> String {
+        format!("{}_id", self.name)
+    }
+}
+
+/// `belongs_to commentable: (Post | Image)`: a `<name>_type` column naming one of `targets`
+/// and a `<name>_id` column holding that record's id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolymorphicRelation {
+    pub name: String,
+    pub optional: bool,
+    pub targets: Vec<String>,
+}
+
+impl PolymorphicRelation {
+    pub fn type_field(&self) -> String {
+        format!("{}_type", self.name)
+    }
+
+    pub fn id_field(&self) -> String {
+        format!("{}_id", self.name)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FieldAttributes {
     pub serialize: Option<bool>,
+    /// `@in(...)`: the field value must be one of these literals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<String>>,
+    /// `= computed("...")`: opaque expression the field is derived from; never stored or editable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub computed: Option<String>,
+    /// `= "draft"`: the value a new record gets when none is given, also the column's DEFAULT.
+    /// Resolve makes a defaulted field non-optional, so its column is NOT NULL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Literal>,
+    /// `@encrypted`: stored as AES-256-GCM ciphertext and never serialized.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+    /// `@sensitive`: printed as `***` by the generated `Debug` impls (as `@encrypted` fields
+    /// are), whether or not it is serialized.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
+    /// `@transient`: set per request (e.g. a distance on a geo query) and returned in responses,
+    /// but never stored or editable. Unlike `computed`, there is no expression behind it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub transient: bool,
+    /// `@title`: the field the generated `summary()` helpers label a record with, instead of the
+    /// first string field.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub title: bool,
+    /// `@feature("name")`: the field only exists when the generated crate's cargo feature is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    /// `@deprecated("...")`: still generated, but marked deprecated with this note.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// `@on_delete(cascade|restrict|set_null)` on a `<resource>_id` key: the referential action
+    /// when the referenced row is deleted. Restrict when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<String>,
+    /// `@min_len(n)`: minimum string length (String/Text only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_len: Option<u64>,
+    /// `@max_len(n)`: maximum string length (String/Text only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_len: Option<u64>,
+    /// `@min(n)`: inclusive lower bound on a numeric value, as written (`0`, `-1.5`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<String>,
+    /// `@max(n)`: inclusive upper bound on a numeric value, as written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<String>,
+    /// `@format(name)`: a well-known string format, one of [`FIELD_FORMATS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// `@label("Title")`: shown instead of the field name in generated views, for any locale
+    /// without its own entry in `labels`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// `@label(en: "Title", fr: "Titre")`: view labels keyed by locale.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// A field default as written: `= "draft"`, `= 0`, `= true`, or `= now()` (the current time,
+/// for `DateTime` fields).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Literal {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Now,
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::String(value) => write!(f, "{:?}", value),
+            Literal::Integer(value) => write!(f, "{}", value),
+            Literal::Bool(value) => write!(f, "{}", value),
+            Literal::Now => f.write_str("now()"),
+        }
+    }
+}
+
+/// Derives `derives: [...]` accepts on a model struct, with the derives each one requires.
+pub const MODEL_DERIVES: &[(&str, &[&str])] = &[
+    ("PartialEq", &[]),
+    ("Eq", &["PartialEq"]),
+    ("PartialOrd", &["PartialEq"]),
+    ("Ord", &["Eq", "PartialOrd"]),
+    ("Hash", &[]),
+];
+
+/// Format names `@format(...)` accepts; they match JSON Schema's `format` keyword.
+pub const FIELD_FORMATS: &[&str] = &["uuid", "email", "uri", "date-time"];
+
+impl FieldAttributes {
+    /// Whether the field carries a constraint enforced through `validator` (`@in`, lengths,
+    /// ranges, formats).
+    pub fn validated(&self) -> bool {
+        self.allowed.is_some()
+            || self.format.is_some()
+            || self.min_len.is_some()
+            || self.max_len.is_some()
+            || self.min.is_some()
+            || self.max.is_some()
+    }
+
+    /// Whether the field has a database column; computed and `@transient` fields don't.
+    pub fn stored(&self) -> bool {
+        self.computed.is_none() && !self.transient
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
     pub ty: TypeRef,
+    /// Merged optionality: `?` on the field name or on the type.
     pub optional: bool,
     pub attributes: FieldAttributes,
+    /// `?` written on the field name itself (`title?: String`). Diagnostics only; not in the IR.
+    #[serde(skip)]
+    pub name_optional: bool,
+    /// Where the field was declared, for diagnostics. Not part of the IR.
+    #[serde(skip)]
+    pub location: Option<SourceLocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+/// 1-based line and column in the source `.via` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Source range of a declaration, for tooling. Only kept in the IR with `--ir-spans`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub file: String,
+    pub start: SourceLocation,
+    /// Just past the last character of the declaration.
+    pub end: SourceLocation,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeRef {
     pub name: String,
     pub optional: bool,
+    #[serde(default, skip_serializing_if = "TypeKind::is_named")]
+    pub kind: TypeKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum TypeKind {
+    /// A builtin, alias, enum, or resource, referred to by `name`.
+    #[default]
+    Named,
+    /// `status: enum(draft, published)` written on the field. The parser names it
+    /// `<Resource><Field>` and also adds it to [`Program::enums`], so codegen treats it like any
+    /// declared enum.
+    Enum(Vec<String>),
+}
+
+impl TypeKind {
+    pub fn is_named(&self) -> bool {
+        matches!(self, TypeKind::Named)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -37,6 +399,46 @@ pub struct Controller {
     pub params: Vec<ParamsProfile>,
     pub respond_with: Vec<String>,
     pub actions: ControllerActions,
+    /// `authorize: true` / `authorize [create, update]`: actions that call a `can_<action>` guard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorize: Option<Authorize>,
+    /// `action show { ... }` blocks adjusting individual actions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub action_overrides: Vec<ActionOverride>,
+}
+
+impl Controller {
+    /// Formats `action` responds with: its own `respond_with` override, else the controller's.
+    pub fn formats_for(&self, action: &str) -> &[String] {
+        self.action_overrides
+            .iter()
+            .find(|entry| entry.name == action)
+            .and_then(|entry| entry.respond_with.as_deref())
+            .unwrap_or(&self.respond_with)
+    }
+}
+
+/// Settings for one action that take precedence over the controller-level ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOverride {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub respond_with: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Authorize {
+    All,
+    Actions(Vec<String>),
+}
+
+impl Authorize {
+    pub fn covers(&self, action: &str) -> bool {
+        match self {
+            Authorize::All => true,
+            Authorize::Actions(actions) => actions.iter().any(|name| name == action),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -56,6 +458,9 @@ pub struct Action {
 pub struct ParamsProfile {
     pub name: ParamsKind,
     pub entries: Vec<ParamEntry>,
+    /// `editable: *` — entries are derived from the model fields during resolution.
+    #[serde(default)]
+    pub wildcard: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -0,0 +1,92 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result, anyhow};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+
+use crate::{ast::Program, config::Config};
+
+/// Default file written by `via bundle`.
+pub const BUNDLE_FILE_NAME: &str = "via.viabundle";
+
+/// Marks a file as a bundle, so `gen --from-ir` can reject a plain IR dump with a useful error.
+pub const BUNDLE_FORMAT: &str = "viabundle";
+
+/// Bumped whenever the bundle or IR layout changes incompatibly; loaders reject other versions.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// First bytes of a gzip stream; compressed bundles are detected by these rather than by name.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A resolved program plus the project config it was resolved under: everything `via gen` needs
+/// to reproduce the same output on another machine without the `.via` sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub format: String,
+    pub version: u32,
+    /// `via` version that wrote the bundle; informational only.
+    pub generator: String,
+    pub config: Config,
+    pub program: Program,
+}
+
+impl Bundle {
+    pub fn new(program: Program, config: Config) -> Self {
+        Self {
+            format: BUNDLE_FORMAT.to_owned(),
+            version: BUNDLE_VERSION,
+            generator: format!("via {}", env!("CARGO_PKG_VERSION")),
+            config,
+            program,
+        }
+    }
+
+    /// Pretty JSON, or gzip-compressed JSON when `compress` is set.
+    pub fn to_bytes(&self, compress: bool) -> Result<Vec<u8>> {
+        let json = serde_json::to_string_pretty(self)? + "\n";
+        if !compress {
+            return Ok(json.into_bytes());
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(json.as_bytes())?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Reads a bundle written by [`Bundle::to_bytes`], compressed or not, and checks that this
+    /// `via` understands its version.
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            fs::read(path).with_context(|| format!("Failed to read bundle {}", path.display()))?;
+        Self::from_bytes(&bytes).map_err(|err| anyhow!("{}: {}", path.display(), err))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let json = if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = String::new();
+            GzDecoder::new(bytes)
+                .read_to_string(&mut json)
+                .context("bundle is not valid gzip")?;
+            json
+        } else {
+            String::from_utf8(bytes.to_vec()).context("bundle is not UTF-8 JSON")?
+        };
+
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        if value.get("format").and_then(|format| format.as_str()) != Some(BUNDLE_FORMAT) {
+            return Err(anyhow!("not a via bundle (create one with `via bundle`)"));
+        }
+        let version = value.get("version").and_then(|version| version.as_u64());
+        if version != Some(u64::from(BUNDLE_VERSION)) {
+            return Err(anyhow!(
+                "bundle version {} is not supported (expected {})",
+                version.map_or_else(|| "?".to_owned(), |version| version.to_string()),
+                BUNDLE_VERSION
+            ));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
@@ -1,12 +1,35 @@
-use std::{collections::HashMap, fmt::Write, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
 
-use crate::ast::{Controller, ControllerActions, Field, Model, ParamEntry, ParamsKind, Resource};
+use crate::{
+    ast::{
+        Controller, ControllerActions, DefaultOrder, EnumDecl, Field, Literal, Model, ParamEntry,
+        ParamsKind, PolymorphicRelation, Program, RequiredWhen, Resource,
+    },
+    graph::{self, Relation, RelationKind},
+    inflect::pluralize,
+    ir, writer,
+};
 
 pub const GENERATED_HEADER: &str = "// @generated by via-core. DO NOT EDIT BY HAND.\n";
 
+/// Default file name of the serialized IR, relative to the output root.
+pub const IR_FILE_NAME: &str = "via.ir.json";
+
+/// Output-root file listing generated paths as `linguist-generated` (see [`GenerateOptions::gitattributes`]).
+pub const GITATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+
+/// Output-relative directory of the loco migration crate the migrations generator writes into.
+pub const MIGRATION_DIR: &str = "migration";
+
 #[derive(Debug, Clone)]
 pub struct GeneratedFile {
     pub relative_path: PathBuf,
@@ -16,6 +39,8 @@ pub struct GeneratedFile {
 #[derive(Debug, Default)]
 pub struct GenerationOutput {
     pub files: Vec<GeneratedFile>,
+    /// Which generator produced `files`, as `(name, count)` runs in file order.
+    pub generated_by: Vec<(&'static str, usize)>,
 }
 
 impl GenerationOutput {
@@ -27,69 +52,870 @@ impl GenerationOutput {
     }
 }
 
-pub fn generate(resources: &[Resource]) -> Result<GenerationOutput> {
+/// Output-relative directories for generated modules and TypeScript, set from `via.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub models_dir: PathBuf,
+    pub controllers_dir: PathBuf,
+    pub ts_dir: PathBuf,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            models_dir: PathBuf::from("src/models"),
+            controllers_dir: PathBuf::from("src/controllers"),
+            ts_dir: PathBuf::from("ts"),
+        }
+    }
+}
+
+/// How `via gen --out-format` arranges the generated modules on disk. Either way the crate has
+/// the same module tree (`crate::models::article`, ...), so only file paths differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutFormat {
+    /// `src/models/<resource>.rs`, `src/controllers/<resource>.rs`, `ts/models/<resource>.ts`.
+    #[default]
+    Nested,
+    /// `src/<resource>_model.rs`, `src/<resource>_controller.rs`, and `ts/<resource>_model.ts`,
+    /// with `src/models.rs` and `src/controllers.rs` mapping them into modules via `#[path]`.
+    Flat,
+}
+
+/// How generated models, params, and pages spell their JSON keys, set by `json_case` in
+/// `via.toml`. Rust fields stay snake_case; camelCase structs get serde's `rename_all`, and
+/// TypeScript, OpenAPI, and view templates derive their keys with the same rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonCase {
+    #[default]
+    Snake,
+    Camel,
+}
+
+impl JsonCase {
+    /// The wire name of a snake_case field, matching serde's `rename_all = "camelCase"` rule
+    /// (underscores dropped, the letter after each one uppercased).
+    pub fn key(self, field: &str) -> String {
+        match self {
+            Self::Snake => field.to_owned(),
+            Self::Camel => {
+                let mut key = String::with_capacity(field.len());
+                let mut upper = false;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        upper = !key.is_empty();
+                    } else if key.is_empty() {
+                        key.push(ch.to_ascii_lowercase());
+                    } else if upper {
+                        key.push(ch.to_ascii_uppercase());
+                        upper = false;
+                    } else {
+                        key.push(ch);
+                    }
+                }
+                key
+            }
+        }
+    }
+
+    /// The container attribute a serialized struct needs for this case, if any.
+    fn serde_attr(self) -> Option<&'static str> {
+        match self {
+            Self::Snake => None,
+            Self::Camel => Some("#[serde(rename_all = \"camelCase\")]"),
+        }
+    }
+}
+
+/// Knobs for optional generator output. Defaults match the plain `via gen` behavior.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Emit branded `<Resource>Id` types and use them for ids/foreign keys in TypeScript.
+    pub ts_branded_ids: bool,
+    /// Emit every TypeScript declaration into a single `ts/schema.ts` instead of per-model files.
+    pub ts_single_file: bool,
+    /// Emit `ts/factories/<resource>.ts` mock builders for front-end tests.
+    pub ts_factories: bool,
+    /// Emit each enum as an `as const` object plus the union type derived from it, so its
+    /// values can be enumerated at runtime, instead of a bare string union.
+    pub ts_enum_runtime: bool,
+    /// Emit an `openapi.json` describing the generated CRUD routes.
+    pub openapi: bool,
+    /// Emit `proto/<resource>.proto` message and gRPC service definitions.
+    pub proto: bool,
+    /// Emit a `requests.http` with a ready-to-run request per route (VS Code REST Client,
+    /// IntelliJ HTTP Client).
+    pub http_file: bool,
+    /// Wrap each generated controller action in a `tracing` span named `<resource>.<action>`.
+    pub tracing: bool,
+    /// Emit a `<Resource>Error` enum per controller, return it from the CRUD handlers, and list
+    /// its codes (and the 500 it can answer with) in the OpenAPI document.
+    pub errors: bool,
+    /// Emit a fluent `<Model>Builder` next to each model.
+    pub builders: bool,
+    /// Emit a loco `Task` stub per model with required columns, for backfilling them when a
+    /// migration adds one to a populated table.
+    pub tasks: bool,
+    /// Emit expanded sea_orm entities (`Entity`, `Column`, `PrimaryKey`, `Relation`, ...)
+    /// under `src/entities/`.
+    pub explicit_entities: bool,
+    /// Add a `DeriveRelatedEntity` enum to each explicit entity for seaography's schema builder,
+    /// behind the generated crate's opt-in `seaography` feature.
+    pub seaography: bool,
+    /// Emit a `.gitattributes` marking every generated path (and the IR) as `linguist-generated`.
+    pub gitattributes: bool,
+    /// Emit a loco `create_table` migration per model under `migration/src/` (see
+    /// [`MigrationGenerator`]).
+    pub migrations: bool,
+    /// Existing migration module names by table, for [`MigrationGenerator::existing`].
+    pub migration_names: BTreeMap<String, String>,
+    /// Unix time stamped on new migrations, for [`MigrationGenerator::timestamp`].
+    pub migration_time: u64,
+    /// Emit `src/introspection.rs`, serving the embedded IR at `GET /_via/resources` behind the
+    /// generated crate's default `introspection` feature.
+    pub introspection: bool,
+    /// Cargo features treated as enabled when deciding whether `@feature` fields appear in the
+    /// TypeScript, OpenAPI, and Protobuf output. Rust output always gates them with `#[cfg]`.
+    pub features: Vec<String>,
+    /// Where models, controllers, and TypeScript files are written.
+    pub layout: Layout,
+    /// Nested directories per module kind, or one flat directory.
+    pub out_format: OutFormat,
+    /// JSON key spelling shared by the Rust, TypeScript, and OpenAPI output.
+    pub json_case: JsonCase,
+    /// Names of built-in generators to leave out of the run (see [`Generator::name`]).
+    pub skip_generators: Vec<String>,
+    /// Resources whose own files (model, controller, entity, TS module, ...) are rendered, as
+    /// picked by `via gen --since`; `None` renders all of them. Shared files such as `mod.rs`,
+    /// the manifest, and `openapi.json` always cover the whole program.
+    pub only: Option<BTreeSet<String>>,
+}
+
+/// A codegen target that turns the parsed program into output files.
+///
+/// The built-in Rust, TypeScript, OpenAPI, and Protobuf emitters implement this trait; custom generators
+/// can be run alongside them with [`run_generators`].
+pub trait Generator {
+    /// Stable identifier used to toggle the generator (e.g. `rust`, `typescript`).
+    fn name(&self) -> &'static str;
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>>;
+}
+
+pub fn generate(program: &Program) -> Result<GenerationOutput> {
+    generate_with_options(program, &GenerateOptions::default())
+}
+
+pub fn generate_with_options(
+    program: &Program,
+    options: &GenerateOptions,
+) -> Result<GenerationOutput> {
+    let defaults = Layout::default();
+    if options.out_format == OutFormat::Flat
+        && (options.layout.models_dir != defaults.models_dir
+            || options.layout.controllers_dir != defaults.controllers_dir)
+    {
+        return Err(anyhow!(
+            "the flat output format writes models and controllers directly under `src/`; \
+             remove `models_dir` and `controllers_dir` from via.toml to use it"
+        ));
+    }
+    let mut output = run_generators(program, &default_generators(options))?;
+    if options.gitattributes {
+        let contents = render_gitattributes(&output.files);
+        output.push(GITATTRIBUTES_FILE_NAME, contents);
+        output.generated_by.push(("gitattributes", 1));
+    }
+    Ok(output)
+}
+
+/// Lists the generated paths, sorted and anchored to the output root, so the file only changes
+/// when resources (or enabled generators) are added or removed.
+fn render_gitattributes(files: &[GeneratedFile]) -> String {
+    let paths: BTreeSet<String> = files
+        .iter()
+        .map(|file| file.relative_path.to_string_lossy().replace('\\', "/"))
+        .chain(std::iter::once(IR_FILE_NAME.to_owned()))
+        .collect();
+
+    let mut buffer = String::new();
+    buffer.push_str("# @generated by via-core. DO NOT EDIT BY HAND.\n");
+    for path in paths {
+        writeln!(buffer, "/{} linguist-generated=true", path).unwrap();
+    }
+    buffer
+}
+
+/// The built-in generators enabled by `options`, in output order.
+pub fn default_generators(options: &GenerateOptions) -> Vec<Box<dyn Generator>> {
+    let mut generators: Vec<Box<dyn Generator>> = vec![
+        Box::new(RustGenerator {
+            tracing: options.tracing,
+            errors: options.errors,
+            builders: options.builders,
+            tasks: options.tasks,
+            explicit_entities: options.explicit_entities,
+            seaography: options.seaography,
+            introspection: options.introspection,
+            models_dir: options.layout.models_dir.clone(),
+            controllers_dir: options.layout.controllers_dir.clone(),
+            flat: options.out_format == OutFormat::Flat,
+            json_case: options.json_case,
+            only: options.only.clone(),
+        }),
+        Box::new(TypeScriptGenerator {
+            branded_ids: options.ts_branded_ids,
+            single_file: options.ts_single_file,
+            dir: options.layout.ts_dir.clone(),
+            features: options.features.clone(),
+            factories: options.ts_factories,
+            enum_runtime: options.ts_enum_runtime,
+            flat: options.out_format == OutFormat::Flat,
+            json_case: options.json_case,
+            errors: options.errors,
+            only: options.only.clone(),
+        }),
+    ];
+    if options.openapi {
+        generators.push(Box::new(OpenApiGenerator {
+            features: options.features.clone(),
+            errors: options.errors,
+            json_case: options.json_case,
+        }));
+    }
+    if options.http_file {
+        generators.push(Box::new(HttpFileGenerator {
+            features: options.features.clone(),
+            json_case: options.json_case,
+        }));
+    }
+    if options.proto {
+        generators.push(Box::new(ProtoGenerator {
+            features: options.features.clone(),
+            only: options.only.clone(),
+        }));
+    }
+    if options.migrations {
+        generators.push(Box::new(MigrationGenerator {
+            existing: options.migration_names.clone(),
+            timestamp: options.migration_time,
+            only: options.only.clone(),
+        }));
+    }
+    generators.retain(|generator| {
+        !options
+            .skip_generators
+            .iter()
+            .any(|name| name == generator.name())
+    });
+    generators
+}
+
+/// Whether `resource` is one whose own files are rendered (see [`GenerateOptions::only`]).
+fn renders(only: &Option<BTreeSet<String>>, resource: &Resource) -> bool {
+    only.as_ref()
+        .is_none_or(|names| names.contains(&resource.name))
+}
+
+pub fn run_generators(
+    program: &Program,
+    generators: &[Box<dyn Generator>],
+) -> Result<GenerationOutput> {
     let mut output = GenerationOutput::default();
-    let mut ts_modules: Vec<String> = Vec::new();
+    for generator in generators {
+        let files = generator.generate(program)?;
+        output.generated_by.push((generator.name(), files.len()));
+        output.files.extend(files);
+    }
+    Ok(output)
+}
 
-    for resource in resources {
-        let module_name = resource.name.to_case(Case::Snake);
-        let controller = resource
-            .controller
-            .clone()
-            .unwrap_or_else(default_controller);
-        let param_structs =
-            build_param_structs(&resource.name, &controller, resource.model.as_ref());
+/// Models, controllers, module aggregators, and the crate manifest.
+pub struct RustGenerator {
+    /// Instrument controller actions with `tracing` spans (adds the `tracing` dependency).
+    pub tracing: bool,
+    /// Emit a `<Resource>Error` enum per controller (adds the `axum` dependency).
+    pub errors: bool,
+    /// Emit `<Model>Builder` structs plus the shared `MissingField` error in `models/mod.rs`.
+    pub builders: bool,
+    /// Emit `src/tasks/backfill_<table>.rs` stubs and a `tasks::register` hook.
+    pub tasks: bool,
+    /// Emit expanded sea_orm entities under `src/entities/` (adds the `sea-orm` dependency).
+    pub explicit_entities: bool,
+    /// Give each explicit entity a seaography `RelatedEntity` enum (adds the optional
+    /// `seaography` dependency and feature).
+    pub seaography: bool,
+    /// Emit `src/introspection.rs` with the IR embedded as a `const` (adds the default-on
+    /// `introspection` feature).
+    pub introspection: bool,
+    /// Output-relative directory of the `models` module (see [`Layout`]).
+    pub models_dir: PathBuf,
+    /// Output-relative directory of the `controllers` module.
+    pub controllers_dir: PathBuf,
+    /// Write `src/<resource>_model.rs` and `src/<resource>_controller.rs` with `src/models.rs`
+    /// and `src/controllers.rs` aggregators instead of using the module directories.
+    pub flat: bool,
+    /// Key spelling of the serialized model, params, and page structs.
+    pub json_case: JsonCase,
+    /// Resources whose own files are rendered (see [`GenerateOptions::only`]).
+    pub only: Option<BTreeSet<String>>,
+}
 
-        if let Some(model) = &resource.model {
-            let path = PathBuf::from(format!("src/models/{}.rs", module_name));
-            let contents = render_model(resource, model, &param_structs);
+impl RustGenerator {
+    fn model_path(&self, module: &str) -> PathBuf {
+        if self.flat {
+            PathBuf::from(format!("src/{}_model.rs", module))
+        } else {
+            self.models_dir.join(format!("{}.rs", module))
+        }
+    }
+
+    fn controller_path(&self, module: &str) -> PathBuf {
+        if self.flat {
+            PathBuf::from(format!("src/{}_controller.rs", module))
+        } else {
+            self.controllers_dir.join(format!("{}.rs", module))
+        }
+    }
+
+    fn prelude_path(&self) -> PathBuf {
+        if self.flat {
+            PathBuf::from("src/models_prelude.rs")
+        } else {
+            self.models_dir.join("prelude.rs")
+        }
+    }
+
+    fn enums_path(&self) -> PathBuf {
+        if self.flat {
+            PathBuf::from("src/enums.rs")
+        } else {
+            self.models_dir.join("enums.rs")
+        }
+    }
+
+    /// The file declaring the submodules of `dir` (`models` or `controllers`).
+    fn aggregator_path(&self, name: &str, dir: &Path) -> PathBuf {
+        if self.flat {
+            PathBuf::from(format!("src/{}.rs", name))
+        } else {
+            dir.join("mod.rs")
+        }
+    }
+}
+
+impl Generator for RustGenerator {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let resources = &program.resources;
+        let mut output = GenerationOutput::default();
+        for resource in resources
+            .iter()
+            .filter(|resource| renders(&self.only, resource))
+        {
+            let module_name = resource.name.to_case(Case::Snake);
+            let controller = resource
+                .controller
+                .clone()
+                .unwrap_or_else(default_controller);
+            let param_structs =
+                build_param_structs(&resource.name, &controller, resource.model.as_ref());
+
+            if let Some(model) = &resource.model {
+                let path = self.model_path(&module_name);
+                output.push(
+                    path,
+                    render_model(
+                        resource,
+                        model,
+                        &param_structs,
+                        &program.enums,
+                        self.builders,
+                        self.json_case,
+                    ),
+                );
+            }
+
+            let path = self.controller_path(&module_name);
+            let contents = render_controller(
+                resource,
+                &controller,
+                resource.model.as_ref(),
+                &param_structs,
+                self.tracing,
+                self.errors,
+                self.json_case,
+            );
             output.push(path, contents);
 
-            let ts_path = PathBuf::from(format!("ts/models/{}.ts", module_name));
-            let ts_contents = render_ts_models(resource, model, &param_structs);
+            if let Some(model) = &resource.model
+                && has_views(resource, &controller)
+            {
+                output.push(
+                    PathBuf::from(format!("src/views/{}.rs", module_name)),
+                    render_views(resource, model),
+                );
+                output
+                    .files
+                    .extend(render_view_templates(resource, model, self.json_case));
+            }
+        }
+
+        if !resources.is_empty() {
+            let enums = !program.enums.is_empty();
+            if enums {
+                output.push(
+                    self.enums_path(),
+                    render_rust_enums(&program.enums, self.explicit_entities),
+                );
+            }
+            output.push(
+                self.aggregator_path("models", &self.models_dir),
+                render_models_mod(resources, self.builders, enums, self.flat),
+            );
+            output.push(
+                self.prelude_path(),
+                render_models_prelude(resources, &program.enums),
+            );
+            output.push(
+                self.aggregator_path("controllers", &self.controllers_dir),
+                render_controllers_mod(resources, self.flat),
+            );
+            let encryption = uses_encryption(resources);
+            if encryption {
+                output.push(PathBuf::from("src/encryption.rs"), ENCRYPTION_MODULE);
+            }
+            if self.explicit_entities {
+                output.files.extend(render_entities(
+                    resources,
+                    &program.enums,
+                    self.seaography,
+                    &self.only,
+                ));
+            }
+            if self.introspection {
+                output.push(
+                    PathBuf::from("src/introspection.rs"),
+                    render_introspection(program)?,
+                );
+            }
+            let backfills: Vec<&Resource> = resources
+                .iter()
+                .filter(|resource| self.tasks && !backfill_columns(resource).is_empty())
+                .collect();
+            for resource in backfills
+                .iter()
+                .filter(|resource| renders(&self.only, resource))
+            {
+                output.push(
+                    PathBuf::from(format!("src/tasks/{}.rs", backfill_task_name(resource))),
+                    render_backfill_task(resource),
+                );
+            }
+            if !backfills.is_empty() {
+                output.push(
+                    PathBuf::from("src/tasks/mod.rs"),
+                    render_tasks_mod(&backfills),
+                );
+            }
+            let viewed: Vec<&Resource> = resources
+                .iter()
+                .filter(|resource| {
+                    resource
+                        .controller
+                        .as_ref()
+                        .is_some_and(|controller| has_views(resource, controller))
+                })
+                .collect();
+            if !viewed.is_empty() {
+                output.push(PathBuf::from("src/views/mod.rs"), render_views_mod(&viewed));
+            }
+            output.push(
+                PathBuf::from("src/lib.rs"),
+                render_root_lib(
+                    encryption,
+                    self.explicit_entities,
+                    self.introspection,
+                    !backfills.is_empty(),
+                    !viewed.is_empty(),
+                    &self.models_dir,
+                    &self.controllers_dir,
+                ),
+            );
+            output.push(
+                PathBuf::from("Cargo.toml"),
+                render_manifest(
+                    resources,
+                    self.tracing,
+                    self.errors,
+                    self.explicit_entities,
+                    self.explicit_entities && self.seaography,
+                    self.introspection,
+                ),
+            );
+        }
+        Ok(output.files)
+    }
+}
+
+/// TypeScript interfaces and params types under `ts/`, plus the barrel export.
+pub struct TypeScriptGenerator {
+    pub branded_ids: bool,
+    /// Emit one `ts/schema.ts` (in dependency order) instead of `ts/models/*.ts` + `ts/index.ts`.
+    pub single_file: bool,
+    /// Cargo features whose `@feature` fields are included; other gated fields are left out.
+    pub features: Vec<String>,
+    /// Output-relative directory for the declarations (see [`Layout`]).
+    pub dir: PathBuf,
+    /// Emit `factories/<resource>.ts` with a `make<Resource>(overrides?)` mock builder per model.
+    pub factories: bool,
+    /// Emit enums as `as const` objects with a derived union type (see [`render_ts_enum`]).
+    pub enum_runtime: bool,
+    /// Write `<resource>_model.ts` directly under `dir` instead of `models/<resource>.ts`.
+    pub flat: bool,
+    /// Property names, matching the Rust structs' serde keys.
+    pub json_case: JsonCase,
+    /// Emit the `ApiError` union matching the `<Resource>Error` bodies (`--gen-errors`).
+    pub errors: bool,
+    /// Resources whose own files are rendered (see [`GenerateOptions::only`]).
+    pub only: Option<BTreeSet<String>>,
+}
+
+impl Generator for TypeScriptGenerator {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let resources = &with_active_features(&program.resources, &self.features);
+        let mut output = GenerationOutput::default();
+        let ts_brands = if self.branded_ids {
+            build_ts_id_brands(resources)
+        } else {
+            HashMap::new()
+        };
+
+        if self.factories {
+            for resource in resources
+                .iter()
+                .filter(|resource| renders(&self.only, resource))
+            {
+                let Some(model) = &resource.model else {
+                    continue;
+                };
+                let module_name = resource.name.to_case(Case::Snake);
+                output.push(
+                    self.dir.join(format!("factories/{}.ts", module_name)),
+                    render_ts_factory(
+                        resource,
+                        model,
+                        &ts_brands,
+                        &program.enums,
+                        self.single_file,
+                        self.flat,
+                        self.json_case,
+                    ),
+                );
+            }
+        }
+
+        if self.single_file {
+            if resources.iter().any(|resource| resource.model.is_some())
+                || !program.enums.is_empty()
+            {
+                let mut schema = render_ts_schema(
+                    resources,
+                    &ts_brands,
+                    &program.enums,
+                    self.enum_runtime,
+                    self.json_case,
+                );
+                if self.errors {
+                    schema.push('\n');
+                    schema.push_str(&render_ts_api_error());
+                }
+                output.push(self.dir.join("schema.ts"), schema);
+            }
+            return Ok(output.files);
+        }
+
+        let mut ts_modules: Vec<String> = Vec::new();
+        for resource in resources {
+            let Some(model) = &resource.model else {
+                continue;
+            };
+            let module_name = resource.name.to_case(Case::Snake);
+            let ts_module = ts_model_module(&module_name, self.flat);
+            if !renders(&self.only, resource) {
+                ts_modules.push(ts_module);
+                continue;
+            }
+            let controller = resource
+                .controller
+                .clone()
+                .unwrap_or_else(default_controller);
+            let param_structs = build_param_structs(&resource.name, &controller, Some(model));
+
+            let ts_path = self.dir.join(format!("{}.ts", ts_module));
+            let ts_contents = render_ts_models(
+                resource,
+                model,
+                &param_structs,
+                &ts_brands,
+                &program.enums,
+                self.flat,
+                self.json_case,
+            );
             output.push(ts_path, ts_contents);
-            ts_modules.push(module_name.clone());
+            ts_modules.push(ts_module);
         }
 
-        let path = PathBuf::from(format!("src/controllers/{}.rs", module_name));
-        let contents = render_controller(
-            resource,
-            &controller,
-            resource.model.as_ref(),
-            &param_structs,
-        );
-        output.push(path, contents);
+        let has_enums = !program.enums.is_empty();
+        if has_enums {
+            output.push(
+                self.dir.join("enums.ts"),
+                render_ts_enums(&program.enums, self.enum_runtime),
+            );
+        }
+        let has_errors = self.errors && !ts_modules.is_empty();
+        if has_errors {
+            let mut errors = String::from("// @generated by via-core. DO NOT EDIT BY HAND.\n\n");
+            errors.push_str(&render_ts_api_error());
+            output.push(self.dir.join("errors.ts"), errors);
+        }
+        if !ts_modules.is_empty() || has_enums {
+            output.push(
+                self.dir.join("index.ts"),
+                render_ts_index(&ts_modules, has_enums, has_errors),
+            );
+        }
+        Ok(output.files)
     }
+}
 
-    if !resources.is_empty() {
-        output.push(
-            PathBuf::from("src/models/mod.rs"),
-            render_models_mod(resources),
-        );
-        output.push(
-            PathBuf::from("src/controllers/mod.rs"),
-            render_controllers_mod(resources),
-        );
-        output.push(PathBuf::from("src/lib.rs"), render_root_lib());
-        output.push(PathBuf::from("Cargo.toml"), render_manifest());
+/// `openapi.json` describing the generated CRUD routes.
+pub struct OpenApiGenerator {
+    /// Cargo features whose `@feature` fields are included; other gated fields are left out.
+    pub features: Vec<String>,
+    /// Describe the codes and 500 response of the `<Resource>Error` enums (`--gen-errors`).
+    pub errors: bool,
+    /// Property names, matching the Rust structs' serde keys.
+    pub json_case: JsonCase,
+}
+
+impl Generator for OpenApiGenerator {
+    fn name(&self) -> &'static str {
+        "openapi"
     }
 
-    if !ts_modules.is_empty() {
-        output.push(PathBuf::from("ts/index.ts"), render_ts_index(&ts_modules));
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let resources = &with_active_features(&program.resources, &self.features);
+        let mut output = GenerationOutput::default();
+        if !resources.is_empty() {
+            output.push(
+                PathBuf::from("openapi.json"),
+                render_openapi(resources, self.errors, self.json_case)?,
+            );
+        }
+        Ok(output.files)
     }
+}
 
-    Ok(output)
+/// `requests.http`: one request per generated route, with sample JSON bodies for the writes.
+pub struct HttpFileGenerator {
+    /// Cargo features whose `@feature` fields appear in the sample bodies, as in OpenAPI.
+    pub features: Vec<String>,
+    /// Body keys, matching the Rust structs' serde keys.
+    pub json_case: JsonCase,
+}
+
+impl Generator for HttpFileGenerator {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let resources = &with_active_features(&program.resources, &self.features);
+        let mut output = GenerationOutput::default();
+        if !resources.is_empty() {
+            output.push(
+                PathBuf::from(HTTP_FILE_NAME),
+                render_http_file(resources, &program.enums, self.json_case),
+            );
+        }
+        Ok(output.files)
+    }
+}
+
+/// `proto/<resource>.proto` with a message per model and params profile, plus a CRUD service.
+pub struct ProtoGenerator {
+    /// Cargo features whose `@feature` fields get a number; other gated fields are `reserved`
+    /// so toggling a feature never renumbers the rest.
+    pub features: Vec<String>,
+    /// Resources whose own files are rendered (see [`GenerateOptions::only`]).
+    pub only: Option<BTreeSet<String>>,
+}
+
+impl Generator for ProtoGenerator {
+    fn name(&self) -> &'static str {
+        "proto"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let resources = &program.resources;
+        let mut output = GenerationOutput::default();
+        let resource_names: Vec<&str> = resources.iter().map(|r| r.name.as_str()).collect();
+        for resource in resources
+            .iter()
+            .filter(|resource| renders(&self.only, resource))
+        {
+            let Some(model) = &resource.model else {
+                continue;
+            };
+            let module_name = resource.name.to_case(Case::Snake);
+            let path = PathBuf::from(format!("proto/{}.proto", module_name));
+            output.push(
+                path,
+                render_proto(resource, model, &resource_names, &self.features),
+            );
+        }
+        Ok(output.files)
+    }
+}
+
+/// `migration/src/m<stamp>_<table>.rs` per model: a loco `create_table` migration for its
+/// stored fields, with foreign keys for its `<resource>_id` keys. Registering the modules in
+/// `migration/src/lib.rs` is left to [`writer::register_migrations`], since that file is the
+/// user's.
+pub struct MigrationGenerator {
+    /// Module names of the migrations already on disk, by table, so a table keeps the
+    /// timestamp it was first generated with (see [`writer::existing_migrations`]).
+    pub existing: BTreeMap<String, String>,
+    /// Unix time stamped on the migrations of tables that don't have one yet.
+    pub timestamp: u64,
+    /// Resources whose own files are rendered (see [`GenerateOptions::only`]).
+    pub only: Option<BTreeSet<String>>,
+}
+
+impl Generator for MigrationGenerator {
+    fn name(&self) -> &'static str {
+        "migrations"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let relations = graph::relations(&program.resources);
+        let stamp = migration_stamp(self.timestamp);
+        let mut output = GenerationOutput::default();
+        for resource in migration_order(&program.resources, &relations)
+            .into_iter()
+            .filter(|resource| renders(&self.only, resource))
+        {
+            let table = resource.table_name();
+            // loco's `create_table` pluralizes the name it is given; a table name that isn't
+            // already in that form would be created under another one.
+            let normalized = cruet::to_plural(&table).to_case(Case::Snake);
+            if normalized != table {
+                return Err(anyhow!(
+                    "{}: loco's create_table would name the table of {} `{}` instead of `{}`; \
+                     set `table \"{}\"` or skip the migrations generator",
+                    resource.file_path,
+                    resource.name,
+                    normalized,
+                    table,
+                    normalized
+                ));
+            }
+            let module = self
+                .existing
+                .get(&table)
+                .cloned()
+                .unwrap_or_else(|| format!("m{}_{}", stamp, table));
+            output.push(
+                format!("{}/src/{}.rs", MIGRATION_DIR, module),
+                render_migration(resource, &relations, &program.resources, &program.enums),
+            );
+        }
+        Ok(output.files)
+    }
+}
+
+/// Copies of `resources` without the `@feature` fields whose feature isn't in `features`, and
+/// without the params entries naming them.
+fn with_active_features(resources: &[Resource], features: &[String]) -> Vec<Resource> {
+    let mut resources = resources.to_vec();
+    for resource in &mut resources {
+        let Some(model) = &mut resource.model else {
+            continue;
+        };
+        let inactive: Vec<String> = model
+            .fields
+            .iter()
+            .filter(|field| !feature_active(field, features))
+            .map(|field| field.name.clone())
+            .collect();
+        if inactive.is_empty() {
+            continue;
+        }
+        model.fields.retain(|field| !inactive.contains(&field.name));
+        if let Some(controller) = &mut resource.controller {
+            for profile in &mut controller.params {
+                profile
+                    .entries
+                    .retain(|entry| !inactive.contains(&entry.name));
+            }
+        }
+    }
+    resources
+}
+
+fn feature_active(field: &Field, features: &[String]) -> bool {
+    field
+        .attributes
+        .feature
+        .as_ref()
+        .is_none_or(|feature| features.contains(feature))
+}
+
+/// `#[cfg(feature = "...")]` for a `@feature` field, placed on everything generated for it.
+fn feature_cfg(field: &Field) -> Option<String> {
+    field
+        .attributes
+        .feature
+        .as_ref()
+        .map(|feature| format!("#[cfg(feature = {:?})]", feature))
+}
+
+fn deprecated_attr(note: Option<&String>) -> Option<String> {
+    note.map(|note| format!("#[deprecated(note = {:?})]", note))
+}
+
+/// `@deprecated` notes as a one-line JSDoc tag, which editors use to strike through usages.
+fn ts_deprecated_doc(note: Option<&String>) -> Option<String> {
+    note.map(|note| format!("/** @deprecated {} */", note.replace("*/", "*\\/")))
 }
 
-fn default_controller() -> Controller {
+pub(crate) fn default_controller() -> Controller {
     Controller {
         params: Vec::new(),
         respond_with: vec!["html".into(), "json".into()],
         actions: ControllerActions::AutoCrud,
+        authorize: None,
+        action_overrides: Vec::new(),
     }
 }
 
-fn render_model(resource: &Resource, model: &Model, param_structs: &[ParamStruct]) -> String {
+fn render_model(
+    resource: &Resource,
+    model: &Model,
+    param_structs: &[ParamStruct],
+    enums: &[EnumDecl],
+    builders: bool,
+    json_case: JsonCase,
+) -> String {
     let mut buffer = String::new();
     writeln!(
         buffer,
@@ -98,649 +924,5328 @@ fn render_model(resource: &Resource, model: &Model, param_structs: &[ParamStruct
     )
     .unwrap();
     buffer.push('\n');
-    buffer.push_str("use serde::{Deserialize, Serialize};\n\n");
+    // Generated impls below still touch deprecated items; warnings belong to callers.
+    if resource.deprecated.is_some()
+        || model
+            .fields
+            .iter()
+            .any(|field| field.attributes.deprecated.is_some())
+    {
+        buffer.push_str("#![allow(deprecated)]\n\n");
+    }
+    buffer.push_str("use serde::{Deserialize, Serialize};\n");
+    let validated_fields: Vec<&Field> = model
+        .fields
+        .iter()
+        .filter(|field| field.attributes.allowed.is_some() || has_format_fn(field))
+        .collect();
+    let model_checks = required_when_checks(resource, model, |name| {
+        model
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.optional)
+    });
+    if !validated_fields.is_empty() || !model_checks.is_empty() {
+        buffer.push_str("use validator::{Validate, ValidationError};\n");
+    } else if model
+        .fields
+        .iter()
+        .any(|field| field.attributes.validated())
+    {
+        buffer.push_str("use validator::Validate;\n");
+    }
+    let mut used_enums: Vec<&str> = model
+        .fields
+        .iter()
+        .map(|field| field.ty.name.as_str())
+        .filter(|ty| enums.iter().any(|decl| decl.name == *ty))
+        .collect();
+    used_enums.sort();
+    used_enums.dedup();
+    let encrypted = model.fields.iter().any(|field| field.attributes.encrypted);
+    if builders || encrypted || !used_enums.is_empty() {
+        buffer.push('\n');
+    }
+    match used_enums.as_slice() {
+        [] => {}
+        [single] => writeln!(buffer, "use super::enums::{};", single).unwrap(),
+        many => writeln!(buffer, "use super::enums::{{{}}};", many.join(", ")).unwrap(),
+    }
+    if encrypted {
+        buffer.push_str("use crate::encryption::{EncryptionError, EncryptionKey};\n");
+    }
+    if builders {
+        buffer.push_str("use super::MissingField;\n");
+    }
+    buffer.push('\n');
 
     let struct_name = &resource.name;
-    writeln!(buffer, "#[derive(Debug, Clone, Serialize, Deserialize)]").unwrap();
+    if let Some(attr) = deprecated_attr(resource.deprecated.as_ref()) {
+        writeln!(buffer, "{}", attr).unwrap();
+    }
+    let mut derives = struct_derives(model.fields.iter(), !model_checks.is_empty());
+    for derive in &resource.derives {
+        write!(derives, ", {}", derive).unwrap();
+    }
+    writeln!(buffer, "#[derive({})]", derives).unwrap();
+    if let Some(attr) = json_case.serde_attr() {
+        writeln!(buffer, "{}", attr).unwrap();
+    }
+    let mut cross_field_validators = Vec::new();
+    if !model_checks.is_empty() {
+        writeln!(buffer, "{}", required_when_attr(struct_name)).unwrap();
+        cross_field_validators.push(render_required_when_validator(struct_name, &model_checks));
+    }
     writeln!(buffer, "pub struct {} {{", struct_name).unwrap();
 
     for field in &model.fields {
         for line in field_doc_lines(field) {
             writeln!(buffer, "    /// {}", line).unwrap();
         }
+        if let Some(cfg) = feature_cfg(field) {
+            writeln!(buffer, "    {}", cfg).unwrap();
+        }
+        if let Some(attr) = deprecated_attr(field.attributes.deprecated.as_ref()) {
+            writeln!(buffer, "    {}", attr).unwrap();
+        }
+        for line in validate_attr_lines(&resource.name, field) {
+            writeln!(buffer, "    {}", line).unwrap();
+        }
         if field.optional {
             buffer.push_str("    #[serde(skip_serializing_if = \"Option::is_none\", default)]\n");
         }
         if field.attributes.serialize == Some(false) {
             buffer.push_str("    #[serde(skip_serializing, skip_deserializing)]\n");
         }
+        if field.attributes.default.is_some() {
+            writeln!(
+                buffer,
+                "    #[serde(default = \"{}\")]",
+                default_fn_path(struct_name, field)
+            )
+            .unwrap();
+        }
         let (ty, _comment) = render_field_type(field, field.optional);
         writeln!(buffer, "    pub {}: {},", field.name, ty).unwrap();
     }
     buffer.push_str("}\n");
 
+    buffer.push('\n');
+    if model.fields.iter().any(is_sensitive) {
+        let fields = model
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), Some(field)));
+        buffer.push_str(&render_redacted_debug(struct_name, fields));
+        buffer.push('\n');
+    }
+    buffer.push_str(&render_polymorphic_enums(struct_name, model));
+    buffer.push_str(&render_model_impl(
+        struct_name,
+        &resource.table_name(),
+        model,
+    ));
+
+    if builders {
+        buffer.push('\n');
+        buffer.push_str(&render_model_builder(struct_name, model));
+    }
+
     if !param_structs.is_empty() {
         buffer.push('\n');
     }
 
     for param_struct in param_structs {
+        let checks = param_struct_checks(resource, model, param_struct);
         writeln!(
             buffer,
-            "#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{",
-            param_struct.name
+            "#[derive({})]",
+            struct_derives(
+                param_struct.fields.iter().filter_map(|f| f.source.as_ref()),
+                !checks.is_empty()
+            )
         )
         .unwrap();
+        if let Some(attr) = json_case.serde_attr() {
+            writeln!(buffer, "{}", attr).unwrap();
+        }
+        if !checks.is_empty() {
+            writeln!(buffer, "{}", required_when_attr(&param_struct.name)).unwrap();
+            cross_field_validators
+                .push(render_required_when_validator(&param_struct.name, &checks));
+        }
+        writeln!(buffer, "pub struct {} {{", param_struct.name).unwrap();
         for field in &param_struct.fields {
             for line in &field.doc {
                 writeln!(buffer, "    /// {}", line).unwrap();
             }
+            if let Some(source) = &field.source {
+                if let Some(cfg) = feature_cfg(source) {
+                    writeln!(buffer, "    {}", cfg).unwrap();
+                }
+                if let Some(attr) = deprecated_attr(source.attributes.deprecated.as_ref()) {
+                    writeln!(buffer, "    {}", attr).unwrap();
+                }
+                for line in validate_attr_lines(&resource.name, source) {
+                    writeln!(buffer, "    {}", line).unwrap();
+                }
+            }
             if field.optional {
                 buffer
                     .push_str("    #[serde(skip_serializing_if = \"Option::is_none\", default)]\n");
+            } else if let Some(source) = field.defaulted_source() {
+                writeln!(
+                    buffer,
+                    "    #[serde(default = \"{}\")]",
+                    default_fn_path(struct_name, source)
+                )
+                .unwrap();
             }
             writeln!(buffer, "    pub {}: {},", field.name, field.ty).unwrap();
         }
         buffer.push_str("}\n\n");
+        if param_struct
+            .fields
+            .iter()
+            .any(|field| field.source.as_ref().is_some_and(is_sensitive))
+        {
+            let fields = param_struct
+                .fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.source.as_ref()));
+            buffer.push_str(&render_redacted_debug(&param_struct.name, fields));
+            buffer.push('\n');
+        }
+        if is_patch_params(resource, param_struct) {
+            buffer.push_str(&render_patch_apply(&resource.name, param_struct));
+        }
+    }
+
+    for field in validated_fields {
+        buffer.push_str(&render_allowed_values_validator(&resource.name, field));
+        buffer.push_str(&render_format_validator(&resource.name, field));
+    }
+    for validator in cross_field_validators {
+        buffer.push_str(&validator);
     }
 
     buffer.trim_end().to_owned() + "\n"
 }
 
-fn render_controller(
-    resource: &Resource,
-    controller: &Controller,
-    model: Option<&Model>,
-    param_structs: &[ParamStruct],
-) -> String {
+/// Merge step for the PATCH handler: only fields present in the body overwrite the model.
+fn render_patch_apply(struct_name: &str, param_struct: &ParamStruct) -> String {
+    let mut body = String::new();
+    for field in &param_struct.fields {
+        let Some(source) = &field.source else {
+            writeln!(
+                body,
+                "        // `{}` has no model field to apply to",
+                field.name
+            )
+            .unwrap();
+            continue;
+        };
+        let value = if source.optional {
+            "Some(value)"
+        } else {
+            "value"
+        };
+        if let Some(cfg) = feature_cfg(source) {
+            writeln!(body, "        {}", cfg).unwrap();
+        }
+        writeln!(
+            body,
+            "        if let Some(value) = self.{} {{\n            model.{} = {};\n        }}",
+            field.name, source.name, value
+        )
+        .unwrap();
+    }
+    let model_arg = if param_struct.fields.iter().any(|f| f.source.is_some()) {
+        "model"
+    } else {
+        "_model"
+    };
+
     let mut buffer = String::new();
+    writeln!(buffer, "impl {} {{", param_struct.name).unwrap();
+    buffer.push_str(
+        "    /// Applies the fields present in a PATCH body to `model`; absent fields keep their value.\n",
+    );
     writeln!(
         buffer,
-        "{}// source: {}",
-        GENERATED_HEADER, resource.file_path
+        "    pub fn apply_to(self, {}: &mut {}) {{",
+        model_arg, struct_name
     )
     .unwrap();
-    buffer.push('\n');
-    buffer.push_str("use loco_rs::prelude::*;\n");
-    buffer.push_str("use serde_json::json;\n");
+    buffer.push_str(&body);
+    buffer.push_str("    }\n}\n\n");
+    buffer
+}
 
-    if !param_structs.is_empty() {
-        let mut names: Vec<String> = param_structs.iter().map(|ps| ps.name.clone()).collect();
-        names.sort();
-        names.dedup();
-        writeln!(buffer, "use crate::models::{{{}}};", names.join(", ")).unwrap();
+/// `src/entities/<resource>.rs` per model in sea-orm-cli's expanded format, plus `mod.rs` and a
+/// `prelude.rs` aliasing each `Entity` to its resource name.
+fn render_entities(
+    resources: &[Resource],
+    enums: &[EnumDecl],
+    seaography: bool,
+    only: &Option<BTreeSet<String>>,
+) -> Vec<GeneratedFile> {
+    let mut output = GenerationOutput::default();
+    let relations = graph::relations(resources);
+    let resource_names: Vec<&str> = resources.iter().map(|r| r.name.as_str()).collect();
+    let mut modules = Vec::new();
+    for resource in resources {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        let module_name = resource.name.to_case(Case::Snake);
+        modules.push((module_name.clone(), resource.name.clone()));
+        if !renders(only, resource) {
+            continue;
+        }
+        let own_relations: Vec<&Relation> = relations
+            .iter()
+            .filter(|relation| {
+                relation.from == resource.name
+                    && matches!(
+                        relation.kind,
+                        RelationKind::BelongsTo | RelationKind::HasMany
+                    )
+            })
+            .collect();
+        output.push(
+            format!("src/entities/{}.rs", module_name),
+            render_entity(
+                resource,
+                model,
+                &own_relations,
+                &resource_names,
+                enums,
+                seaography,
+            ),
+        );
     }
 
-    buffer.push('\n');
-
-    if !controller.respond_with.is_empty() {
-        let formats = controller
-            .respond_with
-            .iter()
-            .map(|f| format!("\"{}\"", f))
-            .collect::<Vec<_>>()
-            .join(", ");
+    let mut mod_rs = String::from(GENERATED_HEADER);
+    mod_rs.push('\n');
+    mod_rs.push_str("pub mod prelude;\n\n");
+    let mut prelude = String::from(GENERATED_HEADER);
+    prelude.push('\n');
+    for (module_name, resource_name) in &modules {
+        writeln!(mod_rs, "pub mod {};", module_name).unwrap();
         writeln!(
-            buffer,
-            "pub const RESPOND_WITH: &[&str] = &[{}];\n",
-            formats
+            prelude,
+            "pub use super::{}::Entity as {};",
+            module_name, resource_name
         )
         .unwrap();
-    } else {
-        buffer.push_str("pub const RESPOND_WITH: &[&str] = &[\"html\", \"json\"];\n\n");
     }
+    output.push("src/entities/mod.rs", mod_rs);
+    output.push("src/entities/prelude.rs", prelude);
+    output.files
+}
 
-    let routes_fn = render_routes_fn(&resource.name, controller);
-    buffer.push_str(&routes_fn);
-    buffer.push('\n');
+/// One column of an explicit entity. `field` is `None` for the implicit `id` key.
+struct EntityColumn<'a> {
+    field: Option<&'a Field>,
+    name: String,
+    rust_ty: String,
+    column_ty: String,
+    comment: Option<String>,
+}
 
-    let actions = resolve_actions(controller);
-    for action in actions {
-        buffer.push_str(&render_action_stub(resource, &action, &param_structs));
+/// Computed fields aren't stored, so they get no column. Enum fields store their wire string
+/// through the enum's `ActiveEnum` impl. Transient fields are mapped the same way; the caller
+/// keeps them out of `Column` and marks them `#[sea_orm(ignore)]` on the model. Encrypted fields
+/// hold base64 ciphertext, so they're `Text` whatever their declared string type.
+fn entity_column<'a>(field: &'a Field, enums: &[EnumDecl]) -> Option<EntityColumn<'a>> {
+    if field.attributes.computed.is_some() {
+        return None;
     }
-
-    if let Some(model) = model {
-        buffer.push_str("// Associated model fields\n");
-        for field in &model.fields {
-            let (ty, comment) = render_field_type(field, field.optional);
-            if let Some(comment) = comment {
-                writeln!(buffer, "// - {}: {} ({})", field.name, ty, comment).unwrap();
-            } else {
-                writeln!(buffer, "// - {}: {}", field.name, ty).unwrap();
-            }
-        }
+    if field.attributes.encrypted {
+        return Some(EntityColumn {
+            field: Some(field),
+            name: field.name.clone(),
+            rust_ty: "String".to_owned(),
+            column_ty: "ColumnType::Text".to_owned(),
+            comment: None,
+        });
     }
-
-    buffer.trim_end().to_owned() + "\n"
+    if enums.iter().any(|decl| decl.name == field.ty.name) {
+        return Some(EntityColumn {
+            field: Some(field),
+            name: field.name.clone(),
+            rust_ty: format!("crate::models::{}", field.ty.name),
+            column_ty: "ColumnType::String(StringLen::None)".to_owned(),
+            comment: None,
+        });
+    }
+    let (rust_ty, column_ty, comment) = match ViaScalar::from_name(&field.ty.name) {
+        Some(ViaScalar::String) => ("String", "ColumnType::String(StringLen::None)", None),
+        Some(ViaScalar::Text) => ("String", "ColumnType::Text", None),
+        Some(ViaScalar::Boolean) => ("bool", "ColumnType::Boolean", None),
+        Some(ViaScalar::Integer) => ("i32", "ColumnType::Integer", None),
+        Some(ViaScalar::BigInt) => ("i64", "ColumnType::BigInteger", None),
+        Some(ViaScalar::Float) => ("f64", "ColumnType::Double", None),
+        Some(ViaScalar::Decimal) => ("Decimal", "ColumnType::Decimal(None)", None),
+        Some(ViaScalar::DateTime) => (
+            "DateTimeWithTimeZone",
+            "ColumnType::TimestampWithTimeZone",
+            None,
+        ),
+        Some(ViaScalar::Date) => ("Date", "ColumnType::Date", None),
+        Some(ViaScalar::Time) => ("Time", "ColumnType::Time", None),
+        Some(ViaScalar::Uuid) => ("Uuid", "ColumnType::Uuid", None),
+        Some(ViaScalar::Json) => ("Json", "ColumnType::JsonBinary", None),
+        None => (
+            "String",
+            "ColumnType::String(StringLen::None)",
+            Some(format!(
+                "TODO: map Via type `{}` to a column type",
+                field.ty.name
+            )),
+        ),
+    };
+    Some(EntityColumn {
+        field: Some(field),
+        name: field.name.clone(),
+        rust_ty: rust_ty.to_owned(),
+        column_ty: column_ty.to_owned(),
+        comment,
+    })
 }
 
-struct ActionSpec {
-    pub action_name: String,
-    pub handler_name: String,
-    pub method: &'static str,
-    pub path: &'static str,
+/// The SQL DEFAULT of a column, as an argument to sea_orm's `ColumnDef::default`.
+fn column_default_expr(default: &Literal) -> String {
+    match default {
+        Literal::Now => "Expr::current_timestamp()".to_owned(),
+        other => other.to_string(),
+    }
 }
 
-fn resolve_actions(controller: &Controller) -> Vec<ActionSpec> {
-    match &controller.actions {
-        ControllerActions::Manual(custom) if !custom.is_empty() => custom
-            .iter()
-            .map(|action| ActionSpec {
-                action_name: action.name.clone(),
-                handler_name: action.name.to_case(Case::Snake),
-                method: "get",
-                path: "/",
-            })
-            .collect(),
-        _ => vec![
-            ActionSpec {
-                action_name: "index".into(),
-                handler_name: "index".into(),
-                method: "get",
-                path: "/",
-            },
-            ActionSpec {
-                action_name: "show".into(),
-                handler_name: "show".into(),
-                method: "get",
-                path: "/{id}",
-            },
-            ActionSpec {
-                action_name: "create".into(),
-                handler_name: "create".into(),
-                method: "post",
-                path: "/",
-            },
-            ActionSpec {
-                action_name: "update".into(),
-                handler_name: "update".into(),
-                method: "put",
-                path: "/{id}",
-            },
-            ActionSpec {
-                action_name: "destroy".into(),
-                handler_name: "destroy".into(),
-                method: "delete",
-                path: "/{id}",
+fn render_entity(
+    resource: &Resource,
+    model: &Model,
+    relations: &[&Relation],
+    resource_names: &[&str],
+    enums: &[EnumDecl],
+    seaography: bool,
+) -> String {
+    let (transient, mut columns): (Vec<EntityColumn>, Vec<EntityColumn>) = model
+        .fields
+        .iter()
+        .filter(|field| !resource_names.contains(&field.ty.name.as_str()))
+        .filter_map(|field| entity_column(field, enums))
+        .partition(|column| column.field.is_some_and(|field| field.attributes.transient));
+    // Models without an explicit `id` get loco's default auto-increment integer key.
+    if !columns.iter().any(|column| column.name == "id") {
+        columns.insert(
+            0,
+            EntityColumn {
+                field: None,
+                name: "id".to_owned(),
+                rust_ty: "i32".to_owned(),
+                column_ty: "ColumnType::Integer".to_owned(),
+                comment: None,
             },
-        ],
+        );
     }
-}
+    let id = columns
+        .iter()
+        .find(|column| column.name == "id")
+        .expect("id column was just ensured");
+    let auto_increment = matches!(id.rust_ty.as_str(), "i32" | "i64");
+    let id_ty = id.rust_ty.clone();
+    let eq = if columns
+        .iter()
+        .chain(&transient)
+        .any(|column| column.rust_ty == "f64")
+    {
+        ""
+    } else {
+        ", Eq"
+    };
+    let cfg_line = |column: &EntityColumn, indent: &str| {
+        column
+            .field
+            .and_then(feature_cfg)
+            .map(|cfg| format!("{}{}\n", indent, cfg))
+            .unwrap_or_default()
+    };
 
-fn render_routes_fn(resource_name: &str, controller: &Controller) -> String {
     let mut buffer = String::new();
-    let plural = pluralize(resource_name);
-    let prefix = format!("{}/", plural.to_case(Case::Snake));
-
-    buffer.push_str("pub fn routes() -> Routes {\n");
-    buffer.push_str("    Routes::new()\n");
-    buffer.push_str(&format!("        .prefix(\"{}\")\n", prefix));
-
-    for action in resolve_actions(controller) {
-        buffer.push_str(&format!(
-            "        .add(\"{}\", {}({}))\n",
-            action.path, action.method, action.handler_name
-        ));
+    writeln!(
+        buffer,
+        "{}// source: {}",
+        GENERATED_HEADER, resource.file_path
+    )
+    .unwrap();
+    buffer.push('\n');
+    buffer.push_str("use sea_orm::entity::prelude::*;\n");
+    if model.fields.iter().any(|field| field.attributes.encrypted) {
+        buffer.push_str("use sea_orm::ActiveValue;\n\n");
+        buffer.push_str("use crate::encryption::{EncryptionError, EncryptionKey};\n");
     }
+    buffer.push('\n');
 
-    buffer.push_str("}\n");
-    buffer
-}
-
-fn render_field_type(field: &Field, optional: bool) -> (String, Option<String>) {
-    let (base, comment) = map_builtin(&field.ty.name);
-    let ty = if optional {
-        format!("Option<{}>", base)
-    } else {
-        base.clone()
-    };
-    (ty, comment)
-}
+    buffer.push_str("#[derive(Copy, Clone, Default, Debug, DeriveEntity)]\npub struct Entity;\n\n");
+    writeln!(
+        buffer,
+        "impl EntityName for Entity {{\n    fn table_name(&self) -> &str {{\n        {:?}\n    }}\n}}\n",
+        resource.table_name()
+    )
+    .unwrap();
 
-fn field_doc_lines(field: &Field) -> Vec<String> {
-    let mut docs = Vec::new();
-    if let Some(false) = field.attributes.serialize {
-        docs.push("Not serialized in API responses".into());
+    let redacted = columns
+        .iter()
+        .chain(&transient)
+        .any(|column| column.field.is_some_and(is_sensitive));
+    writeln!(
+        buffer,
+        "#[derive(Clone, {}PartialEq, DeriveModel, DeriveActiveModel{})]",
+        if redacted { "" } else { "Debug, " },
+        eq
+    )
+    .unwrap();
+    buffer.push_str("pub struct Model {\n");
+    for column in columns.iter().chain(&transient) {
+        if let Some(comment) = &column.comment {
+            writeln!(buffer, "    /// {}", comment).unwrap();
+        }
+        buffer.push_str(&cfg_line(column, "    "));
+        if column.field.is_some_and(|field| field.attributes.transient) {
+            buffer.push_str("    #[sea_orm(ignore)]\n");
+        }
+        let optional = column.field.is_some_and(|field| field.optional);
+        let ty = if optional {
+            format!("Option<{}>", column.rust_ty)
+        } else {
+            column.rust_ty.clone()
+        };
+        writeln!(buffer, "    pub {}: {},", column.name, ty).unwrap();
+    }
+    buffer.push_str("}\n\n");
+    if redacted {
+        let fields = columns
+            .iter()
+            .chain(&transient)
+            .map(|column| (column.name.as_str(), column.field));
+        buffer.push_str(&render_redacted_debug("Model", fields));
+        buffer.push('\n');
     }
-    docs
-}
 
-#[derive(Debug)]
-struct ParamStruct {
-    name: String,
-    fields: Vec<ParamField>,
-}
+    buffer.push_str("#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]\npub enum Column {\n");
+    for column in &columns {
+        buffer.push_str(&cfg_line(column, "    "));
+        writeln!(buffer, "    {},", column.name.to_case(Case::Pascal)).unwrap();
+    }
+    buffer.push_str("}\n\n");
 
-#[derive(Debug)]
-struct ParamField {
-    name: String,
-    ty: String,
-    optional: bool,
-    doc: Vec<String>,
-}
+    buffer.push_str(
+        "#[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]\npub enum PrimaryKey {\n    Id,\n}\n\n",
+    );
+    writeln!(
+        buffer,
+        "impl PrimaryKeyTrait for PrimaryKey {{\n    type ValueType = {};\n\n    fn auto_increment() -> bool {{\n        {}\n    }}\n}}\n",
+        id_ty, auto_increment
+    )
+    .unwrap();
 
-fn build_param_structs(
-    resource_struct_name: &str,
-    controller: &Controller,
-    model: Option<&Model>,
-) -> Vec<ParamStruct> {
-    let mut structs = Vec::new();
-    if controller.params.is_empty() {
-        return structs;
+    // A `Related<T>` impl can only exist once per target, so later relations to the same
+    // resource are left out.
+    let mut seen = BTreeSet::new();
+    let relations: Vec<&&Relation> = relations
+        .iter()
+        .filter(|relation| seen.insert(relation.to.clone()))
+        .collect();
+    buffer.push_str("#[derive(Copy, Clone, Debug, EnumIter)]\npub enum Relation {\n");
+    for relation in &relations {
+        writeln!(buffer, "    {},", relation.to).unwrap();
     }
+    buffer.push_str("}\n\n");
 
-    let field_map = model.map(|m| build_field_index(m));
+    buffer.push_str("impl ColumnTrait for Column {\n    type EntityName = Entity;\n\n");
+    buffer.push_str("    fn def(&self) -> ColumnDef {\n        match self {\n");
+    for column in &columns {
+        let nullable = if column.field.is_some_and(|field| field.optional) {
+            ".null()"
+        } else {
+            ""
+        };
+        let default = column
+            .field
+            .and_then(|field| field.attributes.default.as_ref())
+            .map(|default| format!(".default({})", column_default_expr(default)))
+            .unwrap_or_default();
+        buffer.push_str(&cfg_line(column, "            "));
+        writeln!(
+            buffer,
+            "            Self::{} => {}.def(){}{},",
+            column.name.to_case(Case::Pascal),
+            column.column_ty,
+            nullable,
+            default
+        )
+        .unwrap();
+    }
+    buffer.push_str("        }\n    }\n}\n\n");
 
-    for profile in &controller.params {
-        match &profile.name {
-            ParamsKind::Editable => {
-                let create_name = format!("{}CreateParams", resource_struct_name);
-                let update_name = format!("{}UpdateParams", resource_struct_name);
-                let create_fields =
-                    build_param_fields(&profile.entries, &field_map, ParamUsage::Create);
-                let update_fields =
-                    build_param_fields(&profile.entries, &field_map, ParamUsage::Update);
-                structs.push(ParamStruct {
-                    name: create_name,
-                    fields: create_fields,
-                });
-                structs.push(ParamStruct {
-                    name: update_name,
-                    fields: update_fields,
-                });
-            }
-            ParamsKind::Named(name) => {
-                let struct_name = format!(
-                    "{}{}Params",
-                    resource_struct_name,
-                    name.to_case(Case::Pascal)
-                );
-                let fields = build_param_fields(&profile.entries, &field_map, ParamUsage::Create);
-                structs.push(ParamStruct {
-                    name: struct_name,
-                    fields,
-                });
-            }
+    buffer.push_str("impl RelationTrait for Relation {\n    fn def(&self) -> RelationDef {\n");
+    if relations.is_empty() {
+        buffer.push_str("        panic!(\"No RelationDef\")\n");
+    } else {
+        buffer.push_str("        match self {\n");
+        for relation in &relations {
+            let target = relation.to.to_case(Case::Snake);
+            let def = match relation.kind {
+                RelationKind::HasMany => {
+                    format!("Entity::has_many(super::{}::Entity).into()", target)
+                }
+                _ => format!(
+                    "Entity::belongs_to(super::{}::Entity)\n                .from(Column::{})\n                .to(super::{}::Column::Id)\n                .on_delete(ForeignKeyAction::{})\n                .into()",
+                    target,
+                    relation.field.to_case(Case::Pascal),
+                    target,
+                    foreign_key_action(model, &relation.field)
+                ),
+            };
+            writeln!(buffer, "            Self::{} => {},", relation.to, def).unwrap();
         }
+        buffer.push_str("        }\n");
     }
+    buffer.push_str("    }\n}\n\n");
 
-    structs
-}
+    for relation in &relations {
+        writeln!(
+            buffer,
+            "impl Related<super::{}::Entity> for Entity {{\n    fn to() -> RelationDef {{\n        Relation::{}.def()\n    }}\n}}\n",
+            relation.to.to_case(Case::Snake),
+            relation.to
+        )
+        .unwrap();
+    }
 
-#[derive(Clone, Copy)]
-enum ParamUsage {
-    Create,
-    Update,
-}
+    for relation in &model.polymorphic {
+        buffer.push_str(&render_polymorphic_loader(relation));
+    }
 
-fn build_param_fields(
-    entries: &[ParamEntry],
-    field_map: &Option<HashMap<String, &Field>>,
-    usage: ParamUsage,
-) -> Vec<ParamField> {
-    entries
+    if !resource.default_scope.is_empty() {
+        buffer.push_str(&render_scoped_finders(resource, model, enums));
+    }
+
+    let encrypted: Vec<&Field> = model
+        .fields
         .iter()
-        .map(|entry| {
-            let (ty, comment, derived_optional) = match field_map {
-                Some(map) => map
-                    .get(&entry.name)
-                    .map(|field| {
-                        let optional = match usage {
-                            ParamUsage::Create => entry.optional || field.optional,
-                            ParamUsage::Update => true,
-                        };
-                        let (ty, comment) = render_param_type(field, optional);
-                        (ty, comment, optional)
-                    })
-                    .unwrap_or_else(|| fallback_param_type(&entry.name, entry.optional, usage)),
-                None => fallback_param_type(&entry.name, entry.optional, usage),
-            };
+        .filter(|field| field.attributes.encrypted)
+        .collect();
+    if encrypted.is_empty() {
+        buffer.push_str("impl ActiveModelBehavior for ActiveModel {}\n");
+    } else {
+        buffer.push_str(&render_encryption_behavior(&encrypted));
+    }
+    if seaography {
+        buffer.push_str(&render_related_entity(resource, &relations));
+    }
+    buffer
+}
 
-            let mut doc = Vec::new();
-            if let Some(comment) = comment {
-                doc.push(comment);
-            }
-            ParamField {
-                name: entry.name.clone(),
+/// `find_scoped()` applying the `default_scope` as sea_orm filters, and `unscoped()` for the
+/// rows it hides. Resolve has checked each value against its column's type.
+fn render_scoped_finders(resource: &Resource, model: &Model, enums: &[EnumDecl]) -> String {
+    let mut buffer = String::from("impl Entity {\n");
+    buffer.push_str("    /// `find()` narrowed by the resource's `default_scope`; `index` and `show` read through\n");
+    buffer.push_str("    /// this.\n");
+    buffer.push_str("    pub fn find_scoped() -> Select<Entity> {\n        Self::find()\n");
+    for condition in &resource.default_scope {
+        let ty = model
+            .fields
+            .iter()
+            .find(|field| field.name == condition.field)
+            .map(|field| field.ty.name.as_str())
+            .unwrap_or_default();
+        let value = if enums.iter().any(|decl| decl.name == ty) {
+            format!(
+                "crate::models::{}::{}",
                 ty,
-                optional: derived_optional,
-                doc,
-            }
-        })
-        .collect()
+                condition.equals.to_case(Case::Pascal)
+            )
+        } else if matches!(
+            ViaScalar::from_name(ty),
+            Some(ViaScalar::String | ViaScalar::Text)
+        ) {
+            format!("{:?}", condition.equals)
+        } else {
+            condition.equals.clone()
+        };
+        writeln!(
+            buffer,
+            "            .filter(Column::{}.eq({}))",
+            condition.field.to_case(Case::Pascal),
+            value
+        )
+        .unwrap();
+    }
+    buffer.push_str("    }\n\n");
+    buffer.push_str("    /// Every row, including the ones `default_scope` hides.\n");
+    buffer
+        .push_str("    pub fn unscoped() -> Select<Entity> {\n        Self::find()\n    }\n}\n\n");
+    buffer
 }
 
-fn render_param_type(field: &Field, optional: bool) -> (String, Option<String>) {
-    let (base, comment) = map_builtin(&field.ty.name);
-    let ty = if optional {
-        format!("Option<{}>", base)
+/// The `RelatedEntity` enum seaography's schema builder walks to expose each relation as a
+/// GraphQL field, in sea-orm-cli's `--seaography` shape. A self-reference names the relation
+/// explicitly, since `Entity` alone doesn't say which side to join. `DeriveRelatedEntity` expands
+/// to nothing unless sea-orm's `seaography` feature is on, so the enum costs nothing by default.
+fn render_related_entity(resource: &Resource, relations: &[&&Relation]) -> String {
+    let mut buffer = String::from(
+        "\n#[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]\npub enum RelatedEntity {\n",
+    );
+    for relation in relations {
+        if relation.to == resource.name {
+            writeln!(
+                buffer,
+                "    #[sea_orm(entity = \"Entity\", def = \"Relation::{}.def()\")]",
+                relation.to
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                buffer,
+                "    #[sea_orm(entity = \"super::{}::Entity\")]",
+                relation.to.to_case(Case::Snake)
+            )
+            .unwrap();
+        }
+        writeln!(buffer, "    {},", relation.to).unwrap();
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// `ActiveModelBehavior` sealing `@encrypted` columns that are being set on save and opening them
+/// in the model `save` returns. SeaORM has no hook on load, so rows read with `find()` keep their
+/// ciphertext until the caller runs `Model::decrypt_fields`.
+fn render_encryption_behavior(fields: &[&Field]) -> String {
+    let mut buffer = String::from("impl Model {\n");
+    buffer.push_str(
+        "    /// Replaces every `@encrypted` column with its plaintext; call after `find()`.\n",
+    );
+    buffer.push_str(
+        "    pub fn decrypt_fields(&mut self, key: &EncryptionKey) -> Result<(), EncryptionError> {\n",
+    );
+    for field in fields {
+        if field.optional {
+            writeln!(
+                buffer,
+                "        if let Some(value) = &mut self.{} {{\n            *value = key.decrypt(value)?;\n        }}",
+                field.name
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                buffer,
+                "        self.{} = key.decrypt(&self.{})?;",
+                field.name, field.name
+            )
+            .unwrap();
+        }
+    }
+    buffer.push_str("        Ok(())\n    }\n}\n\n");
+
+    buffer.push_str("/// Seals `@encrypted` columns with the key from `VIA_ENCRYPTION_KEY`.\n");
+    buffer.push_str("#[async_trait::async_trait]\nimpl ActiveModelBehavior for ActiveModel {\n");
+    buffer.push_str(
+        "    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>\n    where\n        C: ConnectionTrait,\n    {\n",
+    );
+    buffer.push_str("        let key = EncryptionKey::from_env().map_err(encryption_error)?;\n");
+    for field in fields {
+        let (pattern, sealed) = if field.optional {
+            (
+                "Some(value)",
+                "Some(key.encrypt(value).map_err(encryption_error)?)",
+            )
+        } else {
+            ("value", "key.encrypt(value).map_err(encryption_error)?")
+        };
+        writeln!(
+            buffer,
+            "        if let ActiveValue::Set({}) = &self.{} {{\n            self.{} = ActiveValue::Set({});\n        }}",
+            pattern, field.name, field.name, sealed
+        )
+        .unwrap();
+    }
+    buffer.push_str("        Ok(self)\n    }\n\n");
+    buffer.push_str(
+        "    async fn after_save<C>(mut model: Model, _db: &C, _insert: bool) -> Result<Model, DbErr>\n    where\n        C: ConnectionTrait,\n    {\n",
+    );
+    buffer.push_str("        let key = EncryptionKey::from_env().map_err(encryption_error)?;\n");
+    buffer.push_str("        model.decrypt_fields(&key).map_err(encryption_error)?;\n");
+    buffer.push_str("        Ok(model)\n    }\n}\n\n");
+    buffer.push_str(
+        "fn encryption_error(err: EncryptionError) -> DbErr {\n    DbErr::Custom(err.to_string())\n}\n",
+    );
+    buffer
+}
+
+/// An enum of the target entities' models and a `find_<name>` that loads the one
+/// `<name>_type`/`<name>_id` point at. Polymorphic keys have no foreign key constraint, so there
+/// is no `Relation` variant to join through.
+fn render_polymorphic_loader(relation: &PolymorphicRelation) -> String {
+    let enum_name = relation.name.to_case(Case::Pascal);
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "/// The record `{}` and `{}` point at.",
+        relation.type_field(),
+        relation.id_field()
+    )
+    .unwrap();
+    buffer.push_str("#[derive(Clone, Debug, PartialEq)]\n");
+    writeln!(buffer, "pub enum {} {{", enum_name).unwrap();
+    for target in &relation.targets {
+        writeln!(
+            buffer,
+            "    {}(super::{}::Model),",
+            target,
+            target.to_case(Case::Snake)
+        )
+        .unwrap();
+    }
+    buffer.push_str("}\n\n");
+    buffer.push_str("impl Model {\n");
+    writeln!(
+        buffer,
+        "    /// Loads the `{}` record, or `None` when `{}` names no target or the row is gone.",
+        relation.name,
+        relation.type_field()
+    )
+    .unwrap();
+    writeln!(
+        buffer,
+        "    pub async fn find_{}<C: ConnectionTrait>(&self, db: &C) -> Result<Option<{}>, DbErr> {{",
+        relation.name, enum_name
+    )
+    .unwrap();
+    if relation.optional {
+        writeln!(
+            buffer,
+            "        let (Some(kind), Some(id)) = (self.{}.as_deref(), self.{}) else {{\n            return Ok(None);\n        }};",
+            relation.type_field(),
+            relation.id_field()
+        )
+        .unwrap();
     } else {
-        base.clone()
+        writeln!(
+            buffer,
+            "        let (kind, id) = (self.{}.as_str(), self.{});",
+            relation.type_field(),
+            relation.id_field()
+        )
+        .unwrap();
+    }
+    buffer.push_str("        Ok(match kind {\n");
+    for target in &relation.targets {
+        writeln!(
+            buffer,
+            "            {:?} => super::{}::Entity::find_by_id(id)\n                .one(db)\n                .await?\n                .map({}::{}),",
+            target,
+            target.to_case(Case::Snake),
+            enum_name,
+            target
+        )
+        .unwrap();
+    }
+    buffer.push_str("            _ => None,\n        })\n    }\n}\n\n");
+    buffer
+}
+
+/// sea-orm `ForeignKeyAction` variant for a key's `@on_delete`, restrict when unset.
+fn foreign_key_action(model: &Model, field_name: &str) -> String {
+    model
+        .fields
+        .iter()
+        .find(|field| field.name == field_name)
+        .and_then(|field| field.attributes.on_delete.as_deref())
+        .unwrap_or("restrict")
+        .to_case(Case::Pascal)
+}
+
+/// `YYYYMMDD_HHMMSS` (UTC) for a unix time, the stamp loco puts in migration module names.
+pub fn migration_stamp(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's `civil_from_days`).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+/// Resources with a model, each after the ones its foreign keys point at, so registering
+/// their migrations in this order creates referenced tables first. Ties (and cycles) fall back
+/// to name order.
+fn migration_order<'a>(resources: &'a [Resource], relations: &[Relation]) -> Vec<&'a Resource> {
+    let mut pending: Vec<&Resource> = resources
+        .iter()
+        .filter(|resource| resource.model.is_some())
+        .collect();
+    let mut ordered: Vec<&Resource> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|resource| {
+                relations.iter().all(|relation| {
+                    relation.from != resource.name
+                        || relation.kind != RelationKind::BelongsTo
+                        || relation.to == resource.name
+                        || !pending.iter().any(|other| other.name == relation.to)
+                })
+            })
+            .unwrap_or(0);
+        ordered.push(pending.remove(ready));
+    }
+    ordered
+}
+
+fn render_migration(
+    resource: &Resource,
+    relations: &[Relation],
+    resources: &[Resource],
+    enums: &[EnumDecl],
+) -> String {
+    let model = resource.model.as_ref().expect("only models get migrations");
+    let table = resource.table_name();
+    let resource_names: Vec<&str> = resources.iter().map(|r| r.name.as_str()).collect();
+
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}// source: {}",
+        GENERATED_HEADER, resource.file_path
+    )
+    .unwrap();
+    buffer.push('\n');
+    buffer.push_str("use loco_rs::schema::*;\nuse sea_orm_migration::prelude::*;\n\n");
+    buffer.push_str("#[derive(DeriveMigrationName)]\npub struct Migration;\n\n");
+    buffer.push_str("#[async_trait::async_trait]\nimpl MigrationTrait for Migration {\n");
+    buffer.push_str("    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {\n");
+
+    let mut columns = String::new();
+    let id = model.fields.iter().find(|field| field.name == "id");
+    match id.and_then(|field| ViaScalar::from_name(&field.ty.name)) {
+        None | Some(ViaScalar::Integer) => {
+            columns.push_str("                (\"id\", ColType::PkAuto),\n");
+        }
+        Some(ViaScalar::Uuid) => columns.push_str("                (\"id\", ColType::PkUuid),\n"),
+        Some(_) => {
+            writeln!(
+                columns,
+                "                // TODO: loco has no primary key column for Via type `{}`\n                (\"id\", ColType::PkAuto),",
+                id.map_or("", |field| field.ty.name.as_str())
+            )
+            .unwrap();
+        }
+    }
+    // loco's `create_table` adds `created_at` and `updated_at` itself.
+    let stored = model.fields.iter().filter(|field| {
+        field.attributes.computed.is_none()
+            && !field.attributes.transient
+            && !resource_names.contains(&field.ty.name.as_str())
+            && !matches!(field.name.as_str(), "id" | "created_at" | "updated_at")
+    });
+    for field in stored {
+        let (col_type, comment) = migration_col_type(field, enums);
+        if let Some(comment) = comment {
+            writeln!(columns, "                // {}", comment).unwrap();
+        }
+        writeln!(
+            columns,
+            "                ({:?}, ColType::{}),",
+            field.name, col_type
+        )
+        .unwrap();
+    }
+
+    // loco's references cascade (or set null, when the key is optional); keys whose
+    // `@on_delete` asks for something else get their constraint added separately.
+    let mut refs = Vec::new();
+    let mut constraints = String::new();
+    let mut seen = BTreeSet::new();
+    for relation in relations {
+        if relation.from != resource.name
+            || relation.kind != RelationKind::BelongsTo
+            || !seen.insert(relation.field.as_str())
+        {
+            continue;
+        }
+        let Some(key) = model
+            .fields
+            .iter()
+            .find(|field| field.name == relation.field)
+        else {
+            continue;
+        };
+        let Some(target) = resources
+            .iter()
+            .find(|other| other.name == relation.to && other.model.is_some())
+        else {
+            continue;
+        };
+        let target_table = target.table_name();
+        let action = foreign_key_action(model, &key.name);
+        match (action.as_str(), key.optional) {
+            ("Cascade", false) => refs.push(format!("({:?}, {:?})", target_table, key.name)),
+            ("SetNull", true) => {
+                refs.push(format!("(\"{}?\", {:?})", target_table, key.name));
+            }
+            _ => {
+                writeln!(
+                    constraints,
+                    "            m.create_foreign_key(\n                ForeignKey::create()\n                    .name(\"fk-{}-{}-to-{}\")\n                    .from(Alias::new({:?}), Alias::new({:?}))\n                    .to(Alias::new({:?}), Alias::new(\"id\"))\n                    .on_delete(ForeignKeyAction::{})\n                    .to_owned(),\n            )\n            .await?;",
+                    target_table, key.name, table, table, key.name, target_table, action
+                )
+                .unwrap();
+            }
+        }
+    }
+    let refs = if refs.is_empty() {
+        "&[]".to_owned()
+    } else {
+        format!("&[{}]", refs.join(", "))
     };
-    (ty, comment)
+
+    write!(
+        buffer,
+        "        create_table(\n            m,\n            {:?},\n            &[\n{}            ],\n            {},\n        )\n        .await",
+        table, columns, refs
+    )
+    .unwrap();
+    if constraints.is_empty() {
+        buffer.push('\n');
+    } else {
+        buffer.push_str("?;\n");
+        buffer.push_str(
+            "        // SQLite can't add foreign keys to an existing table; there, these keys stay\n        // unconstrained.\n",
+        );
+        buffer.push_str(
+            "        if m.get_database_backend() != sea_orm::DatabaseBackend::Sqlite {\n",
+        );
+        buffer.push_str(&constraints);
+        buffer.push_str("        }\n        Ok(())\n");
+    }
+    buffer.push_str("    }\n\n");
+    writeln!(
+        buffer,
+        "    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {{\n        drop_table(m, {:?}).await\n    }}\n}}",
+        table
+    )
+    .unwrap();
+    buffer
 }
 
-fn fallback_param_type(
-    name: &str,
-    entry_optional: bool,
-    usage: ParamUsage,
-) -> (String, Option<String>, bool) {
-    let optional = match usage {
-        ParamUsage::Create => entry_optional,
-        ParamUsage::Update => true,
+/// loco `ColType` of a stored field, plus a comment to put above it. Enum fields store their
+/// wire string and encrypted ones their ciphertext as `Text`, as in the explicit entities. Optional fields take the `*Null` variant, and so do
+/// `@feature` fields, whose column must accept rows written with the feature off; a literal
+/// default picks `*WithDefault` instead.
+fn migration_col_type(field: &Field, enums: &[EnumDecl]) -> (String, Option<String>) {
+    let (base, mut comment) = if field.attributes.encrypted {
+        ("Text", None)
+    } else if enums.iter().any(|decl| decl.name == field.ty.name) {
+        ("String", None)
+    } else {
+        match ViaScalar::from_name(&field.ty.name) {
+            Some(ViaScalar::String) => ("String", None),
+            Some(ViaScalar::Text) => ("Text", None),
+            Some(ViaScalar::Boolean) => ("Boolean", None),
+            Some(ViaScalar::Integer) => ("Integer", None),
+            Some(ViaScalar::BigInt) => ("BigInteger", None),
+            Some(ViaScalar::Float) => ("Double", None),
+            Some(ViaScalar::Decimal) => ("Decimal", None),
+            Some(ViaScalar::DateTime) => ("TimestampWithTimeZone", None),
+            Some(ViaScalar::Date) => ("Date", None),
+            Some(ViaScalar::Time) => ("Time", None),
+            Some(ViaScalar::Uuid) => ("Uuid", None),
+            Some(ViaScalar::Json) => ("JsonBinary", None),
+            None => (
+                "String",
+                Some(format!(
+                    "TODO: map Via type `{}` to a column type",
+                    field.ty.name
+                )),
+            ),
+        }
     };
-    let ty = if optional {
-        "Option<String>".to_string()
+    let col_type = match &field.attributes.default {
+        Some(Literal::String(value)) => format!("{}WithDefault({:?}.to_owned())", base, value),
+        Some(Literal::Integer(value)) => format!("{}WithDefault({})", base, value),
+        Some(Literal::Bool(value)) => format!("{}WithDefault({})", base, value),
+        Some(Literal::Now) => {
+            comment = Some("`now()` is filled in by the generated model and params".to_owned());
+            base.to_owned()
+        }
+        None if field.optional => format!("{}Null", base),
+        None => match &field.attributes.feature {
+            Some(feature) => {
+                comment = Some(format!(
+                    "nullable: the column only exists in the model with the `{}` feature",
+                    feature
+                ));
+                format!("{}Null", base)
+            }
+            None => base.to_owned(),
+        },
+    };
+    (col_type, comment)
+}
+
+/// Shared by every generated `<Model>Builder::build()`; lives in `models/mod.rs`.
+const MISSING_FIELD_ERROR: &str = r#"/// Returned by `<Model>Builder::build()` when a required field was never set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingField(pub &'static str);
+
+impl std::fmt::Display for MissingField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "missing required field `{}`", self.0)
+    }
+}
+
+impl std::error::Error for MissingField {}
+"#;
+
+/// Fluent builder with one setter per field. Optional fields default to `None`; `build()`
+/// reports the first required field that was never set.
+fn render_model_builder(struct_name: &str, model: &Model) -> String {
+    let builder_name = format!("{}Builder", struct_name);
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "/// Fluent builder for [`{}`], e.g. for tests and seeders.",
+        struct_name
+    )
+    .unwrap();
+    let redacted = model.fields.iter().any(is_sensitive);
+    if redacted {
+        buffer.push_str("#[derive(Clone, Default)]\n");
     } else {
-        "String".to_string()
+        buffer.push_str("#[derive(Debug, Clone, Default)]\n");
+    }
+    writeln!(buffer, "pub struct {} {{", builder_name).unwrap();
+    for field in &model.fields {
+        if let Some(cfg) = feature_cfg(field) {
+            writeln!(buffer, "    {}", cfg).unwrap();
+        }
+        let (ty, _) = render_field_type(field, false);
+        writeln!(buffer, "    {}: Option<{}>,", field.name, ty).unwrap();
+    }
+    buffer.push_str("}\n\n");
+    if redacted {
+        let fields = model
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), Some(field)));
+        buffer.push_str(&render_redacted_debug(&builder_name, fields));
+        buffer.push('\n');
+    }
+
+    writeln!(buffer, "impl {} {{", struct_name).unwrap();
+    writeln!(
+        buffer,
+        "    pub fn builder() -> {} {{\n        {}::default()\n    }}",
+        builder_name, builder_name
+    )
+    .unwrap();
+    buffer.push_str("}\n\n");
+
+    writeln!(buffer, "impl {} {{", builder_name).unwrap();
+    for field in &model.fields {
+        if let Some(cfg) = feature_cfg(field) {
+            writeln!(buffer, "    {}", cfg).unwrap();
+        }
+        let (ty, _) = render_field_type(field, false);
+        writeln!(
+            buffer,
+            "    pub fn {name}(mut self, value: impl Into<{ty}>) -> Self {{\n        self.{name} = Some(value.into());\n        self\n    }}\n",
+            name = field.name,
+            ty = ty
+        )
+        .unwrap();
+    }
+    writeln!(
+        buffer,
+        "    pub fn build(self) -> Result<{}, MissingField> {{",
+        struct_name
+    )
+    .unwrap();
+    writeln!(buffer, "        Ok({} {{", struct_name).unwrap();
+    for field in &model.fields {
+        if let Some(cfg) = feature_cfg(field) {
+            writeln!(buffer, "            {}", cfg).unwrap();
+        }
+        if field.optional {
+            writeln!(
+                buffer,
+                "            {name}: self.{name},",
+                name = field.name
+            )
+            .unwrap();
+        } else if field.attributes.default.is_some() {
+            writeln!(
+                buffer,
+                "            {name}: self.{name}.unwrap_or_else({path}),",
+                name = field.name,
+                path = default_fn_path(struct_name, field)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                buffer,
+                "            {name}: self.{name}.ok_or(MissingField(\"{name}\"))?,",
+                name = field.name
+            )
+            .unwrap();
+        }
+    }
+    buffer.push_str("        })\n    }\n}\n");
+    buffer
+}
+
+/// `Post::default_status`, the function [`render_default_fn`] emits for a defaulted field.
+fn default_fn_path(struct_name: &str, field: &Field) -> String {
+    format!("{}::default_{}", struct_name, field.name)
+}
+
+/// `default_<field>()` returning the field's default in the model's type, for serde and the
+/// builder. `now()` is the current time as the RFC 3339 string the model stores.
+fn render_default_fn(field: &Field, default: &Literal) -> String {
+    let (ty, _) = render_field_type(field, false);
+    let value = match default {
+        Literal::String(value) => match ViaScalar::from_name(&field.ty.name) {
+            Some(_) => format!("{:?}.to_owned()", value),
+            None => format!("{}::{}", field.ty.name, value.to_case(Case::Pascal)),
+        },
+        Literal::Now => "chrono::Utc::now().to_rfc3339()".to_owned(),
+        other => other.to_string(),
     };
-    let comment = Some(format!(
-        "TODO: confirm type for `{}` (fallback to String)",
-        name
-    ));
-    (ty, comment, optional)
+    let cfg = feature_cfg(field)
+        .map(|cfg| format!("    {}\n", cfg))
+        .unwrap_or_default();
+    format!(
+        "\n    /// Value a new record gets for `{}` when none is given; also the column default.\n{}    pub fn default_{}() -> {} {{\n        {}\n    }}\n",
+        field.name, cfg, field.name, ty, value
+    )
+}
+
+/// What a record's `summary()` shows: the `@title` field, else the first serialized String/Text
+/// field that exists in every build.
+fn title_field(model: &Model) -> Option<&Field> {
+    model
+        .fields
+        .iter()
+        .find(|field| field.attributes.title)
+        .or_else(|| {
+            model.fields.iter().find(|field| {
+                matches!(
+                    ViaScalar::from_name(&field.ty.name),
+                    Some(ViaScalar::String | ViaScalar::Text)
+                ) && field.attributes.serialize != Some(false)
+                    && field.attributes.feature.is_none()
+            })
+        })
+}
+
+/// The required `id` a title-less record is summarized by, when the model declares one.
+fn summary_id(model: &Model) -> Option<&Field> {
+    model.fields.iter().find(|field| {
+        field.name == "id"
+            && !field.optional
+            && field.attributes.serialize != Some(false)
+            && field.attributes.feature.is_none()
+    })
+}
+
+/// `summary()`: a one-line label for admin and debug views. An optional title falls back to the
+/// same label a model without one gets: `<Name> #<id>`, or just the name without an `id`.
+fn render_summary_method(struct_name: &str, model: &Model) -> String {
+    let fallback = match summary_id(model) {
+        Some(_) => format!("format!(\"{} #{{}}\", self.id)", struct_name),
+        None => format!("{:?}.to_owned()", struct_name),
+    };
+    let (doc, body) = match title_field(model) {
+        Some(field) => {
+            let source = if field.attributes.title {
+                format!("the `@title` field `{}`", field.name)
+            } else {
+                format!("`{}`, the first string field", field.name)
+            };
+            let body = if field.optional {
+                format!(
+                    "self.{}.clone().unwrap_or_else(|| {})",
+                    field.name, fallback
+                )
+            } else {
+                format!("self.{}.clone()", field.name)
+            };
+            (source, body)
+        }
+        None if summary_id(model).is_some() => ("the id".to_owned(), fallback),
+        None => ("the model name".to_owned(), fallback),
+    };
+    format!(
+        "    /// Label for admin and debug views: {}.\n    pub fn summary(&self) -> String {{\n        {}\n    }}\n",
+        doc, body
+    )
 }
 
-fn build_field_index(model: &Model) -> HashMap<String, &Field> {
-    let mut map = HashMap::new();
-    for field in &model.fields {
-        map.insert(field.name.clone(), field);
+/// Emits the inlined expression as a `<FIELD>_SQL` constant for queries to select, plus a
+/// read-only accessor for the populated value.
+/// The model's `TABLE_NAME`, a `<FIELD>_SQL` const and accessor per computed field, and the
+/// encrypt/decrypt pair when any field is `@encrypted`.
+fn render_model_impl(struct_name: &str, table_name: &str, model: &Model) -> String {
+    let mut buffer = String::new();
+    writeln!(buffer, "impl {} {{", struct_name).unwrap();
+    buffer.push_str("    /// Database table backing this model.\n");
+    writeln!(buffer, "    pub const TABLE_NAME: &str = {:?};", table_name).unwrap();
+    buffer.push('\n');
+    buffer.push_str(&render_summary_method(struct_name, model));
+    for field in &model.fields {
+        if let Some(default) = &field.attributes.default {
+            buffer.push_str(&render_default_fn(field, default));
+        }
+    }
+    let computed_fields = model
+        .fields
+        .iter()
+        .filter(|field| field.attributes.computed.is_some());
+    for field in computed_fields {
+        let expr = field.attributes.computed.as_deref().unwrap_or_default();
+        let cfg = feature_cfg(field)
+            .map(|cfg| format!("    {}\n", cfg))
+            .unwrap_or_default();
+        buffer.push('\n');
+        writeln!(
+            buffer,
+            "    /// Expression backing the computed `{}` field; select it to populate the field.",
+            field.name
+        )
+        .unwrap();
+        writeln!(
+            buffer,
+            "{}    pub const {}_SQL: &str = {:?};\n",
+            cfg,
+            field.name.to_case(Case::UpperSnake),
+            expr
+        )
+        .unwrap();
+        let (ty, _) = render_field_type(field, field.optional);
+        let (return_ty, body) = match ty.as_str() {
+            "String" => ("&str".to_owned(), format!("&self.{}", field.name)),
+            "Option<String>" => (
+                "Option<&str>".to_owned(),
+                format!("self.{}.as_deref()", field.name),
+            ),
+            _ => (format!("&{}", ty), format!("&self.{}", field.name)),
+        };
+        writeln!(
+            buffer,
+            "{}    pub fn {}(&self) -> {} {{\n        {}\n    }}",
+            cfg, field.name, return_ty, body
+        )
+        .unwrap();
+    }
+    let encrypted_fields: Vec<&Field> = model
+        .fields
+        .iter()
+        .filter(|field| field.attributes.encrypted)
+        .collect();
+    if !encrypted_fields.is_empty() {
+        buffer.push_str(&render_encryption_methods(&encrypted_fields));
+    }
+    for relation in &model.polymorphic {
+        buffer.push_str(&render_polymorphic_accessor(struct_name, relation));
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+fn polymorphic_enum_name(struct_name: &str, relation: &PolymorphicRelation) -> String {
+    format!("{}{}", struct_name, relation.name.to_case(Case::Pascal))
+}
+
+/// One enum per `belongs_to name: (A | B)`, naming the target model and its id.
+fn render_polymorphic_enums(struct_name: &str, model: &Model) -> String {
+    let mut buffer = String::new();
+    for relation in &model.polymorphic {
+        writeln!(
+            buffer,
+            "/// What `{}.{}` points at: the target model and its id.",
+            struct_name, relation.name
+        )
+        .unwrap();
+        buffer.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n");
+        writeln!(
+            buffer,
+            "pub enum {} {{",
+            polymorphic_enum_name(struct_name, relation)
+        )
+        .unwrap();
+        for target in &relation.targets {
+            writeln!(buffer, "    {}(i32),", target).unwrap();
+        }
+        buffer.push_str("}\n\n");
+    }
+    buffer
+}
+
+/// Reads `<name>_type`/`<name>_id` back as the relation's enum.
+fn render_polymorphic_accessor(struct_name: &str, relation: &PolymorphicRelation) -> String {
+    let enum_name = polymorphic_enum_name(struct_name, relation);
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "\n    /// The `{}` target, or `None` when `{}` names none of {}.",
+        relation.name,
+        relation.type_field(),
+        relation.targets.join(", ")
+    )
+    .unwrap();
+    writeln!(
+        buffer,
+        "    pub fn {}(&self) -> Option<{}> {{",
+        relation.name, enum_name
+    )
+    .unwrap();
+    if relation.optional {
+        writeln!(
+            buffer,
+            "        let (Some(kind), Some(id)) = (self.{}.as_deref(), self.{}) else {{\n            return None;\n        }};",
+            relation.type_field(),
+            relation.id_field()
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            buffer,
+            "        let (kind, id) = (self.{}.as_str(), self.{});",
+            relation.type_field(),
+            relation.id_field()
+        )
+        .unwrap();
+    }
+    buffer.push_str("        match kind {\n");
+    for target in &relation.targets {
+        writeln!(
+            buffer,
+            "            {:?} => Some({}::{}(id)),",
+            target, enum_name, target
+        )
+        .unwrap();
+    }
+    buffer.push_str("            _ => None,\n        }\n    }\n");
+    buffer
+}
+
+fn render_encryption_methods(fields: &[&Field]) -> String {
+    let names = fields
+        .iter()
+        .map(|field| format!("{:?}", field.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut buffer = String::new();
+    buffer.push_str("\n    /// Fields stored as ciphertext (`@encrypted`).\n");
+    writeln!(
+        buffer,
+        "    pub const ENCRYPTED_FIELDS: &[&str] = &[{}];",
+        names
+    )
+    .unwrap();
+    for (method, call, doc) in [
+        (
+            "encrypt_fields",
+            "encrypt",
+            "Replaces every `@encrypted` field with its ciphertext; call before saving.",
+        ),
+        (
+            "decrypt_fields",
+            "decrypt",
+            "Reverses [`Self::encrypt_fields`]; call after loading.",
+        ),
+    ] {
+        writeln!(buffer, "\n    /// {}", doc).unwrap();
+        writeln!(
+            buffer,
+            "    pub fn {}(&mut self, key: &EncryptionKey) -> Result<(), EncryptionError> {{",
+            method
+        )
+        .unwrap();
+        for field in fields {
+            if field.optional {
+                writeln!(
+                    buffer,
+                    "        if let Some(value) = &mut self.{} {{\n            *value = key.{}(value)?;\n        }}",
+                    field.name, call
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    buffer,
+                    "        self.{} = key.{}(&self.{})?;",
+                    field.name, call, field.name
+                )
+                .unwrap();
+            }
+        }
+        buffer.push_str("        Ok(())\n    }\n");
+    }
+    buffer
+}
+
+/// The params PATCH applies; an absent field there means "unchanged", not "missing".
+fn is_patch_params(resource: &Resource, param_struct: &ParamStruct) -> bool {
+    param_struct.name == format!("{}UpdateParams", resource.name)
+}
+
+/// Derives for a model or params struct. `Debug` is left out when a field is sensitive, since
+/// [`render_redacted_debug`] implements it instead.
+fn struct_derives<'a>(
+    fields: impl Iterator<Item = &'a Field> + Clone,
+    cross_field: bool,
+) -> String {
+    let mut derives = Vec::new();
+    if !fields.clone().any(is_sensitive) {
+        derives.push("Debug");
+    }
+    derives.extend(["Clone", "Serialize", "Deserialize"]);
+    if cross_field || fields.clone().any(|field| field.attributes.validated()) {
+        derives.push("Validate");
+    }
+    derives.join(", ")
+}
+
+/// Whether generated `Debug` output hides the field: `@sensitive`, or `@encrypted`, whose
+/// plaintext passes through the model before it is sealed.
+fn is_sensitive(field: &Field) -> bool {
+    field.attributes.sensitive || field.attributes.encrypted
+}
+
+/// A `Debug` impl printing `***` for sensitive fields and the value for the rest, so logging a
+/// record never leaks them. `fields` pairs each struct field with its model field, if any.
+fn render_redacted_debug<'a>(
+    struct_name: &str,
+    fields: impl Iterator<Item = (&'a str, Option<&'a Field>)>,
+) -> String {
+    let fields: Vec<(&str, Option<&Field>)> = fields.collect();
+    let mut buffer = String::new();
+    writeln!(buffer, "impl std::fmt::Debug for {} {{", struct_name).unwrap();
+    buffer.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    writeln!(
+        buffer,
+        "        let mut debug = f.debug_struct({:?});",
+        struct_name
+    )
+    .unwrap();
+    for (name, field) in fields {
+        if let Some(cfg) = field.and_then(feature_cfg) {
+            writeln!(buffer, "        {}", cfg).unwrap();
+        }
+        if field.is_some_and(is_sensitive) {
+            writeln!(
+                buffer,
+                "        debug.field({:?}, &format_args!(\"***\"));",
+                name
+            )
+            .unwrap();
+        } else {
+            writeln!(buffer, "        debug.field({:?}, &self.{});", name, name).unwrap();
+        }
+    }
+    buffer.push_str("        debug.finish()\n    }\n}\n");
+    buffer
+}
+
+fn validate_attr_lines(resource_name: &str, field: &Field) -> Vec<String> {
+    let mut lines = Vec::new();
+    if field.attributes.allowed.is_some() {
+        lines.push(format!(
+            "#[validate(custom(function = \"{}\"))]",
+            allowed_values_fn_name(resource_name, field)
+        ));
+    }
+    let attrs = &field.attributes;
+    match attrs.format.as_deref() {
+        Some("email") => lines.push("#[validate(email)]".to_owned()),
+        Some("uri") => lines.push("#[validate(url)]".to_owned()),
+        Some(_) => lines.push(format!(
+            "#[validate(custom(function = \"{}\"))]",
+            format_fn_name(resource_name, field)
+        )),
+        None => {}
+    }
+    let length = [("min", attrs.min_len), ("max", attrs.max_len)]
+        .into_iter()
+        .filter_map(|(key, bound)| Some(format!("{} = {}", key, bound?)))
+        .collect::<Vec<_>>();
+    if !length.is_empty() {
+        lines.push(format!("#[validate(length({}))]", length.join(", ")));
+    }
+    let range = [("min", &attrs.min), ("max", &attrs.max)]
+        .into_iter()
+        .filter_map(|(key, bound)| {
+            let bound = bound.as_ref()?;
+            // Float fields need float literals for validator's bound to type-check.
+            let bound = if is_integer_type(&field.ty.name) || bound.contains('.') {
+                bound.clone()
+            } else {
+                format!("{}.0", bound)
+            };
+            Some(format!("{} = {}", key, bound))
+        })
+        .collect::<Vec<_>>();
+    if !range.is_empty() {
+        lines.push(format!("#[validate(range({}))]", range.join(", ")));
+    }
+    lines
+}
+
+/// `validator` checks email and uri formats itself; uuid and date-time get a custom function.
+fn has_format_fn(field: &Field) -> bool {
+    matches!(
+        field.attributes.format.as_deref(),
+        Some("uuid" | "date-time")
+    )
+}
+
+fn format_fn_name(resource_name: &str, field: &Field) -> String {
+    format!(
+        "validate_{}_{}_format",
+        resource_name.to_case(Case::Snake),
+        field.name
+    )
+}
+
+fn render_format_validator(resource_name: &str, field: &Field) -> String {
+    let (parse, code) = match field.attributes.format.as_deref() {
+        Some("uuid") => ("uuid::Uuid::parse_str(value)", "uuid"),
+        Some("date-time") => ("chrono::DateTime::parse_from_rfc3339(value)", "date_time"),
+        _ => return String::new(),
+    };
+    let cfg = feature_cfg(field)
+        .map(|cfg| format!("{}\n", cfg))
+        .unwrap_or_default();
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}fn {}(value: &str) -> Result<(), ValidationError> {{",
+        cfg,
+        format_fn_name(resource_name, field)
+    )
+    .unwrap();
+    writeln!(buffer, "    {}", parse).unwrap();
+    buffer.push_str("        .map(|_| ())\n");
+    writeln!(
+        buffer,
+        "        .map_err(|_| ValidationError::new({:?}))",
+        code
+    )
+    .unwrap();
+    buffer.push_str("}\n\n");
+    buffer
+}
+
+fn allowed_values_const_name(resource_name: &str, field: &Field) -> String {
+    format!("{}_{}_ALLOWED", resource_name, field.name).to_case(Case::UpperSnake)
+}
+
+fn allowed_values_fn_name(resource_name: &str, field: &Field) -> String {
+    format!(
+        "validate_{}_{}_in",
+        resource_name.to_case(Case::Snake),
+        field.name
+    )
+}
+
+fn render_allowed_values_validator(resource_name: &str, field: &Field) -> String {
+    let Some(allowed) = &field.attributes.allowed else {
+        return String::new();
+    };
+    let const_name = allowed_values_const_name(resource_name, field);
+    let (element_ty, arg_ty, values) = if is_integer_type(&field.ty.name) {
+        // validator passes numeric fields by value rather than by reference.
+        let (base, _) = map_builtin(&field.ty.name);
+        (base.clone(), base, allowed.join(", "))
+    } else {
+        let values = allowed
+            .iter()
+            .map(|value| format!("{:?}", value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        ("&str".to_owned(), "&str".to_owned(), values)
+    };
+
+    let cfg = feature_cfg(field)
+        .map(|cfg| format!("{}\n", cfg))
+        .unwrap_or_default();
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}pub const {}: &[{}] = &[{}];\n",
+        cfg, const_name, element_ty, values
+    )
+    .unwrap();
+    writeln!(
+        buffer,
+        "{}fn {}(value: {}) -> Result<(), ValidationError> {{",
+        cfg,
+        allowed_values_fn_name(resource_name, field),
+        arg_ty
+    )
+    .unwrap();
+    writeln!(buffer, "    if {}.contains(&value) {{", const_name).unwrap();
+    buffer.push_str("        Ok(())\n    } else {\n");
+    buffer.push_str("        Err(ValidationError::new(\"in\"))\n    }\n}\n\n");
+    buffer
+}
+
+/// A `require ... when ...` rule as it applies to one struct.
+struct RequiredWhenCheck<'a> {
+    rule: &'a RequiredWhen,
+    /// Model field compared against the literal; its type decides how the literal is written.
+    when: &'a Field,
+    /// Whether `when` is an `Option` in this struct.
+    when_optional: bool,
+}
+
+/// The rules a struct can check, given each field's optionality in it (`None` when the struct
+/// lacks the field). A rule whose required field isn't an `Option` there can't fail and is left
+/// out.
+fn required_when_checks<'a>(
+    resource: &'a Resource,
+    model: &'a Model,
+    optional_in_struct: impl Fn(&str) -> Option<bool>,
+) -> Vec<RequiredWhenCheck<'a>> {
+    resource
+        .validations
+        .iter()
+        .filter_map(|rule| {
+            let when = model.fields.iter().find(|field| field.name == rule.when)?;
+            let when_optional = optional_in_struct(&rule.when)?;
+            optional_in_struct(&rule.field).filter(|optional| *optional)?;
+            Some(RequiredWhenCheck {
+                rule,
+                when,
+                when_optional,
+            })
+        })
+        .collect()
+}
+
+/// The rules a params struct checks. PATCH params check none, since an absent field there means
+/// "unchanged" rather than "missing".
+fn param_struct_checks<'a>(
+    resource: &'a Resource,
+    model: &'a Model,
+    param_struct: &ParamStruct,
+) -> Vec<RequiredWhenCheck<'a>> {
+    if is_patch_params(resource, param_struct) {
+        return Vec::new();
+    }
+    required_when_checks(resource, model, |name| {
+        param_struct
+            .fields
+            .iter()
+            .find(|field| field.name == name && field.source.is_some())
+            .map(|field| field.optional)
+    })
+}
+
+/// Whether a params struct derives `Validate` (see [`struct_derives`]).
+fn param_struct_validated(resource: &Resource, model: &Model, param_struct: &ParamStruct) -> bool {
+    param_struct
+        .fields
+        .iter()
+        .filter_map(|field| field.source.as_ref())
+        .any(|field| field.attributes.validated())
+        || !param_struct_checks(resource, model, param_struct).is_empty()
+}
+
+fn required_when_attr(struct_name: &str) -> String {
+    format!(
+        "#[validate(schema(function = \"{}\"))]",
+        required_when_fn_name(struct_name)
+    )
+}
+
+fn required_when_fn_name(struct_name: &str) -> String {
+    format!(
+        "validate_{}_required_when",
+        struct_name.to_case(Case::Snake)
+    )
+}
+
+fn required_when_literal(check: &RequiredWhenCheck) -> String {
+    if is_integer_type(&check.when.ty.name) {
+        check.rule.equals.clone()
+    } else {
+        format!("{:?}", check.rule.equals)
+    }
+}
+
+fn render_required_when_validator(struct_name: &str, checks: &[RequiredWhenCheck]) -> String {
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "fn {}(value: &{}) -> Result<(), ValidationError> {{",
+        required_when_fn_name(struct_name),
+        struct_name
+    )
+    .unwrap();
+    for check in checks {
+        let rule = check.rule;
+        let literal = required_when_literal(check);
+        let matches = if !check.when_optional {
+            format!("value.{} == {}", rule.when, literal)
+        } else if is_integer_type(&check.when.ty.name) {
+            format!("value.{} == Some({})", rule.when, literal)
+        } else {
+            format!("value.{}.as_deref() == Some({})", rule.when, literal)
+        };
+        writeln!(
+            buffer,
+            "    if {} && value.{}.is_none() {{",
+            matches, rule.field
+        )
+        .unwrap();
+        writeln!(
+            buffer,
+            "        return Err(ValidationError::new(\"required_when\")\n            .with_message({:?}.into()));\n    }}",
+            format!("`{}` is required when `{}` is {}", rule.field, rule.when, literal)
+        )
+        .unwrap();
+    }
+    buffer.push_str("    Ok(())\n}\n\n");
+    buffer
+}
+
+fn is_integer_type(name: &str) -> bool {
+    matches!(
+        ViaScalar::from_name(name),
+        Some(ViaScalar::Integer | ViaScalar::BigInt)
+    )
+}
+
+fn render_controller(
+    resource: &Resource,
+    controller: &Controller,
+    model: Option<&Model>,
+    param_structs: &[ParamStruct],
+    tracing: bool,
+    errors: bool,
+    json_case: JsonCase,
+) -> String {
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}// source: {}",
+        GENERATED_HEADER, resource.file_path
+    )
+    .unwrap();
+    buffer.push('\n');
+    if errors {
+        buffer.push_str("use axum::http::StatusCode;\n");
+        buffer.push_str("use loco_rs::controller::ErrorDetail;\n");
+    }
+    buffer.push_str("use loco_rs::prelude::*;\n");
+    buffer.push_str("use serde_json::json;\n");
+    if tracing {
+        buffer.push_str("use tracing::Instrument;\n");
+    }
+
+    let html_show = renders_html(resource, controller, "show");
+    let edit_form = renders_edit_form(resource, controller);
+    let mut names: Vec<String> = param_structs.iter().map(|ps| ps.name.clone()).collect();
+    if resource.cursor_paginated() || html_show || edit_form {
+        names.push(resource.name.clone());
+    }
+    if !names.is_empty() {
+        names.sort();
+        names.dedup();
+        writeln!(buffer, "use crate::models::{{{}}};", names.join(", ")).unwrap();
+    }
+    if has_views(resource, controller) {
+        buffer.push_str("use crate::views;\n");
+    }
+
+    buffer.push('\n');
+
+    if !controller.respond_with.is_empty() {
+        let formats = controller
+            .respond_with
+            .iter()
+            .map(|f| format!("\"{}\"", f))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(
+            buffer,
+            "pub const RESPOND_WITH: &[&str] = &[{}];\n",
+            formats
+        )
+        .unwrap();
+    } else {
+        buffer.push_str("pub const RESPOND_WITH: &[&str] = &[\"html\", \"json\"];\n\n");
+    }
+    let format_overrides = controller
+        .action_overrides
+        .iter()
+        .filter_map(|entry| entry.respond_with.as_ref().map(|formats| (entry, formats)));
+    for (entry, formats) in format_overrides {
+        writeln!(
+            buffer,
+            "/// Overrides `RESPOND_WITH` for `{}`.\npub const {}_RESPOND_WITH: &[&str] = &[{}];\n",
+            entry.name,
+            entry.name.to_case(Case::UpperSnake),
+            formats
+                .iter()
+                .map(|format| format!("{:?}", format))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap();
+    }
+
+    let routes_fn = render_routes_fn(resource, controller);
+    buffer.push_str(&routes_fn);
+    buffer.push('\n');
+
+    if responds_with_xml(controller) {
+        buffer.push_str(XML_RESPOND_HELPER);
+        buffer.push('\n');
+    }
+    if errors {
+        buffer.push_str(&render_error_enum(resource));
+    }
+
+    let actions = resolve_actions(controller);
+    let has_index = actions.iter().any(|action| action.action_name == "index");
+    if resource.cursor_paginated() && has_index {
+        buffer.push_str(&render_index_cursor(resource, json_case));
+    } else if let Some(order) = &resource.default_order
+        && has_index
+    {
+        buffer.push_str(&render_index_order(order, model));
+    }
+    if actions
+        .iter()
+        .any(|action| scoped_action(resource, &action.action_name))
+    {
+        buffer.push_str(&render_default_scope(resource));
+    }
+    let guarded: Vec<&ActionSpec> = actions
+        .iter()
+        .filter(|action| {
+            controller
+                .authorize
+                .as_ref()
+                .is_some_and(|authorize| authorize.covers(&action.action_name))
+        })
+        .collect();
+    if !guarded.is_empty() {
+        buffer.push_str(&render_authorization_guards(resource, &guarded));
+    }
+
+    for action in &actions {
+        let is_guarded = guarded
+            .iter()
+            .any(|guarded| guarded.action_name == action.action_name);
+        if action.custom || action.body.is_some() {
+            buffer.push_str(&render_manual_action(resource, action, is_guarded, errors));
+            continue;
+        }
+        let xml = controller
+            .formats_for(&action.action_name)
+            .iter()
+            .any(|format| format == "xml");
+        let html = matches!(action.action_name.as_str(), "index" | "show")
+            && renders_html(resource, controller, &action.action_name);
+        let stub = StubOptions {
+            tracing,
+            xml,
+            html,
+            guarded: is_guarded,
+            errors,
+            json_case,
+        };
+        buffer.push_str(&render_action_stub(resource, action, param_structs, &stub));
+    }
+    buffer.push_str(&render_form_handlers(resource, controller));
+
+    if let Some(model) = model {
+        buffer.push_str("// Associated model fields\n");
+        for field in &model.fields {
+            let (ty, comment) = render_field_type(field, field.optional);
+            if let Some(comment) = comment {
+                writeln!(buffer, "// - {}: {} ({})", field.name, ty, comment).unwrap();
+            } else {
+                writeln!(buffer, "// - {}: {}", field.name, ty).unwrap();
+            }
+        }
+    }
+
+    buffer.trim_end().to_owned() + "\n"
+}
+
+pub(crate) struct ActionSpec {
+    pub action_name: String,
+    pub handler_name: String,
+    pub method: &'static str,
+    pub path: String,
+    /// Handler body written in `actions { name { ... } }`, emitted in place of the stub.
+    pub body: Option<String>,
+    /// Listed in `actions { ... }` under a name auto_crud doesn't generate.
+    pub custom: bool,
+}
+
+/// The handlers `actions auto_crud` generates, in route order.
+const CRUD_ACTIONS: [(&str, &str, &str); 6] = [
+    ("index", "get", "/"),
+    ("show", "get", "/{id}"),
+    ("create", "post", "/"),
+    ("update", "put", "/{id}"),
+    ("partial_update", "patch", "/{id}"),
+    ("destroy", "delete", "/{id}"),
+];
+
+/// auto_crud's actions, or the ones listed in `actions { ... }`. Listed CRUD names keep their
+/// usual method and path; any other name is routed as `POST /<name>`.
+pub(crate) fn resolve_actions(controller: &Controller) -> Vec<ActionSpec> {
+    let crud = |name: &str, body: Option<String>| {
+        CRUD_ACTIONS
+            .iter()
+            .find(|(action, _, _)| *action == name)
+            .map(|&(action, method, path)| ActionSpec {
+                action_name: action.to_owned(),
+                handler_name: action.to_owned(),
+                method,
+                path: path.to_owned(),
+                body,
+                custom: false,
+            })
+    };
+    match &controller.actions {
+        ControllerActions::Manual(listed) if !listed.is_empty() => listed
+            .iter()
+            .map(|action| {
+                crud(&action.name, action.body.clone()).unwrap_or_else(|| {
+                    let handler_name = action.name.to_case(Case::Snake);
+                    ActionSpec {
+                        action_name: action.name.clone(),
+                        path: format!("/{}", handler_name),
+                        handler_name,
+                        method: "post",
+                        body: action.body.clone(),
+                        custom: true,
+                    }
+                })
+            })
+            .collect(),
+        _ => CRUD_ACTIONS
+            .iter()
+            .filter_map(|(name, _, _)| crud(name, None))
+            .collect(),
+    }
+}
+
+fn render_routes_fn(resource: &Resource, controller: &Controller) -> String {
+    let mut buffer = String::new();
+    let plural = pluralize(&resource.name);
+    let prefix = format!("{}/", plural.to_case(Case::Snake));
+
+    buffer.push_str("pub fn routes() -> Routes {\n");
+    buffer.push_str("    Routes::new()\n");
+    buffer.push_str(&format!("        .prefix(\"{}\")\n", prefix));
+
+    for action in resolve_actions(controller) {
+        buffer.push_str(&format!(
+            "        .add(\"{}\", {}({}))\n",
+            action.path, action.method, action.handler_name
+        ));
+    }
+    if renders_new_form(resource, controller) {
+        buffer.push_str("        .add(\"/new\", get(new))\n");
+    }
+    if renders_edit_form(resource, controller) {
+        buffer.push_str("        .add(\"/{id}/edit\", get(edit))\n");
+    }
+
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// Columns `index` can sort on: the primary key, stored model fields, and loco's timestamps.
+/// Computed and transient fields have no column and encrypted ones would sort by ciphertext.
+pub(crate) fn orderable_fields(model: Option<&Model>) -> Vec<String> {
+    let declared = model
+        .into_iter()
+        .flat_map(|model| &model.fields)
+        .filter(|field| field.attributes.stored() && !field.attributes.encrypted)
+        .map(|field| field.name.clone());
+    let mut fields: Vec<String> = Vec::new();
+    for name in std::iter::once("id".to_owned())
+        .chain(declared)
+        .chain(["created_at".to_owned(), "updated_at".to_owned()])
+    {
+        if !fields.contains(&name) {
+            fields.push(name);
+        }
+    }
+    fields
+}
+
+/// `DEFAULT_ORDER`, the `?order=` query type, and the helper `index` uses to pick its ordering.
+fn render_index_order(order: &DefaultOrder, model: Option<&Model>) -> String {
+    let fields = orderable_fields(model)
+        .iter()
+        .map(|field| format!("{:?}", field))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r#"/// Ordering `index` applies when the request has no `?order=`.
+pub const DEFAULT_ORDER: (&str, &str) = ({:?}, {:?});
+
+/// Columns `?order=` may name.
+pub const ORDERABLE_FIELDS: &[&str] = &[{}];
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct IndexQuery {{
+    /// `<field>` or `<field> asc|desc`, e.g. `?order=title%20desc`.
+    pub order: Option<String>,
+}}
+
+/// Resolves `?order=` against `ORDERABLE_FIELDS`, falling back to `DEFAULT_ORDER`.
+fn index_order(query: &IndexQuery) -> Result<(&'static str, &'static str)> {{
+    let Some(order) = query.order.as_deref() else {{
+        return Ok(DEFAULT_ORDER);
+    }};
+    let mut parts = order.split_whitespace();
+    let requested = parts.next().unwrap_or_default();
+    let Some(field) = ORDERABLE_FIELDS.iter().copied().find(|field| *field == requested) else {{
+        return bad_request(format!("cannot order by `{{}}`", requested));
+    }};
+    let direction = match parts.next() {{
+        None | Some("asc") => "asc",
+        Some("desc") => "desc",
+        Some(other) => return bad_request(format!("unknown order direction `{{}}`", other)),
+    }};
+    if parts.next().is_some() {{
+        return bad_request("expected `?order=<field> [asc|desc]`");
+    }}
+    Ok((field, direction))
+}}
+
+"#,
+        order.field,
+        order.direction(),
+        fields
+    )
+}
+
+/// Whether the `default_scope` applies to `action`: it narrows the reads, `index` and `show`.
+fn scoped_action(resource: &Resource, action: &str) -> bool {
+    !resource.default_scope.is_empty() && matches!(action, "index" | "show")
+}
+
+fn render_default_scope(resource: &Resource) -> String {
+    let conditions = resource
+        .default_scope
+        .iter()
+        .map(|condition| format!("({:?}, {:?})", condition.field, condition.equals))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "/// `default_scope`: `(field, value)` pairs every row `index` and `show` return must match.\npub const DEFAULT_SCOPE: &[(&str, &str)] = &[{}];\n\n",
+        conditions
+    )
+}
+
+/// Page sizes for `pagination: cursor`: the default when `?limit=` is absent, and its ceiling.
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
+/// Keyset pagination for `index`: a fixed ordering (the `default_order`, else `id asc`) with ties
+/// broken by `id`, so `?after=` can resume strictly after the last row of the previous page.
+fn render_index_cursor(resource: &Resource, json_case: JsonCase) -> String {
+    let (field, direction) = match &resource.default_order {
+        Some(order) => (order.field.as_str(), order.direction()),
+        None => ("id", "asc"),
+    };
+    format!(
+        r#"/// Keyset ordering for `index`, tie-broken by `id`; cursors encode a position in it.
+pub const CURSOR_ORDER: (&str, &str) = ({field:?}, {direction:?});
+
+/// Rows per page when the request has no `?limit=`, and the most it may ask for.
+pub const DEFAULT_PAGE_SIZE: u64 = {default_size};
+pub const MAX_PAGE_SIZE: u64 = {max_size};
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct IndexQuery {{
+    /// `next_cursor` from the previous page; the first page when absent.
+    pub after: Option<String>,
+    pub limit: Option<u64>,
+}}
+
+/// One page of `index`; `next_cursor` is `None` on the last page.
+#[derive(Debug, serde::Serialize)]
+{page_attr}pub struct {name}Page {{
+    pub items: Vec<{name}>,
+    pub next_cursor: Option<String>,
+}}
+
+/// Validates `?limit=`, falling back to `DEFAULT_PAGE_SIZE`.
+fn page_size(query: &IndexQuery) -> Result<u64> {{
+    match query.limit {{
+        None => Ok(DEFAULT_PAGE_SIZE),
+        Some(limit) if (1..=MAX_PAGE_SIZE).contains(&limit) => Ok(limit),
+        Some(limit) => bad_request(format!("limit must be between 1 and {{}}, got {{}}", MAX_PAGE_SIZE, limit)),
+    }}
+}}
+
+"#,
+        name = resource.name,
+        page_attr = json_case
+            .serde_attr()
+            .map(|attr| format!("{}\n", attr))
+            .unwrap_or_default(),
+        default_size = DEFAULT_PAGE_SIZE,
+        max_size = MAX_PAGE_SIZE,
+    )
+}
+
+/// Full mounted path of an action, e.g. `/articles/{id}`.
+pub(crate) fn route_path(resource_name: &str, action: &ActionSpec) -> String {
+    let collection_path = format!("/{}", pluralize(resource_name));
+    match action.path.as_str() {
+        "/" => collection_path,
+        other => format!("{}{}", collection_path, other),
+    }
+}
+
+pub(crate) fn render_field_type(field: &Field, optional: bool) -> (String, Option<String>) {
+    let (base, comment) = map_builtin(&field.ty.name);
+    let ty = if optional {
+        format!("Option<{}>", base)
+    } else {
+        base.clone()
+    };
+    (ty, comment)
+}
+
+fn field_doc_lines(field: &Field) -> Vec<String> {
+    let mut docs = Vec::new();
+    if let Some(expr) = &field.attributes.computed {
+        docs.push(format!("Computed from `{}`; read-only", expr));
+    }
+    if field.attributes.transient {
+        docs.push("Transient: set per request and never stored; read-only".into());
+    }
+    if field.attributes.encrypted {
+        docs.push("Encrypted at rest (AES-256-GCM); see `encrypt_fields`".into());
+    }
+    if let Some(false) = field.attributes.serialize {
+        docs.push("Not serialized in API responses".into());
+    }
+    docs
+}
+
+#[derive(Debug)]
+struct ParamStruct {
+    name: String,
+    fields: Vec<ParamField>,
+}
+
+#[derive(Debug)]
+struct ParamField {
+    name: String,
+    ty: String,
+    optional: bool,
+    doc: Vec<String>,
+    /// Model field backing this entry, when the name matches one.
+    source: Option<Field>,
+}
+
+impl ParamField {
+    /// The backing model field when it has a default and this entry is required, so serde can
+    /// fill it in instead of rejecting the request.
+    fn defaulted_source(&self) -> Option<&Field> {
+        self.source
+            .as_ref()
+            .filter(|source| !self.optional && source.attributes.default.is_some())
+    }
+}
+
+fn build_param_structs(
+    resource_struct_name: &str,
+    controller: &Controller,
+    model: Option<&Model>,
+) -> Vec<ParamStruct> {
+    let mut structs = Vec::new();
+    if controller.params.is_empty() {
+        return structs;
+    }
+
+    let field_map = model.map(|m| build_field_index(m));
+
+    for profile in &controller.params {
+        match &profile.name {
+            ParamsKind::Editable => {
+                let create_name = format!("{}CreateParams", resource_struct_name);
+                let update_name = format!("{}UpdateParams", resource_struct_name);
+                let create_fields =
+                    build_param_fields(&profile.entries, &field_map, ParamUsage::Create);
+                let update_fields =
+                    build_param_fields(&profile.entries, &field_map, ParamUsage::Update);
+                structs.push(ParamStruct {
+                    name: create_name,
+                    fields: create_fields,
+                });
+                structs.push(ParamStruct {
+                    name: update_name,
+                    fields: update_fields,
+                });
+            }
+            ParamsKind::Named(name) => {
+                let struct_name = format!(
+                    "{}{}Params",
+                    resource_struct_name,
+                    name.to_case(Case::Pascal)
+                );
+                let fields = build_param_fields(&profile.entries, &field_map, ParamUsage::Create);
+                structs.push(ParamStruct {
+                    name: struct_name,
+                    fields,
+                });
+            }
+        }
+    }
+
+    structs
+}
+
+#[derive(Clone, Copy)]
+enum ParamUsage {
+    Create,
+    Update,
+}
+
+fn build_param_fields(
+    entries: &[ParamEntry],
+    field_map: &Option<HashMap<String, &Field>>,
+    usage: ParamUsage,
+) -> Vec<ParamField> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (ty, comment, derived_optional) = match field_map {
+                Some(map) => map
+                    .get(&entry.name)
+                    .map(|field| {
+                        let optional = match usage {
+                            ParamUsage::Create => entry.optional || field.optional,
+                            ParamUsage::Update => true,
+                        };
+                        let (ty, comment) = render_param_type(field, optional);
+                        (ty, comment, optional)
+                    })
+                    .unwrap_or_else(|| fallback_param_type(&entry.name, entry.optional, usage)),
+                None => fallback_param_type(&entry.name, entry.optional, usage),
+            };
+
+            let mut doc = Vec::new();
+            if let Some(comment) = comment {
+                doc.push(comment);
+            }
+            ParamField {
+                name: entry.name.clone(),
+                ty,
+                optional: derived_optional,
+                doc,
+                source: field_map
+                    .as_ref()
+                    .and_then(|map| map.get(&entry.name))
+                    .map(|field| (*field).clone()),
+            }
+        })
+        .collect()
+}
+
+fn render_param_type(field: &Field, optional: bool) -> (String, Option<String>) {
+    let (base, comment) = map_builtin(&field.ty.name);
+    let ty = if optional {
+        format!("Option<{}>", base)
+    } else {
+        base.clone()
+    };
+    (ty, comment)
+}
+
+fn fallback_param_type(
+    name: &str,
+    entry_optional: bool,
+    usage: ParamUsage,
+) -> (String, Option<String>, bool) {
+    let optional = match usage {
+        ParamUsage::Create => entry_optional,
+        ParamUsage::Update => true,
+    };
+    let ty = if optional {
+        "Option<String>".to_string()
+    } else {
+        "String".to_string()
+    };
+    let comment = Some(format!(
+        "TODO: confirm type for `{}` (fallback to String)",
+        name
+    ));
+    (ty, comment, optional)
+}
+
+fn build_field_index(model: &Model) -> HashMap<String, &Field> {
+    let mut map = HashMap::new();
+    for field in &model.fields {
+        map.insert(field.name.clone(), field);
+    }
+    map
+}
+
+/// Built-in Via scalar types. Every generator maps these to its own target type system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ViaScalar {
+    String,
+    Text,
+    Boolean,
+    Integer,
+    BigInt,
+    Float,
+    Decimal,
+    DateTime,
+    Date,
+    Time,
+    Uuid,
+    Json,
+}
+
+impl ViaScalar {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "String" => Self::String,
+            "Text" => Self::Text,
+            "Boolean" => Self::Boolean,
+            "Integer" => Self::Integer,
+            "BigInt" => Self::BigInt,
+            "Float" => Self::Float,
+            "Decimal" => Self::Decimal,
+            "DateTime" => Self::DateTime,
+            "Date" => Self::Date,
+            "Time" => Self::Time,
+            "UUID" => Self::Uuid,
+            "Json" => Self::Json,
+            _ => return None,
+        })
+    }
+
+    /// [`MODEL_DERIVES`](crate::ast::MODEL_DERIVES) the mapped Rust type lacks: `f64` has no
+    /// total order or hash, and `serde_json::Value` has no order or hash.
+    pub(crate) fn missing_derives(self) -> &'static [&'static str] {
+        match self {
+            Self::Float | Self::Decimal => &["Eq", "Ord", "Hash"],
+            Self::Json => &["PartialOrd", "Ord", "Hash"],
+            _ => &[],
+        }
+    }
+}
+
+fn map_builtin(name: &str) -> (String, Option<String>) {
+    let Some(scalar) = ViaScalar::from_name(name) else {
+        return (
+            name.to_owned(),
+            Some(format!("TODO: map Via type `{}` to a Rust type", name)),
+        );
+    };
+    match scalar {
+        ViaScalar::String | ViaScalar::Text => ("String".into(), None),
+        ViaScalar::Boolean => ("bool".into(), None),
+        ViaScalar::Integer => ("i32".into(), None),
+        ViaScalar::BigInt => ("i64".into(), None),
+        ViaScalar::Float => ("f64".into(), None),
+        ViaScalar::Decimal => (
+            "f64".into(),
+            Some("Consider rust_decimal::Decimal for exact precision".into()),
+        ),
+        ViaScalar::DateTime => (
+            "String".into(),
+            Some("ISO 8601 DateTime string; swap for chrono if desired".into()),
+        ),
+        ViaScalar::Date => ("String".into(), Some("ISO 8601 Date string".into())),
+        ViaScalar::Time => ("String".into(), Some("ISO 8601 Time string".into())),
+        ViaScalar::Uuid => (
+            "String".into(),
+            Some("UUID string; use uuid::Uuid for strong typing".into()),
+        ),
+        ViaScalar::Json => ("serde_json::Value".into(), None),
+    }
+}
+
+/// `pub mod <module>;`, pointed at the module's file in `src/` under the flat layout (where the
+/// aggregator is `src/<kind>.rs`, so `#[path]` resolves relative to `src/`).
+fn submodule_decl(module: &str, flat_suffix: Option<&str>) -> String {
+    match flat_suffix {
+        Some(suffix) => format!(
+            "#[path = \"{}{}.rs\"]\npub mod {};\n",
+            module, suffix, module
+        ),
+        None => format!("pub mod {};\n", module),
+    }
+}
+
+fn render_models_mod(resources: &[Resource], builders: bool, enums: bool, flat: bool) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    if builders {
+        buffer.push_str(MISSING_FIELD_ERROR);
+        buffer.push('\n');
+    }
+    if enums {
+        buffer.push_str(&submodule_decl("enums", flat.then_some("")));
+    }
+    for resource in resources {
+        let module = resource.name.to_case(Case::Snake);
+        buffer.push_str(&submodule_decl(&module, flat.then_some("_model")));
+    }
+    if flat {
+        buffer.push_str("#[path = \"models_prelude.rs\"]\n");
+    }
+    buffer.push_str("pub mod prelude;\n");
+    buffer.push('\n');
+    if enums {
+        buffer.push_str("pub use enums::*;\n");
+    }
+    for resource in resources {
+        let module = resource.name.to_case(Case::Snake);
+        writeln!(buffer, "pub use {}::*;", module).unwrap();
+    }
+    buffer
+}
+
+/// `models/prelude.rs`: every model struct and enum, sorted, for `use models::prelude::*`.
+fn render_models_prelude(resources: &[Resource], enums: &[EnumDecl]) -> String {
+    let mut names: Vec<(&str, bool)> = resources
+        .iter()
+        .filter(|resource| resource.model.is_some())
+        .map(|resource| (resource.name.as_str(), resource.deprecated.is_some()))
+        .chain(enums.iter().map(|decl| (decl.name.as_str(), false)))
+        .collect();
+    names.sort_unstable();
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    for (name, deprecated) in names {
+        if deprecated {
+            buffer.push_str("#[allow(deprecated)]\n");
+        }
+        writeln!(buffer, "pub use super::{};", name).unwrap();
+    }
+    buffer
+}
+
+/// Shared by every generated enum's `FromStr`; lives in `models/enums.rs`.
+const UNKNOWN_VARIANT_ERROR: &str = r#"/// Returned by an enum's `FromStr` when the string isn't one of its wire values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownVariant {
+    pub enum_name: &'static str,
+    pub value: String,
+}
+
+impl std::fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown {} `{}`", self.enum_name, self.value)
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
+"#;
+
+/// `models/enums.rs`: one Rust enum per declaration, serialized as the variant as written, with
+/// `Display`/`FromStr` over the same wire strings and a generated round-trip test. With
+/// `active_enum` (explicit entities) each also derives sea_orm's `ActiveEnum` as a string column.
+fn render_rust_enums(enums: &[EnumDecl], active_enum: bool) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    if active_enum {
+        buffer.push_str("use sea_orm::entity::prelude::*;\n");
+    }
+    buffer.push_str("use serde::{Deserialize, Serialize};\n\n");
+    buffer.push_str(UNKNOWN_VARIANT_ERROR);
+
+    for decl in enums {
+        let variants: Vec<(String, &String)> = decl
+            .variants
+            .iter()
+            .map(|variant| (variant.to_case(Case::Pascal), variant))
+            .collect();
+        buffer.push('\n');
+        if active_enum {
+            buffer.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, DeriveActiveEnum)]\n");
+            buffer.push_str(
+                "#[sea_orm(rs_type = \"String\", db_type = \"String(StringLen::None)\")]\n",
+            );
+        } else {
+            buffer.push_str(
+                "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n",
+            );
+        }
+        writeln!(buffer, "pub enum {} {{", decl.name).unwrap();
+        for (rust_name, wire) in &variants {
+            writeln!(buffer, "    #[serde(rename = {:?})]", wire).unwrap();
+            if active_enum {
+                writeln!(buffer, "    #[sea_orm(string_value = {:?})]", wire).unwrap();
+            }
+            writeln!(buffer, "    {},", rust_name).unwrap();
+        }
+        buffer.push_str("}\n\n");
+
+        writeln!(buffer, "impl {} {{", decl.name).unwrap();
+        writeln!(
+            buffer,
+            "    /// Every variant, in declaration order.\n    pub const ALL: &[{}] = &[{}];\n",
+            decl.name,
+            variants
+                .iter()
+                .map(|(rust_name, _)| format!("Self::{}", rust_name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap();
+        buffer.push_str("    /// The wire string, as used by serde and `Display`.\n");
+        buffer.push_str("    pub fn as_str(&self) -> &'static str {\n        match self {\n");
+        for (rust_name, wire) in &variants {
+            writeln!(buffer, "            Self::{} => {:?},", rust_name, wire).unwrap();
+        }
+        buffer.push_str("        }\n    }\n}\n\n");
+
+        writeln!(
+            buffer,
+            "impl std::fmt::Display for {} {{\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n        f.write_str(self.as_str())\n    }}\n}}\n",
+            decl.name
+        )
+        .unwrap();
+
+        writeln!(buffer, "impl std::str::FromStr for {} {{", decl.name).unwrap();
+        buffer.push_str("    type Err = UnknownVariant;\n\n");
+        buffer.push_str("    fn from_str(value: &str) -> Result<Self, Self::Err> {\n");
+        buffer.push_str("        match value {\n");
+        for (rust_name, wire) in &variants {
+            writeln!(buffer, "            {:?} => Ok(Self::{}),", wire, rust_name).unwrap();
+        }
+        writeln!(
+            buffer,
+            "            _ => Err(UnknownVariant {{\n                enum_name: {:?},\n                value: value.to_owned(),\n            }}),",
+            decl.name
+        )
+        .unwrap();
+        buffer.push_str("        }\n    }\n}\n");
+    }
+
+    buffer.push_str("\n#[cfg(test)]\nmod tests {\n    use super::*;\n");
+    for decl in enums {
+        writeln!(
+            buffer,
+            "\n    #[test]\n    fn {}_round_trips_through_strings() {{\n        for value in {}::ALL {{\n            assert_eq!(value.to_string().parse::<{}>(), Ok(*value));\n        }}\n        assert!(\"\".parse::<{}>().is_err());\n    }}",
+            decl.name.to_case(Case::Snake),
+            decl.name,
+            decl.name,
+            decl.name
+        )
+        .unwrap();
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+fn render_controllers_mod(resources: &[Resource], flat: bool) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    for resource in resources {
+        let module = resource.name.to_case(Case::Snake);
+        buffer.push_str(&submodule_decl(&module, flat.then_some("_controller")));
+    }
+    buffer.push('\n');
+    for resource in resources {
+        let module = resource.name.to_case(Case::Snake);
+        writeln!(buffer, "pub use {}::*;", module).unwrap();
+    }
+    buffer.push_str("\nuse loco_rs::prelude::Routes;\n\n");
+    buffer.push_str(
+        "/// Every generated controller's routes, ordered by resource name, for `Hooks::routes` to\n/// `add_route` one by one.\n",
+    );
+    buffer.push_str("pub fn routes() -> Vec<Routes> {\n    vec![\n");
+    for resource in resources {
+        writeln!(
+            buffer,
+            "        {}::routes(),",
+            resource.name.to_case(Case::Snake)
+        )
+        .unwrap();
+    }
+    buffer.push_str("    ]\n}\n");
+    buffer
+}
+
+/// Whether `action` answers HTML requests by rendering one of the resource's views: the
+/// resource has a model and declares a controller listing `html` for the action. Resources
+/// without a `controller` block respond with the default formats but get no views.
+fn renders_html(resource: &Resource, controller: &Controller, action: &str) -> bool {
+    resource.model.is_some()
+        && resource.controller.is_some()
+        && resolve_actions(controller)
+            .iter()
+            .any(|spec| spec.action_name == action)
+        && controller
+            .formats_for(action)
+            .iter()
+            .any(|format| format == "html")
+}
+
+/// `GET /new` renders the create form when `create` is rendered as HTML.
+fn renders_new_form(resource: &Resource, controller: &Controller) -> bool {
+    renders_html(resource, controller, "create")
+}
+
+/// `GET /{id}/edit` renders the edit form when `update` (or `partial_update`) is.
+fn renders_edit_form(resource: &Resource, controller: &Controller) -> bool {
+    renders_html(resource, controller, "update")
+        || renders_html(resource, controller, "partial_update")
+}
+
+fn has_views(resource: &Resource, controller: &Controller) -> bool {
+    renders_html(resource, controller, "index")
+        || renders_html(resource, controller, "show")
+        || renders_new_form(resource, controller)
+        || renders_edit_form(resource, controller)
+}
+
+fn views_dir(resource: &Resource) -> String {
+    pluralize(&resource.name).to_case(Case::Snake)
+}
+
+fn render_views_mod(resources: &[&Resource]) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    for resource in resources {
+        writeln!(buffer, "pub mod {};", resource.name.to_case(Case::Snake)).unwrap();
+    }
+    buffer
+}
+
+/// Locale the generated views pass to `labels()` when any field has a `@label`.
+const VIEWS_DEFAULT_LOCALE: &str = "en";
+
+/// Fields the view templates show: the serialized ones, minus feature-gated fields, which may
+/// not be compiled in.
+fn view_fields(model: &Model) -> Vec<&Field> {
+    model
+        .fields
+        .iter()
+        .filter(|field| {
+            field.attributes.serialize != Some(false)
+                && !field.attributes.encrypted
+                && field.attributes.feature.is_none()
+        })
+        .collect()
+}
+
+fn has_label(field: &Field) -> bool {
+    field.attributes.label.is_some() || !field.attributes.labels.is_empty()
+}
+
+/// `src/views/<resource>.rs`: loco view functions rendering the templates under
+/// `assets/views/<resources>/`, in the shape `loco generate scaffold --html` produces.
+fn render_views(resource: &Resource, model: &Model) -> String {
+    let dir = views_dir(resource);
+    let name = &resource.name;
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}// source: {}",
+        GENERATED_HEADER, resource.file_path
+    )
+    .unwrap();
+    buffer.push('\n');
+    if resource.deprecated.is_some() {
+        buffer.push_str("#![allow(deprecated)]\n\n");
+    }
+    buffer.push_str("use loco_rs::prelude::*;\n\n");
+    writeln!(buffer, "use crate::models::{};\n", name).unwrap();
+    let labeled: Vec<&Field> = view_fields(model)
+        .into_iter()
+        .filter(|field| has_label(field))
+        .collect();
+    let data = |entries: &[&str]| {
+        let mut entries = entries.to_vec();
+        if !labeled.is_empty() {
+            entries.push("\"labels\": labels(DEFAULT_LOCALE)");
+        }
+        format!("{{{}}}", entries.join(", "))
+    };
+    let views = [
+        (
+            format!("Render a list view of `{}`.", dir),
+            format!("list(v: &impl ViewRenderer, items: &[{}])", name),
+            "list",
+            data(&["\"items\": items"]),
+        ),
+        (
+            format!("Render a single `{}` view.", dir),
+            format!("show(v: &impl ViewRenderer, item: &{})", name),
+            "show",
+            data(&["\"item\": item"]),
+        ),
+        (
+            format!("Render a `{}` create form.", dir),
+            "create(v: &impl ViewRenderer)".to_owned(),
+            "create",
+            data(&[]),
+        ),
+        (
+            format!("Render a `{}` edit form.", dir),
+            format!("edit(v: &impl ViewRenderer, item: &{})", name),
+            "edit",
+            data(&["\"item\": item"]),
+        ),
+    ];
+    for (index, (doc, signature, template, data)) in views.iter().enumerate() {
+        if index > 0 {
+            buffer.push('\n');
+        }
+        writeln!(
+            buffer,
+            "/// {}\n///\n/// # Errors\n///\n/// When there is an issue with rendering the view.\npub fn {} -> Result<Response> {{\n    format::render().view(v, \"{}/{}.html\", data!({}))\n}}",
+            doc, signature, dir, template, data
+        )
+        .unwrap();
+    }
+    if !labeled.is_empty() {
+        buffer.push('\n');
+        buffer.push_str(&render_view_labels(&labeled));
+    }
+    buffer
+}
+
+/// `DEFAULT_LOCALE` and `labels(locale)`, the map the templates read `@label` text from. A
+/// locale a field has no label for falls back to its unlocalized `@label`, then its name.
+fn render_view_labels(fields: &[&Field]) -> String {
+    let locales: BTreeSet<&str> = fields
+        .iter()
+        .flat_map(|field| field.attributes.labels.keys().map(String::as_str))
+        .collect();
+    let map = |locale: Option<&str>, indent: &str| {
+        let entries: Vec<String> = fields
+            .iter()
+            .map(|field| {
+                let text = locale
+                    .and_then(|locale| field.attributes.labels.get(locale))
+                    .or(field.attributes.label.as_ref())
+                    .unwrap_or(&field.name);
+                format!("{}    {:?}: {:?}", indent, field.name, text)
+            })
+            .collect();
+        format!("data!({{\n{}\n{}}})", entries.join(",\n"), indent)
+    };
+
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "/// Locale the views above render labels in; see [`labels`].\npub const DEFAULT_LOCALE: &str = {:?};\n",
+        VIEWS_DEFAULT_LOCALE
+    )
+    .unwrap();
+    buffer.push_str(
+        "/// Field labels for `locale`, keyed by field name, for the templates' `labels` map.\n",
+    );
+    buffer.push_str("#[must_use]\n");
+    if locales.is_empty() {
+        buffer.push_str("pub fn labels(_locale: &str) -> serde_json::Value {\n");
+        writeln!(buffer, "    {}", map(None, "    ")).unwrap();
+    } else {
+        buffer.push_str("pub fn labels(locale: &str) -> serde_json::Value {\n");
+        buffer.push_str("    match locale {\n");
+        for locale in &locales {
+            writeln!(
+                buffer,
+                "        {:?} => {},",
+                locale,
+                map(Some(locale), "        ")
+            )
+            .unwrap();
+        }
+        writeln!(buffer, "        _ => {},", map(None, "        ")).unwrap();
+        buffer.push_str("    }\n");
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// Stub Tera templates for [`render_views`], extending the app's `base.html`. They list the
+/// serialized model fields; feature-gated ones are left out since they may not be compiled in.
+fn render_view_templates(
+    resource: &Resource,
+    model: &Model,
+    json_case: JsonCase,
+) -> Vec<GeneratedFile> {
+    let dir = views_dir(resource);
+    let fields = view_fields(model);
+    let editable: Vec<&Field> = fields
+        .iter()
+        .copied()
+        .filter(|field| field.attributes.stored() && field.name != "id")
+        .collect();
+    let key = |field: &Field| json_case.key(&field.name);
+    let label = |field: &Field| {
+        if has_label(field) {
+            format!("{{{{ labels.{} }}}}", field.name)
+        } else {
+            field.name.clone()
+        }
+    };
+    let value = |field: &Field| {
+        if field.optional {
+            format!("{{{{ item.{} | default(value=\"\") }}}}", key(field))
+        } else {
+            format!("{{{{ item.{} }}}}", key(field))
+        }
+    };
+    let page = |title: String, content: String| {
+        format!(
+            "{{# @generated by via-core. Delete this line to keep your edits when regenerating. #}}\n{{% extends \"base.html\" %}}\n\n{{% block title %}}\n{}\n{{% endblock title %}}\n\n{{% block content %}}\n{}{{% endblock content %}}\n",
+            title, content
+        )
+    };
+
+    let mut list = String::from("<table>\n  <thead>\n    <tr>\n");
+    for field in &fields {
+        writeln!(list, "      <th>{}</th>", label(field)).unwrap();
+    }
+    list.push_str("      <th></th>\n    </tr>\n  </thead>\n  <tbody>\n");
+    list.push_str("    {% for item in items %}\n    <tr>\n");
+    for field in &fields {
+        writeln!(list, "      <td>{}</td>", value(field)).unwrap();
+    }
+    writeln!(
+        list,
+        "      <td>{{% if item.id is defined %}}<a href=\"/{}/{{{{ item.id }}}}\">Show</a> <a href=\"/{}/{{{{ item.id }}}}/edit\">Edit</a>{{% endif %}}</td>",
+        dir, dir
+    )
+    .unwrap();
+    list.push_str("    </tr>\n    {% endfor %}\n  </tbody>\n</table>\n");
+    writeln!(list, "<a href=\"/{}/new\">Create</a>", dir).unwrap();
+
+    // Links need the record's `id`, which the model only serializes if it declares one.
+    let mut show = String::from("<dl>\n");
+    for field in &fields {
+        writeln!(
+            show,
+            "  <dt>{}</dt>\n  <dd>{}</dd>",
+            label(field),
+            value(field)
+        )
+        .unwrap();
+    }
+    show.push_str("</dl>\n");
+    writeln!(
+        show,
+        "{{% if item.id is defined %}}<a href=\"/{}/{{{{ item.id }}}}/edit\">Edit</a> {{% endif %}}<a href=\"/{}\">Back</a>",
+        dir, dir
+    )
+    .unwrap();
+
+    // The create/update handlers take JSON bodies, so the forms only lay out the inputs.
+    let form = |action: String, method: &str, item: bool| {
+        let mut form = format!(
+            "{{# TODO: submit as JSON ({} {}); the handler doesn't accept form posts. #}}\n<form>\n",
+            method, action
+        );
+        for field in &editable {
+            let required = if field.optional { "" } else { " required" };
+            if ViaScalar::from_name(&field.ty.name) == Some(ViaScalar::Text) {
+                let current = if item { value(field) } else { String::new() };
+                writeln!(
+                    form,
+                    "  <label>{}\n    <textarea name=\"{}\"{}>{}</textarea>\n  </label>",
+                    label(field),
+                    key(field),
+                    required,
+                    current
+                )
+                .unwrap();
+                continue;
+            }
+            let input = match ViaScalar::from_name(&field.ty.name) {
+                Some(ViaScalar::Boolean) => "checkbox",
+                Some(ViaScalar::Integer | ViaScalar::BigInt) => "number",
+                Some(ViaScalar::Float | ViaScalar::Decimal) => "number\" step=\"any",
+                Some(ViaScalar::Date) => "date",
+                _ => "text",
+            };
+            let checkbox = input == "checkbox";
+            let current = match (item, checkbox) {
+                (false, _) => String::new(),
+                (true, true) => format!("{{% if item.{} %}} checked{{% endif %}}", key(field)),
+                (true, false) => format!(" value=\"{}\"", value(field)),
+            };
+            // An unchecked box is a valid `false`, so checkboxes are never `required`.
+            let required = if checkbox { "" } else { required };
+            writeln!(
+                form,
+                "  <label>{}\n    <input type=\"{}\" name=\"{}\"{}{}>\n  </label>",
+                label(field),
+                input,
+                key(field),
+                current,
+                required
+            )
+            .unwrap();
+        }
+        form.push_str("  <button type=\"submit\">Save</button>\n</form>\n");
+        writeln!(form, "<a href=\"/{}\">Back</a>", dir).unwrap();
+        form
+    };
+
+    [
+        ("list", format!("List of {}", dir), list),
+        ("show", format!("View {}", resource.name), show),
+        (
+            "create",
+            format!("Create {}", resource.name),
+            form(format!("/{}", dir), "POST", false),
+        ),
+        (
+            "edit",
+            format!("Edit {}", resource.name),
+            form(format!("/{}/{{{{ item.id }}}}", dir), "PATCH", true),
+        ),
+    ]
+    .into_iter()
+    .map(|(template, title, content)| GeneratedFile {
+        relative_path: PathBuf::from(format!("assets/views/{}/{}.html", dir, template)),
+        contents: page(title, content),
+    })
+    .collect()
+}
+
+fn render_root_lib(
+    encryption: bool,
+    entities: bool,
+    introspection: bool,
+    tasks: bool,
+    views: bool,
+    models_dir: &Path,
+    controllers_dir: &Path,
+) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    buffer.push_str(&render_mod_decl("controllers", controllers_dir));
+    if encryption {
+        buffer.push_str("pub mod encryption;\n");
+    }
+    if entities {
+        buffer.push_str("pub mod entities;\n");
+    }
+    if introspection {
+        buffer.push_str("#[cfg(feature = \"introspection\")]\npub mod introspection;\n");
+    }
+    buffer.push_str(&render_mod_decl("models", models_dir));
+    if tasks {
+        buffer.push_str("pub mod tasks;\n");
+    }
+    if views {
+        buffer.push_str("pub mod views;\n");
+    }
+    buffer
+}
+
+/// Stored columns that can't be null, and so need values for existing rows when a migration
+/// adds them: required, stored (not computed or transient) model fields other than the primary
+/// key, without a default to fill them.
+fn backfill_columns(resource: &Resource) -> Vec<&Field> {
+    resource
+        .model
+        .iter()
+        .flat_map(|model| &model.fields)
+        .filter(|field| {
+            !field.optional
+                && field.attributes.stored()
+                && field.attributes.default.is_none()
+                && field.name != "id"
+        })
+        .collect()
+}
+
+fn backfill_task_name(resource: &Resource) -> String {
+    format!("backfill_{}", resource.table_name().to_case(Case::Snake))
+}
+
+/// A loco `Task` to fill required columns on existing rows, with its `run` body in a manual
+/// region so the backfill written there survives regeneration.
+fn render_backfill_task(resource: &Resource) -> String {
+    let table = resource.table_name();
+    let name = backfill_task_name(resource);
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "{}// source: {}",
+        GENERATED_HEADER, resource.file_path
+    )
+    .unwrap();
+    buffer.push_str("\nuse loco_rs::prelude::*;\n\n");
+    writeln!(
+        buffer,
+        "/// Fills required columns of `{}` on existing rows. When a migration adds one of these\n\
+         /// to a populated table, add it as nullable, run `cargo loco task {}`, then\n\
+         /// make it NOT NULL.\n\
+         ///\n\
+         /// Required columns:",
+        table, name
+    )
+    .unwrap();
+    for field in backfill_columns(resource) {
+        let cfg = field
+            .attributes
+            .feature
+            .as_ref()
+            .map(|feature| format!(" (feature `{}`)", feature))
+            .unwrap_or_default();
+        writeln!(buffer, "/// - `{}`: {}{}", field.name, field.ty.name, cfg).unwrap();
+    }
+    let struct_name = name.to_case(Case::Pascal);
+    writeln!(buffer, "pub struct {};\n", struct_name).unwrap();
+    buffer.push_str("#[async_trait]\n");
+    writeln!(buffer, "impl Task for {} {{", struct_name).unwrap();
+    buffer.push_str("    fn task(&self) -> TaskInfo {\n        TaskInfo {\n");
+    writeln!(buffer, "            name: {:?}.to_string(),", name).unwrap();
+    writeln!(
+        buffer,
+        "            detail: {:?}.to_string(),",
+        format!("Backfill required columns of `{}`", table)
+    )
+    .unwrap();
+    buffer.push_str("        }\n    }\n\n");
+    buffer.push_str("    // Code between `via:manual` markers is kept on regeneration.\n");
+    writeln!(buffer, "    {}{}", writer::MANUAL_BEGIN, name).unwrap();
+    buffer.push_str(
+        "    async fn run(&self, _ctx: &AppContext, _vars: &task::Vars) -> Result<()> {\n",
+    );
+    writeln!(
+        buffer,
+        "        // TODO: set the new column on existing `{}` rows, in batches for large tables",
+        table
+    )
+    .unwrap();
+    buffer.push_str("        Ok(())\n    }\n");
+    writeln!(buffer, "    {}{}", writer::MANUAL_END, name).unwrap();
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// `tasks::register`, to call from the app's `Hooks::register_tasks`.
+fn render_tasks_mod(resources: &[&Resource]) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    for resource in resources {
+        writeln!(buffer, "pub mod {};", backfill_task_name(resource)).unwrap();
+    }
+    buffer.push_str("\nuse loco_rs::task::Tasks;\n\n");
+    buffer.push_str(
+        "/// Registers every generated backfill task; call from `Hooks::register_tasks`.\n",
+    );
+    buffer.push_str("pub fn register(tasks: &mut Tasks) {\n");
+    for resource in resources {
+        let name = backfill_task_name(resource);
+        writeln!(
+            buffer,
+            "    tasks.register({}::{});",
+            name,
+            name.to_case(Case::Pascal)
+        )
+        .unwrap();
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// `GET /_via/resources` serving the resources from the IR (the same JSON as `via.ir.json`),
+/// embedded as a raw string so a running binary can report the schema it was built from.
+fn render_introspection(program: &Program) -> Result<String> {
+    let ir = ir::to_json(program)?;
+    // Enough `#`s that no `"#...` run inside the JSON closes the raw string early.
+    let mut hashes = String::from("#");
+    while ir.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push_str(
+        r#"
+//! Introspection endpoint listing the resources this crate was generated from. Compiled only
+//! with the `introspection` feature (on by default); build with `--no-default-features` to leave
+//! it out of production binaries.
+
+use loco_rs::prelude::*;
+
+"#,
+    );
+    writeln!(
+        buffer,
+        "/// The serialized IR (`{}`) this crate was generated from.",
+        IR_FILE_NAME
+    )
+    .unwrap();
+    writeln!(
+        buffer,
+        "pub const IR_JSON: &str = r{hashes}\"{ir}\"{hashes};\n",
+        hashes = hashes,
+        ir = ir
+    )
+    .unwrap();
+    buffer.push_str(
+        r#"pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("_via/")
+        .add("/resources", get(resources))
+}
+
+pub async fn resources(State(_ctx): State<AppContext>) -> Result<Response> {
+    let ir: serde_json::Value = serde_json::from_str(IR_JSON)?;
+    format::json(ir["resources"].clone())
+}
+"#,
+    );
+    Ok(buffer)
+}
+
+/// `pub mod <name>;`, with a `#[path]` pointing at `<dir>/mod.rs` when the module doesn't live
+/// in the default `src/<name>` (paths are relative to `src/lib.rs`).
+fn render_mod_decl(name: &str, dir: &Path) -> String {
+    if dir == Path::new("src").join(name) {
+        return format!("pub mod {};\n", name);
+    }
+    let dir = dir.to_string_lossy().replace('\\', "/");
+    let relative = match dir.strip_prefix("src/") {
+        Some(inside_src) => inside_src.to_owned(),
+        None => format!("../{}", dir),
+    };
+    format!("#[path = \"{}/mod.rs\"]\npub mod {};\n", relative, name)
+}
+
+fn uses_encryption(resources: &[Resource]) -> bool {
+    resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .any(|field| field.attributes.encrypted)
+}
+
+const ENCRYPTION_MODULE: &str = r#"// @generated by via-core. DO NOT EDIT BY HAND.
+
+//! AES-256-GCM helpers backing `@encrypted` fields. Ciphertexts are base64 of `nonce || sealed`.
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+
+/// Environment variable [`EncryptionKey::from_env`] reads: a base64-encoded 32-byte key.
+pub const KEY_ENV: &str = "VIA_ENCRYPTION_KEY";
+
+#[derive(Debug)]
+pub struct EncryptionError(pub &'static str);
+
+impl std::fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "encryption error: {}", self.0)
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+pub struct EncryptionKey(LessSafeKey);
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EncryptionError> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| EncryptionError("key must be 32 bytes"))?;
+        Ok(Self(LessSafeKey::new(key)))
+    }
+
+    /// Loads the key from `VIA_ENCRYPTION_KEY`. Apps keeping secrets in loco config can read
+    /// the setting themselves and call [`EncryptionKey::from_bytes`].
+    pub fn from_env() -> Result<Self, EncryptionError> {
+        let encoded =
+            std::env::var(KEY_ENV).map_err(|_| EncryptionError("VIA_ENCRYPTION_KEY is not set"))?;
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|_| EncryptionError("VIA_ENCRYPTION_KEY is not valid base64"))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, EncryptionError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| EncryptionError("failed to generate a nonce"))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.0
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| EncryptionError("failed to encrypt"))?;
+        let mut bytes = nonce.to_vec();
+        bytes.extend(sealed);
+        Ok(STANDARD.encode(bytes))
+    }
+
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, EncryptionError> {
+        let bytes = STANDARD
+            .decode(ciphertext)
+            .map_err(|_| EncryptionError("ciphertext is not valid base64"))?;
+        if bytes.len() < NONCE_LEN {
+            return Err(EncryptionError("ciphertext is too short"));
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| EncryptionError("invalid nonce"))?;
+        let mut sealed = sealed.to_vec();
+        let plaintext = self
+            .0
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| EncryptionError("failed to decrypt (wrong key or tampered value)"))?;
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|_| EncryptionError("decrypted value is not UTF-8"))
+    }
+}
+"#;
+
+/// Whether any action of `controller` (after per-action overrides) can answer with XML.
+fn responds_with_xml(controller: &Controller) -> bool {
+    resolve_actions(controller).iter().any(|action| {
+        controller
+            .formats_for(&action.action_name)
+            .iter()
+            .any(|format| format == "xml")
+    })
+}
+
+fn render_manifest(
+    resources: &[Resource],
+    tracing: bool,
+    errors: bool,
+    entities: bool,
+    seaography: bool,
+    introspection: bool,
+) -> String {
+    let uses_validator = resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .any(|field| field.attributes.validated())
+        || resources
+            .iter()
+            .any(|resource| !resource.validations.is_empty());
+
+    let mut buffer = String::new();
+    buffer.push_str("# @generated by via-core. DO NOT EDIT BY HAND.\n");
+    buffer.push_str("\n[package]\n");
+    buffer.push_str("name = \"via-generated\"\n");
+    buffer.push_str("version = \"0.1.0\"\n");
+    buffer.push_str("edition = \"2021\"\n");
+    buffer.push_str("publish = false\n\n");
+    buffer.push_str("[lib]\n");
+    buffer.push_str("path = \"src/lib.rs\"\n\n");
+    buffer.push_str("[dependencies]\n");
+    buffer.push_str("loco-rs = { version = \"0.16\" }\n");
+    buffer.push_str("serde = { version = \"1\", features = [\"derive\"] }\n");
+    buffer.push_str("serde_json = { version = \"1\" }\n");
+    if uses_validator {
+        buffer.push_str("validator = { version = \"0.20\", features = [\"derive\"] }\n");
+    }
+    if tracing {
+        buffer.push_str("tracing = { version = \"0.1\" }\n");
+    }
+    if errors {
+        buffer.push_str("axum = { version = \"0.8\" }\n");
+    }
+    if entities {
+        buffer.push_str("sea-orm = { version = \"1.1\", default-features = false, features = [\"macros\", \"with-json\", \"with-chrono\", \"with-rust_decimal\", \"with-uuid\"] }\n");
+    }
+    if seaography {
+        buffer.push_str("seaography = { version = \"1.1\", optional = true }\n");
+    }
+    let formats: BTreeSet<&str> = resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .filter_map(|field| field.attributes.format.as_deref())
+        .collect();
+    let now_default = resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .any(|field| field.attributes.default == Some(Literal::Now));
+    if formats.contains("date-time") || now_default {
+        buffer.push_str("chrono = { version = \"0.4\" }\n");
+    }
+    if formats.contains("uuid") {
+        buffer.push_str("uuid = { version = \"1\" }\n");
+    }
+    if uses_encryption(resources) {
+        buffer.push_str("base64 = { version = \"0.22\" }\n");
+        buffer.push_str("ring = { version = \"0.17\" }\n");
+    }
+    let uses_xml = resources
+        .iter()
+        .filter_map(|resource| resource.controller.as_ref())
+        .any(responds_with_xml);
+    if uses_xml {
+        buffer.push_str("quick-xml = { version = \"0.38\", features = [\"serialize\"] }\n");
+    }
+    let mut features: BTreeSet<&str> = resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .filter_map(|field| field.attributes.feature.as_deref())
+        .collect();
+    if introspection {
+        features.insert("introspection");
+    }
+    if seaography {
+        features.insert("seaography");
+    }
+    if !features.is_empty() {
+        buffer.push_str("\n[features]\n");
+        if introspection {
+            buffer.push_str("default = [\"introspection\"]\n");
+        }
+        for feature in features {
+            if feature == "seaography" {
+                buffer.push_str("seaography = [\"dep:seaography\", \"sea-orm/seaography\"]\n");
+            } else {
+                writeln!(buffer, "{} = []", feature).unwrap();
+            }
+        }
+    }
+    buffer
+}
+
+/// How an action stub answers, beyond its placeholder JSON body.
+struct StubOptions {
+    /// Instrument the handler with a `tracing` span.
+    tracing: bool,
+    /// Serialize the body as XML for clients that ask for it.
+    xml: bool,
+    /// Render the resource's view for HTML clients.
+    html: bool,
+    /// Call the action's `can_<action>` guard first.
+    guarded: bool,
+    /// Return the controller's `<Resource>Error` instead of loco's error.
+    errors: bool,
+    /// Key spelling of the placeholder page body.
+    json_case: JsonCase,
+}
+
+/// A handler listed in `actions { ... }`: its body as written, or `todo!()` when it has none,
+/// behind the authorization guard when the action has one. A written body keeps loco's
+/// `Result<Response>` even under `--gen-errors`, since that's what `format::json` and friends
+/// return.
+fn render_manual_action(
+    resource: &Resource,
+    action: &ActionSpec,
+    guarded: bool,
+    errors: bool,
+) -> String {
+    let uses_ctx = guarded || action.body.is_some();
+    let return_type = if errors && action.body.is_none() {
+        format!("Result<Response, {}Error>", resource.name)
+    } else {
+        "Result<Response>".to_owned()
+    };
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "pub async fn {}(State({}ctx): State<AppContext>) -> {} {{",
+        action.handler_name,
+        if uses_ctx { "" } else { "_" },
+        return_type
+    )
+    .unwrap();
+    if guarded {
+        writeln!(buffer, "    can_{}(&ctx, None)?;", action.handler_name).unwrap();
+    }
+    match &action.body {
+        Some(body) => {
+            for line in dedent(body) {
+                if line.is_empty() {
+                    buffer.push('\n');
+                } else {
+                    writeln!(buffer, "    {}", line).unwrap();
+                }
+            }
+        }
+        None => writeln!(
+            buffer,
+            "    todo!(\"{}#{}\")",
+            resource.name, action.action_name
+        )
+        .unwrap(),
+    }
+    buffer.push_str("}\n\n");
+    buffer
+}
+
+/// `text`'s lines without leading and trailing blank lines or the indentation they all share.
+fn dedent(text: &str) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return Vec::new();
+    };
+    let lines = &lines[first..=last];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect()
+}
+
+fn render_action_stub(
+    resource: &Resource,
+    action: &ActionSpec,
+    param_structs: &[ParamStruct],
+    options: &StubOptions,
+) -> String {
+    let &StubOptions {
+        tracing,
+        xml,
+        html,
+        guarded,
+        errors,
+        json_case,
+    } = options;
+    let message = format!("{}#{}", resource.name, action.action_name);
+    let todo = if scoped_action(resource, &action.action_name) {
+        format!("\"todo\": \"{}\", \"scope\": DEFAULT_SCOPE", message)
+    } else {
+        format!("\"todo\": \"{}\"", message)
+    };
+    let params_struct = |suffix: &str| {
+        find_param_struct_name(param_structs, &format!("{}{}", resource.name, suffix))
+            .unwrap_or_else(|| "serde_json::Value".to_owned())
+    };
+    let (extractors, payload, has_id) = match action.action_name.as_str() {
+        "show" | "destroy" => (
+            vec!["Path(id): Path<String>".to_owned()],
+            format!("json!({{{}, \"id\": id}})", todo),
+            true,
+        ),
+        "create" => (
+            vec![format!(
+                "Json(payload): Json<{}>",
+                params_struct("CreateParams")
+            )],
+            format!("json!({{{}, \"payload\": payload}})", todo),
+            false,
+        ),
+        // PUT replaces the whole record, so it takes the full (create-shaped) body; PATCH
+        // (`partial_update`) takes the all-optional update params.
+        "update" | "partial_update" => (
+            vec![
+                "Path(id): Path<String>".to_owned(),
+                format!(
+                    "Json(payload): Json<{}>",
+                    params_struct(if action.action_name == "update" {
+                        "CreateParams"
+                    } else {
+                        "UpdateParams"
+                    })
+                ),
+            ],
+            format!("json!({{{}, \"id\": id, \"payload\": payload}})", todo),
+            true,
+        ),
+        "index" if resource.cursor_paginated() => (
+            vec!["Query(query): Query<IndexQuery>".to_owned()],
+            format!(
+                "json!({{{}, \"order\": CURSOR_ORDER, \"after\": query.after, \"limit\": limit, \"items\": [], \"{}\": null}})",
+                todo,
+                json_case.key("next_cursor")
+            ),
+            false,
+        ),
+        "index" if resource.default_order.is_some() => (
+            vec!["Query(query): Query<IndexQuery>".to_owned()],
+            format!("json!({{{}, \"order\": [order_by, direction]}})", todo),
+            false,
+        ),
+        _ => (Vec::new(), format!("json!({{{}}})", todo), false),
+    };
+
+    let mut arguments = vec![if guarded {
+        "State(ctx): State<AppContext>".to_owned()
+    } else {
+        "State(_ctx): State<AppContext>".to_owned()
+    }];
+    if html {
+        arguments.push("ViewEngine(v): ViewEngine<TeraView>".to_owned());
+    }
+    if xml || html {
+        arguments.push("Format(respond_to): Format".to_owned());
+    }
+    arguments.extend(extractors);
+    let mut body = Vec::new();
+    if guarded {
+        let id_arg = if has_id { "Some(id.as_str())" } else { "None" };
+        body.push(format!("can_{}(&ctx, {})?;", action.handler_name, id_arg));
+    }
+    if html {
+        let views = format!("views::{}", resource.name.to_case(Case::Snake));
+        body.push("if matches!(respond_to, RespondTo::Html) {".to_owned());
+        if action.action_name == "index" && errors {
+            body.push(format!("    return Ok({}::list(&v, &[])?);", views));
+        } else if action.action_name == "index" {
+            body.push(format!("    return {}::list(&v, &[]);", views));
+        } else if errors {
+            body.extend(render_record_view_or_error(resource, &views));
+        } else {
+            body.extend(render_record_view(resource, &views, "show", "return "));
+        }
+        body.push("}".to_owned());
+    }
+    if errors
+        && let Some(param_struct) = payload_struct(resource, action, param_structs)
+        && resource
+            .model
+            .as_ref()
+            .is_some_and(|model| param_struct_validated(resource, model, param_struct))
+    {
+        body.push(format!(
+            "Validate::validate(&payload).map_err({}Error::from)?;",
+            resource.name
+        ));
+    }
+    if action.action_name == "index" && resource.cursor_paginated() {
+        body.push("let limit = page_size(&query)?;".to_owned());
+    } else if action.action_name == "index" && resource.default_order.is_some() {
+        body.push("let (order_by, direction) = index_order(&query)?;".to_owned());
+    }
+    let response = if xml {
+        let module_name = resource.name.to_case(Case::Snake);
+        let root = if action.action_name == "index" {
+            pluralize(&module_name)
+        } else {
+            module_name
+        };
+        format!("respond(respond_to, \"{}\", {})", root, payload)
+    } else {
+        format!("format::json({})", payload)
+    };
+    body.push(if errors {
+        format!("Ok({}?)", response)
+    } else {
+        response
+    });
+
+    let return_type = if errors {
+        format!("Result<Response, {}Error>", resource.name)
+    } else {
+        "Result<Response>".to_owned()
+    };
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "pub async fn {}({}) -> {} {{",
+        action.handler_name,
+        arguments.join(", "),
+        return_type
+    )
+    .unwrap();
+    if tracing {
+        let span_name = format!(
+            "{}.{}",
+            resource.name.to_case(Case::Snake),
+            action.handler_name
+        );
+        let id_field = if has_id { ", id = %id" } else { "" };
+        writeln!(
+            buffer,
+            "    let span = tracing::info_span!(\"{}\"{});",
+            span_name, id_field
+        )
+        .unwrap();
+        buffer.push_str("    async move {\n");
+        for line in &body {
+            writeln!(buffer, "        {}", line).unwrap();
+        }
+        buffer.push_str("    }\n    .instrument(span)\n    .await\n");
+    } else {
+        for line in &body {
+            writeln!(buffer, "    {}", line).unwrap();
+        }
+    }
+    buffer.push_str("}\n\n");
+    buffer
+}
+
+/// Lines rendering `view` for a record that the stub doesn't load yet, so it answers 404.
+fn render_record_view(resource: &Resource, views: &str, view: &str, prefix: &str) -> Vec<String> {
+    vec![
+        "    // TODO: load the record by `id`; until then this renders a 404.".to_owned(),
+        format!("    let item: Option<{}> = None;", resource.name),
+        format!("    {}match item {{", prefix),
+        format!("        Some(item) => {}::{}(&v, &item),", views, view),
+        "        None => not_found(),".to_owned(),
+        format!("    }}{}", if prefix.is_empty() { "" } else { ";" }),
+    ]
+}
+
+/// [`render_record_view`] for handlers returning the controller's error enum.
+fn render_record_view_or_error(resource: &Resource, views: &str) -> Vec<String> {
+    vec![
+        "    // TODO: load the record by `id`; until then this renders a 404.".to_owned(),
+        format!("    let item: Option<{}> = None;", resource.name),
+        "    return match item {".to_owned(),
+        format!("        Some(item) => Ok({}::show(&v, &item)?),", views),
+        format!("        None => Err({}Error::NotFound),", resource.name),
+        "    };".to_owned(),
+    ]
+}
+
+/// The params struct an action's JSON body deserializes into, if it has one.
+fn payload_struct<'a>(
+    resource: &Resource,
+    action: &ActionSpec,
+    param_structs: &'a [ParamStruct],
+) -> Option<&'a ParamStruct> {
+    let suffix = match action.action_name.as_str() {
+        "create" | "update" => "CreateParams",
+        "partial_update" => "UpdateParams",
+        _ => return None,
+    };
+    let name = format!("{}{}", resource.name, suffix);
+    param_structs.iter().find(|ps| ps.name == name)
+}
+
+/// `<Resource>Error`: the failures a controller's handlers answer with, each with its status and
+/// a body matching the OpenAPI `Error` schema. Anything else passes through as loco's error.
+fn render_error_enum(resource: &Resource) -> String {
+    let name = format!("{}Error", resource.name);
+    let noun = resource.name.to_case(Case::Lower);
+    let mut buffer = String::new();
+    writeln!(
+        buffer,
+        "/// Failures of the `{}` handlers. Each answers with its status and a JSON body matching\n\
+         /// the OpenAPI `Error` schema: `code`, `message`, and per-field `details` for validation.",
+        resource.name
+    )
+    .unwrap();
+    buffer.push_str("#[derive(Debug)]\n");
+    writeln!(buffer, "pub enum {} {{", name).unwrap();
+    writeln!(buffer, "    /// 404: no {} with the requested id.", noun).unwrap();
+    buffer.push_str("    NotFound,\n");
+    buffer.push_str("    /// 422: the params failed validation, as `(field, message)` pairs.\n");
+    buffer.push_str("    Validation(Vec<(String, String)>),\n");
+    buffer.push_str("    /// 500: a database call failed. Clients only see a generic message.\n");
+    buffer.push_str("    Db(DbErr),\n");
+    buffer.push_str("    /// Any other failure, answered the way loco answers it.\n");
+    buffer.push_str("    Loco(Error),\n");
+    buffer.push_str("}\n\n");
+
+    writeln!(buffer, "impl {} {{", name).unwrap();
+    buffer.push_str(
+        "    fn status(&self) -> StatusCode {\n        match self {\n            Self::NotFound => StatusCode::NOT_FOUND,\n            Self::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,\n            Self::Db(_) | Self::Loco(_) => StatusCode::INTERNAL_SERVER_ERROR,\n        }\n    }\n\n",
+    );
+    buffer.push_str(
+        "    fn code(&self) -> &'static str {\n        match self {\n            Self::NotFound => \"not_found\",\n            Self::Validation(_) => \"validation_failed\",\n            Self::Db(_) | Self::Loco(_) => \"internal_error\",\n        }\n    }\n\n",
+    );
+    writeln!(
+        buffer,
+        "    fn message(&self) -> &'static str {{\n        match self {{\n            Self::NotFound => \"{} not found\",\n            Self::Validation(_) => \"{} params failed validation\",\n            Self::Db(_) | Self::Loco(_) => \"Internal server error\",\n        }}\n    }}\n",
+        resource.name, resource.name
+    )
+    .unwrap();
+    buffer.push_str(
+        "    /// Messages keyed by field name, for `Validation` only.\n    fn details(&self) -> Option<serde_json::Value> {\n        let Self::Validation(errors) = self else {\n            return None;\n        };\n        let mut details = serde_json::Map::new();\n        for (field, message) in errors {\n            if let Some(messages) = details\n                .entry(field.clone())\n                .or_insert_with(|| json!([]))\n                .as_array_mut()\n            {\n                messages.push(json!(message));\n            }\n        }\n        Some(serde_json::Value::Object(details))\n    }\n}\n\n",
+    );
+
+    writeln!(buffer, "impl IntoResponse for {} {{", name).unwrap();
+    buffer.push_str(
+        "    fn into_response(self) -> Response {\n        if let Self::Loco(err) = self {\n            return err.into_response();\n        }\n        let mut body = json!({\"code\": self.code(), \"message\": self.message()});\n        if let Some(details) = self.details() {\n            body[\"details\"] = details;\n        }\n        (self.status(), Json(body)).into_response()\n    }\n}\n\n",
+    );
+
+    writeln!(buffer, "impl From<{}> for Error {{", name).unwrap();
+    writeln!(buffer, "    fn from(err: {}) -> Self {{", name).unwrap();
+    writeln!(
+        buffer,
+        "        if let {}::Loco(err) = err {{\n            return err;\n        }}",
+        name
+    )
+    .unwrap();
+    buffer.push_str(
+        "        let detail = ErrorDetail {\n            error: Some(err.code().to_owned()),\n            description: Some(err.message().to_owned()),\n            errors: err.details(),\n        };\n        Self::CustomError(err.status(), detail)\n    }\n}\n\n",
+    );
+
+    writeln!(buffer, "impl From<Error> for {} {{", name).unwrap();
+    buffer.push_str("    fn from(err: Error) -> Self {\n        Self::Loco(err)\n    }\n}\n\n");
+    writeln!(buffer, "impl From<DbErr> for {} {{", name).unwrap();
+    buffer.push_str("    fn from(err: DbErr) -> Self {\n        Self::Db(err)\n    }\n}\n\n");
+    writeln!(buffer, "impl From<ModelError> for {} {{", name).unwrap();
+    buffer.push_str(
+        "    fn from(err: ModelError) -> Self {\n        match err {\n            ModelError::EntityNotFound => Self::NotFound,\n            ModelError::DbErr(err) => Self::Db(err),\n            other => Self::Loco(other.into()),\n        }\n    }\n}\n\n",
+    );
+    writeln!(
+        buffer,
+        "impl From<loco_rs::validator::ValidationErrors> for {} {{",
+        name
+    )
+    .unwrap();
+    buffer.push_str(
+        "    fn from(errors: loco_rs::validator::ValidationErrors) -> Self {\n        let mut fields = Vec::new();\n        for (field, errors) in errors.field_errors() {\n            for error in errors {\n                let message = error\n                    .message\n                    .as_ref()\n                    .map_or_else(|| error.code.to_string(), ToString::to_string);\n                fields.push((field.to_string(), message));\n            }\n        }\n        Self::Validation(fields)\n    }\n}\n\n",
+    );
+    buffer
+}
+
+/// `new` and `edit` render the create and edit forms for HTML clients.
+fn render_form_handlers(resource: &Resource, controller: &Controller) -> String {
+    let views = format!("views::{}", resource.name.to_case(Case::Snake));
+    let mut buffer = String::new();
+    if renders_new_form(resource, controller) {
+        writeln!(
+            buffer,
+            "pub async fn new(ViewEngine(v): ViewEngine<TeraView>) -> Result<Response> {{\n    {}::create(&v)\n}}\n",
+            views
+        )
+        .unwrap();
+    }
+    if renders_edit_form(resource, controller) {
+        buffer.push_str("pub async fn edit(ViewEngine(v): ViewEngine<TeraView>, Path(_id): Path<String>) -> Result<Response> {\n");
+        for line in render_record_view(resource, &views, "edit", "") {
+            writeln!(buffer, "{}", line).unwrap();
+        }
+        buffer.push_str("}\n\n");
+    }
+    buffer
+}
+
+/// One `can_<action>` guard per authorized action, each wrapped in a manual region so the
+/// writer carries hand-written rules over when the file is regenerated.
+fn render_authorization_guards(resource: &Resource, actions: &[&ActionSpec]) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(
+        "// Authorization guards. Code between `via:manual` markers is kept on regeneration.\n",
+    );
+    for action in actions {
+        let region = format!("can_{}", action.handler_name);
+        writeln!(buffer, "{}{}", writer::MANUAL_BEGIN, region).unwrap();
+        writeln!(
+            buffer,
+            "/// Called before `{}#{}` runs; return an error to reject the request.",
+            resource.name, action.action_name
+        )
+        .unwrap();
+        writeln!(
+            buffer,
+            "fn {}(_ctx: &AppContext, _id: Option<&str>) -> Result<()> {{",
+            region
+        )
+        .unwrap();
+        writeln!(
+            buffer,
+            "    // TODO: authorize {}#{}",
+            resource.name, action.action_name
+        )
+        .unwrap();
+        buffer.push_str("    Ok(())\n}\n");
+        writeln!(buffer, "{}{}\n", writer::MANUAL_END, region).unwrap();
+    }
+    buffer
+}
+
+/// Per-controller helper used when `respond_with` includes `xml`. Serde drives the mapping: each
+/// serialized field becomes a child element named after the field, `None` and
+/// `serialize: false` fields are omitted, and the document root is the resource name.
+const XML_RESPOND_HELPER: &str = r#"/// Serializes `value` as XML when the request asks for it (`Accept`/`Content-Type`), JSON otherwise.
+fn respond<T: serde::Serialize>(respond_to: RespondTo, root: &str, value: T) -> Result<Response> {
+    match respond_to {
+        RespondTo::Xml => {
+            let body = quick_xml::se::to_string_with_root(root, &value)
+                .map_err(|err| Error::Any(Box::new(err)))?;
+            Ok(([("content-type", "application/xml")], body).into_response())
+        }
+        _ => format::json(value),
+    }
+}
+"#;
+
+fn find_param_struct_name(param_structs: &[ParamStruct], target: &str) -> Option<String> {
+    param_structs
+        .iter()
+        .find(|ps| ps.name == target)
+        .map(|ps| ps.name.clone())
+}
+
+/// Path of a resource's declarations relative to the TypeScript dir, without extension.
+fn ts_model_module(module: &str, flat: bool) -> String {
+    if flat {
+        format!("{}_model", module)
+    } else {
+        format!("models/{}", module)
+    }
+}
+
+fn render_ts_models(
+    resource: &Resource,
+    model: &Model,
+    param_structs: &[ParamStruct],
+    ts_brands: &HashMap<String, String>,
+    enums: &[EnumDecl],
+    flat: bool,
+    json_case: JsonCase,
+) -> String {
+    let mut buffer = String::new();
+    writeln!(buffer, "// @generated by via-core. DO NOT EDIT BY HAND.").unwrap();
+    buffer.push('\n');
+
+    let module_name = resource.name.to_case(Case::Snake);
+    if let Some(brand) = ts_brands.get(&module_name) {
+        let mut imports: Vec<(&String, &String)> = model
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .chain(
+                param_structs
+                    .iter()
+                    .flat_map(|ps| ps.fields.iter().map(|f| f.name.as_str())),
+            )
+            .filter_map(|name| name.strip_suffix("_id"))
+            .filter(|target| *target != module_name)
+            .filter_map(|target| ts_brands.get_key_value(target))
+            .collect();
+        imports.sort();
+        imports.dedup();
+        for (target_module, target_brand) in imports {
+            // Model files are siblings in either layout.
+            let sibling = if flat {
+                format!("{}_model", target_module)
+            } else {
+                target_module.clone()
+            };
+            writeln!(
+                buffer,
+                "import type {{ {} }} from './{}';",
+                target_brand, sibling
+            )
+            .unwrap();
+        }
+        if !buffer.ends_with("\n\n") {
+            buffer.push('\n');
+        }
+        writeln!(buffer, "{}", render_ts_brand(brand, resource)).unwrap();
+    }
+
+    buffer.push_str(&render_ts_declarations(
+        resource,
+        model,
+        param_structs,
+        ts_brands,
+        enums,
+        json_case,
+    ));
+    buffer
+}
+
+/// `PostId = number & { ... }`, branding whatever TS type the resource's `id` maps to. Models
+/// without an `id` field get an auto-increment integer key, so their brand is a number.
+fn render_ts_brand(brand: &str, resource: &Resource) -> String {
+    let id_type = resource
+        .model
+        .iter()
+        .flat_map(|model| &model.fields)
+        .find(|field| field.name == "id")
+        .map_or("Integer", |field| field.ty.name.as_str());
+    let (base, _) = map_via_type_to_ts(id_type);
+    let base = if base == "unknown" {
+        "string".into()
+    } else {
+        base
+    };
+    format!(
+        "export type {} = {} & {{ readonly __brand: \"{}\" }};\n",
+        brand, base, brand
+    )
+}
+
+/// The model interface and params types for one resource, without header or imports. With
+/// `sti: kind` the model becomes `<Name>Base` plus one interface per discriminator value, joined
+/// into `type <Name> = <Name><Value> | ...` so consumers can narrow on `kind`.
+fn render_ts_declarations(
+    resource: &Resource,
+    model: &Model,
+    param_structs: &[ParamStruct],
+    ts_brands: &HashMap<String, String>,
+    enums: &[EnumDecl],
+    json_case: JsonCase,
+) -> String {
+    let mut buffer = String::new();
+    let module_name = resource.name.to_case(Case::Snake);
+    let discriminator = resource
+        .sti
+        .as_ref()
+        .and_then(|name| model.fields.iter().find(|field| &field.name == name))
+        .and_then(|field| Some((field, sti_variants(field, enums)?)));
+    let interface_name = match discriminator {
+        Some(_) => format!("{}Base", resource.name),
+        None => resource.name.clone(),
+    };
+    if let Some(doc) = ts_deprecated_doc(resource.deprecated.as_ref()) {
+        writeln!(buffer, "{}", doc).unwrap();
+    }
+    writeln!(buffer, "export interface {} {{", interface_name).unwrap();
+    for field in &model.fields {
+        if field.attributes.serialize == Some(false)
+            || discriminator
+                .as_ref()
+                .is_some_and(|(discriminator, _)| discriminator.name == field.name)
+        {
+            continue;
+        }
+        for comment in field_doc_lines(field) {
+            writeln!(buffer, "  // {}", comment).unwrap();
+        }
+        let (ts_type, note) = match ts_branded_id(&module_name, &field.name, ts_brands) {
+            Some(brand) => (brand, None),
+            None => match ts_allowed_union(field).or_else(|| ts_format_type(field)) {
+                Some(narrowed) => (narrowed, None),
+                None => map_via_type_to_ts(&field.ty.name),
+            },
+        };
+        if let Some(note) = note {
+            writeln!(buffer, "  // {}", note).unwrap();
+        }
+        if let Some(doc) = ts_deprecated_doc(field.attributes.deprecated.as_ref()) {
+            writeln!(buffer, "  {}", doc).unwrap();
+        }
+        let optional = if field.optional { "?" } else { "" };
+        let readonly = if !field.attributes.stored() {
+            "readonly "
+        } else {
+            ""
+        };
+        writeln!(
+            buffer,
+            "  {}{}{}: {};",
+            readonly,
+            json_case.key(&field.name),
+            optional,
+            ts_type
+        )
+        .unwrap();
+    }
+    buffer.push_str("}\n\n");
+
+    if let Some((field, variants)) = &discriminator {
+        let mut members = Vec::new();
+        for variant in variants {
+            let member = format!("{}{}", resource.name, variant.to_case(Case::Pascal));
+            writeln!(
+                buffer,
+                "export interface {} extends {} {{",
+                member, interface_name
+            )
+            .unwrap();
+            for comment in field_doc_lines(field) {
+                writeln!(buffer, "  // {}", comment).unwrap();
+            }
+            writeln!(buffer, "  {}: {:?};", json_case.key(&field.name), variant).unwrap();
+            buffer.push_str("}\n\n");
+            members.push(member);
+        }
+        if let Some(doc) = ts_deprecated_doc(resource.deprecated.as_ref()) {
+            writeln!(buffer, "{}", doc).unwrap();
+        }
+        writeln!(
+            buffer,
+            "export type {} = {};\n",
+            resource.name,
+            members.join(" | ")
+        )
+        .unwrap();
+    }
+
+    for relation in &model.polymorphic {
+        let members = relation
+            .targets
+            .iter()
+            .map(|target| {
+                format!(
+                    "\n  | {{ {}: {:?}; {}: number }}",
+                    json_case.key(&relation.type_field()),
+                    target,
+                    json_case.key(&relation.id_field())
+                )
+            })
+            .collect::<String>();
+        writeln!(
+            buffer,
+            "// `{}` as a union narrowed by `{}`.\nexport type {}{} ={};\n",
+            relation.name,
+            json_case.key(&relation.type_field()),
+            resource.name,
+            relation.name.to_case(Case::Pascal),
+            members
+        )
+        .unwrap();
+    }
+
+    buffer.push_str(&render_ts_summary(resource, model, json_case));
+
+    if resource.cursor_paginated() {
+        writeln!(
+            buffer,
+            "export interface {}Page {{\n  items: {}[];\n  {}: string | null;\n}}\n",
+            resource.name,
+            resource.name,
+            json_case.key("next_cursor")
+        )
+        .unwrap();
+    }
+
+    for param_struct in param_structs {
+        writeln!(buffer, "export type {} = {{", param_struct.name).unwrap();
+        for field in &param_struct.fields {
+            for doc in &field.doc {
+                writeln!(buffer, "  // {}", doc).unwrap();
+            }
+            let ts_type = ts_branded_id(&module_name, &field.name, ts_brands)
+                .or_else(|| field.source.as_ref().and_then(ts_allowed_union))
+                .or_else(|| field.source.as_ref().and_then(ts_format_type))
+                .unwrap_or_else(|| map_rust_type_to_ts(&field.ty));
+            let deprecated = field
+                .source
+                .as_ref()
+                .and_then(|source| ts_deprecated_doc(source.attributes.deprecated.as_ref()));
+            if let Some(doc) = deprecated {
+                writeln!(buffer, "  {}", doc).unwrap();
+            }
+            let optional = if field.optional { "?" } else { "" };
+            writeln!(
+                buffer,
+                "  {}{}: {};",
+                json_case.key(&field.name),
+                optional,
+                ts_type
+            )
+            .unwrap();
+        }
+        buffer.push_str("};\n\n");
+    }
+
+    buffer.trim_end().to_owned() + "\n"
+}
+
+/// `<name>Summary(record)`, the TypeScript twin of the model's `summary()`.
+fn render_ts_summary(resource: &Resource, model: &Model, json_case: JsonCase) -> String {
+    let fallback = match summary_id(model) {
+        Some(id) => format!(
+            "`{} #${{record.{}}}`",
+            resource.name,
+            json_case.key(&id.name)
+        ),
+        None => format!("{:?}", resource.name),
+    };
+    let (param, body) = match title_field(model) {
+        Some(field) if field.optional => (
+            "record",
+            format!("record.{} ?? {}", json_case.key(&field.name), fallback),
+        ),
+        Some(field) => ("record", format!("record.{}", json_case.key(&field.name))),
+        None if summary_id(model).is_some() => ("record", fallback),
+        None => ("_record", fallback),
+    };
+    format!(
+        "// Label for admin and debug views, as `{}::summary()` builds it in Rust.\nexport function {}Summary({}: {}): string {{\n  return {};\n}}\n\n",
+        resource.name,
+        resource.name.to_case(Case::Camel),
+        param,
+        resource.name,
+        body
+    )
+}
+
+/// The closed set of values an `sti` discriminator can take: the variants of its enum type, or
+/// its `@in(...)` strings.
+pub(crate) fn sti_variants(field: &Field, enums: &[EnumDecl]) -> Option<Vec<String>> {
+    if let Some(decl) = enums.iter().find(|decl| decl.name == field.ty.name) {
+        return Some(decl.variants.clone());
+    }
+    if ViaScalar::from_name(&field.ty.name) == Some(ViaScalar::String) {
+        return field.attributes.allowed.clone();
+    }
+    None
+}
+
+/// `make<Resource>(overrides)` returning a fully populated mock of the model interface. Values
+/// are fixed per type so snapshots of mock data stay stable; optional fields are `undefined`.
+fn render_ts_factory(
+    resource: &Resource,
+    model: &Model,
+    ts_brands: &HashMap<String, String>,
+    enums: &[EnumDecl],
+    single_file: bool,
+    flat: bool,
+    json_case: JsonCase,
+) -> String {
+    let module_name = resource.name.to_case(Case::Snake);
+    let types_path = |module: &str| {
+        if single_file {
+            "../schema".to_owned()
+        } else {
+            format!("../{}", ts_model_module(module, flat))
+        }
+    };
+    let discriminator = resource
+        .sti
+        .as_ref()
+        .and_then(|name| model.fields.iter().find(|field| &field.name == name))
+        .and_then(|field| Some((field.name.as_str(), sti_variants(field, enums)?)));
+
+    let mut imports: BTreeSet<(String, String)> = BTreeSet::new();
+    imports.insert((types_path(&module_name), resource.name.clone()));
+    let mut properties = Vec::new();
+    for field in &model.fields {
+        if field.attributes.serialize == Some(false) {
+            continue;
+        }
+        let value = if field.optional {
+            "undefined".to_owned()
+        } else if let Some((_, variants)) = discriminator
+            .as_ref()
+            .filter(|(name, _)| *name == field.name)
+        {
+            format!("{:?}", variants[0])
+        } else if let Some(brand) = ts_branded_id(&module_name, &field.name, ts_brands) {
+            let target = if field.name == "id" {
+                module_name.clone()
+            } else {
+                field.name.trim_end_matches("_id").to_owned()
+            };
+            imports.insert((types_path(&target), brand.clone()));
+            format!("\"1\" as {}", brand)
+        } else if let Some(first) = field.attributes.allowed.as_ref().and_then(|v| v.first()) {
+            if is_integer_type(&field.ty.name) {
+                first.clone()
+            } else {
+                format!("{:?}", first)
+            }
+        } else {
+            ts_default_value(field)
+        };
+        properties.push(format!("    {}: {},", json_case.key(&field.name), value));
+    }
+
+    let mut grouped: Vec<(String, Vec<String>)> = Vec::new();
+    for (path, name) in imports {
+        match grouped.last_mut() {
+            Some((last, names)) if *last == path => names.push(name),
+            _ => grouped.push((path, vec![name])),
+        }
+    }
+
+    let mut buffer = String::new();
+    buffer.push_str(GENERATED_HEADER);
+    buffer.push('\n');
+    for (path, names) in grouped {
+        writeln!(
+            buffer,
+            "import type {{ {} }} from '{}';",
+            names.join(", "),
+            path
+        )
+        .unwrap();
+    }
+    buffer.push('\n');
+    writeln!(
+        buffer,
+        "export function make{name}(overrides: Partial<{name}> = {{}}): {name} {{",
+        name = resource.name
+    )
+    .unwrap();
+    buffer.push_str("  return {\n");
+    for property in properties {
+        writeln!(buffer, "{}", property).unwrap();
+    }
+    buffer.push_str("    ...overrides,\n");
+    // Spreading a partial union loses the discriminant's narrowing, so assert the result.
+    if discriminator.is_some() {
+        writeln!(buffer, "  }} as {};", resource.name).unwrap();
+    } else {
+        buffer.push_str("  };\n");
+    }
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// Placeholder for a required field in a TS factory, matching [`map_via_type_to_ts`].
+fn ts_default_value(field: &Field) -> String {
+    match field.attributes.format.as_deref() {
+        Some("uuid") => return "\"00000000-0000-0000-0000-000000000000\"".into(),
+        Some("email") => return format!("\"{}@example.com\"", field.name),
+        Some("uri") => return "\"https://example.com\"".into(),
+        Some("date-time") => return "\"1970-01-01T00:00:00Z\"".into(),
+        _ => {}
+    }
+    let Some(scalar) = ViaScalar::from_name(&field.ty.name) else {
+        return "null".into();
+    };
+    match scalar {
+        ViaScalar::String | ViaScalar::Text => format!("{:?}", field.name),
+        ViaScalar::Boolean => "false".into(),
+        ViaScalar::Integer | ViaScalar::BigInt | ViaScalar::Float | ViaScalar::Decimal => {
+            if field.name == "id" { "1" } else { "0" }.into()
+        }
+        ViaScalar::DateTime => "\"1970-01-01T00:00:00Z\"".into(),
+        ViaScalar::Date => "\"1970-01-01\"".into(),
+        ViaScalar::Time => "\"00:00:00\"".into(),
+        ViaScalar::Uuid => "\"00000000-0000-0000-0000-000000000000\"".into(),
+        ViaScalar::Json => "null".into(),
+    }
+}
+
+/// `@in(...)` fields become a literal union, e.g. `"admin" | "editor"`.
+fn ts_allowed_union(field: &Field) -> Option<String> {
+    let allowed = field.attributes.allowed.as_ref()?;
+    let members = allowed
+        .iter()
+        .map(|value| {
+            if is_integer_type(&field.ty.name) {
+                value.clone()
+            } else {
+                format!("{:?}", value)
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(members.join(" | "))
+}
+
+/// `@format(uuid)` and `@format(email)` narrow to template literal types, so a plain string
+/// has to be checked (or asserted) before it can be assigned.
+fn ts_format_type(field: &Field) -> Option<String> {
+    match field.attributes.format.as_deref()? {
+        "uuid" => Some("`${string}-${string}-${string}-${string}-${string}`".to_owned()),
+        "email" => Some("`${string}@${string}`".to_owned()),
+        _ => None,
+    }
+}
+
+fn build_ts_id_brands(resources: &[Resource]) -> HashMap<String, String> {
+    resources
+        .iter()
+        .filter(|resource| resource.model.is_some())
+        .map(|resource| {
+            (
+                resource.name.to_case(Case::Snake),
+                format!("{}Id", resource.name),
+            )
+        })
+        .collect()
+}
+
+/// Resolves the branded id type for `id` (own resource) or `<resource>_id` (foreign key) fields.
+fn ts_branded_id(
+    module_name: &str,
+    field_name: &str,
+    ts_brands: &HashMap<String, String>,
+) -> Option<String> {
+    let target = if field_name == "id" {
+        module_name
+    } else {
+        field_name.strip_suffix("_id")?
+    };
+    ts_brands.get(target).cloned()
+}
+
+/// Every model's declarations in one file. Resources come after the resources they reference
+/// (via `<resource>_id` foreign keys or resource-typed fields); TypeScript hoists type
+/// declarations, so reference cycles still compile and simply keep declaration order.
+fn render_ts_schema(
+    resources: &[Resource],
+    ts_brands: &HashMap<String, String>,
+    enums: &[EnumDecl],
+    enum_runtime: bool,
+    json_case: JsonCase,
+) -> String {
+    let ordered = ts_dependency_order(resources);
+    let mut buffer = String::new();
+    buffer.push_str("// @generated by via-core. DO NOT EDIT BY HAND.\n\n");
+
+    let brands: Vec<(&String, &Resource)> = ordered
+        .iter()
+        .filter_map(|resource| {
+            ts_brands
+                .get(&resource.name.to_case(Case::Snake))
+                .map(|brand| (brand, *resource))
+        })
+        .collect();
+    for (brand, resource) in &brands {
+        buffer.push_str(&render_ts_brand(brand, resource));
+    }
+    if !brands.is_empty() {
+        buffer.push('\n');
+    }
+
+    let mut sections: Vec<String> = enums
+        .iter()
+        .map(|decl| render_ts_enum(decl, enum_runtime))
+        .collect();
+    for resource in ordered {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        let controller = resource
+            .controller
+            .clone()
+            .unwrap_or_else(default_controller);
+        let param_structs = build_param_structs(&resource.name, &controller, Some(model));
+        sections.push(render_ts_declarations(
+            resource,
+            model,
+            &param_structs,
+            ts_brands,
+            enums,
+            json_case,
+        ));
+    }
+    buffer.push_str(&sections.join("\n"));
+    buffer
+}
+
+fn ts_dependency_order(resources: &[Resource]) -> Vec<&Resource> {
+    fn visit<'a>(
+        index: usize,
+        resources: &'a [Resource],
+        visited: &mut Vec<bool>,
+        ordered: &mut Vec<&'a Resource>,
+    ) {
+        if visited[index] {
+            return;
+        }
+        visited[index] = true;
+        if let Some(model) = &resources[index].model {
+            for field in &model.fields {
+                let target = field.name.strip_suffix("_id").unwrap_or("");
+                let dependency = resources.iter().position(|other| {
+                    other.name == field.ty.name || other.name.to_case(Case::Snake) == target
+                });
+                if let Some(dependency) = dependency {
+                    visit(dependency, resources, visited, ordered);
+                }
+            }
+        }
+        ordered.push(&resources[index]);
+    }
+
+    let mut visited = vec![false; resources.len()];
+    let mut ordered = Vec::with_capacity(resources.len());
+    for index in 0..resources.len() {
+        visit(index, resources, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+/// `ts/enums.ts`: every top-level enum, in declaration order.
+fn render_ts_enums(enums: &[EnumDecl], runtime: bool) -> String {
+    let mut buffer = String::new();
+    buffer.push_str("// @generated by via-core. DO NOT EDIT BY HAND.\n\n");
+    let declarations: Vec<String> = enums
+        .iter()
+        .map(|decl| render_ts_enum(decl, runtime))
+        .collect();
+    buffer.push_str(&declarations.join("\n"));
+    buffer
+}
+
+/// `export type Status = "draft" | "published";`, or with `runtime` an `as const` object keyed by
+/// the PascalCased variant plus the same union derived from it, so `Object.values(Status)` works.
+fn render_ts_enum(decl: &EnumDecl, runtime: bool) -> String {
+    if !runtime {
+        let members: Vec<String> = decl
+            .variants
+            .iter()
+            .map(|variant| format!("{:?}", variant))
+            .collect();
+        return format!("export type {} = {};\n", decl.name, members.join(" | "));
+    }
+    let mut buffer = String::new();
+    writeln!(buffer, "export const {} = {{", decl.name).unwrap();
+    for variant in &decl.variants {
+        writeln!(
+            buffer,
+            "  {}: {:?},",
+            variant.to_case(Case::Pascal),
+            variant
+        )
+        .unwrap();
+    }
+    buffer.push_str("} as const;\n");
+    writeln!(
+        buffer,
+        "export type {} = (typeof {})[keyof typeof {}];",
+        decl.name, decl.name, decl.name
+    )
+    .unwrap();
+    buffer
+}
+
+fn render_ts_index(modules: &[String], enums: bool, errors: bool) -> String {
+    let mut buffer = String::new();
+    buffer.push_str("// @generated by via-core. DO NOT EDIT BY HAND.\n\n");
+    if enums {
+        buffer.push_str("export * from './enums';\n");
+    }
+    if errors {
+        buffer.push_str("export * from './errors';\n");
+    }
+    for module in modules {
+        writeln!(buffer, "export * from './{}';", module).unwrap();
+    }
+    buffer
+}
+
+/// `ApiError`, a union over [`ERROR_CODES`] mirroring the bodies the `<Resource>Error` enums
+/// answer with, plus an `isApiError` guard for narrowing a failed response's body.
+fn render_ts_api_error() -> String {
+    let mut buffer = String::new();
+    buffer.push_str(
+        "/**\n * Body of a failed request, as the generated `<Resource>Error` enums answer it. Switch on\n * `code` to narrow; only `validation_failed` carries per-field `details`.\n */\n",
+    );
+    buffer.push_str("export type ApiError =\n");
+    let variants: Vec<String> = ERROR_CODES
+        .iter()
+        .map(|code| {
+            let details = if *code == "validation_failed" {
+                "; details: Record<string, string[]>"
+            } else {
+                ""
+            };
+            format!("  | {{ code: {:?}; message: string{} }}", code, details)
+        })
+        .collect();
+    buffer.push_str(&variants.join("\n"));
+    buffer.push_str(";\n\n");
+    buffer.push_str("export type ApiErrorCode = ApiError['code'];\n\n");
+    let codes: Vec<String> = ERROR_CODES
+        .iter()
+        .map(|code| format!("{:?}", code))
+        .collect();
+    writeln!(
+        buffer,
+        "export const API_ERROR_CODES: readonly ApiErrorCode[] = [{}];\n",
+        codes.join(", ")
+    )
+    .unwrap();
+    buffer.push_str(
+        "/** Whether a response body is an {@link ApiError} rather than, say, loco's own error body. */\n",
+    );
+    buffer.push_str("export function isApiError(value: unknown): value is ApiError {\n");
+    buffer
+        .push_str("  if (typeof value !== 'object' || value === null) {\n    return false;\n  }\n");
+    buffer.push_str("  const { code, message } = value as Record<string, unknown>;\n");
+    buffer.push_str(
+        "  return typeof message === 'string' && API_ERROR_CODES.includes(code as ApiErrorCode);\n",
+    );
+    buffer.push_str("}\n");
+    buffer
+}
+
+pub(crate) fn map_via_type_to_ts(name: &str) -> (String, Option<String>) {
+    let Some(scalar) = ViaScalar::from_name(name) else {
+        return (
+            "unknown".into(),
+            Some(format!("TODO: map Via type `{}` to a TS type", name)),
+        );
+    };
+    match scalar {
+        ViaScalar::String | ViaScalar::Text => ("string".into(), None),
+        ViaScalar::Boolean => ("boolean".into(), None),
+        ViaScalar::Integer | ViaScalar::BigInt | ViaScalar::Float | ViaScalar::Decimal => {
+            ("number".into(), None)
+        }
+        ViaScalar::DateTime | ViaScalar::Date | ViaScalar::Time => {
+            ("string".into(), Some("ISO 8601 formatted string".into()))
+        }
+        ViaScalar::Uuid => ("string".into(), Some("UUID string".into())),
+        ViaScalar::Json => ("unknown".into(), None),
+    }
+}
+
+fn map_rust_type_to_ts(ty: &str) -> String {
+    let trimmed = ty.trim();
+    if let Some(inner) = trimmed
+        .strip_prefix("Option<")
+        .and_then(|v| v.strip_suffix('>'))
+    {
+        return map_rust_type_to_ts(inner);
+    }
+    if let Some(inner) = trimmed
+        .strip_prefix("Vec<")
+        .and_then(|v| v.strip_suffix('>'))
+    {
+        let inner_ts = map_rust_type_to_ts(inner);
+        return format!("{}[]", inner_ts);
+    }
+    match trimmed {
+        "String" | "&str" => "string".into(),
+        "bool" | "Boolean" => "boolean".into(),
+        "i64" | "i32" | "i16" | "i8" | "i128" | "u64" | "u32" | "u16" | "u8" | "f64" | "f32" => {
+            "number".into()
+        }
+        "serde_json::Value" => "unknown".into(),
+        other => format!("unknown /* {} */", other),
+    }
+}
+
+/// Name of the shared error schema referenced by every error response.
+const OPENAPI_ERROR_SCHEMA: &str = "Error";
+
+/// Error responses shared by all operations: `(status, component name, description)`.
+const OPENAPI_ERROR_RESPONSES: &[(&str, &str, &str)] = &[
+    ("400", "BadRequest", "Malformed request"),
+    ("404", "NotFound", "Record not found"),
+    ("422", "UnprocessableEntity", "Params failed validation"),
+];
+
+/// The extra response every operation can answer with when handlers return `<Resource>Error`.
+const OPENAPI_INTERNAL_ERROR_RESPONSE: (&str, &str, &str) = (
+    "500",
+    "InternalServerError",
+    "Unexpected failure, such as a database error",
+);
+
+/// `code` values of the generated `<Resource>Error` enums.
+const ERROR_CODES: &[&str] = &["not_found", "validation_failed", "internal_error"];
+
+fn render_openapi(resources: &[Resource], errors: bool, json_case: JsonCase) -> Result<String> {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+
+    for resource in resources {
+        let module_name = resource.name.to_case(Case::Snake);
+        let controller = resource
+            .controller
+            .clone()
+            .unwrap_or_else(default_controller);
+        let param_structs =
+            build_param_structs(&resource.name, &controller, resource.model.as_ref());
+
+        if let Some(model) = &resource.model {
+            let fields = model
+                .fields
+                .iter()
+                .filter(|field| field.attributes.serialize != Some(false))
+                .map(|field| OpenApiProperty {
+                    name: &field.name,
+                    via_type: &field.ty.name,
+                    optional: field.optional,
+                    source: Some(field),
+                });
+            let mut schema = openapi_object_schema(fields, json_case);
+            describe_required_when(&mut schema, &resource.validations, json_case);
+            if resource.deprecated.is_some() {
+                schema["deprecated"] = Value::Bool(true);
+            }
+            schemas.insert(resource.name.clone(), schema);
+            if resource.cursor_paginated() {
+                let next_cursor = json_case.key("next_cursor");
+                schemas.insert(
+                    format!("{}Page", resource.name),
+                    json!({
+                        "type": "object",
+                        "required": ["items", next_cursor],
+                        "properties": {
+                            "items": {
+                                "type": "array",
+                                "items": { "$ref": format!("#/components/schemas/{}", resource.name) }
+                            },
+                            next_cursor: { "type": "string", "nullable": true }
+                        }
+                    }),
+                );
+            }
+        }
+        for param_struct in &param_structs {
+            let fields = param_struct.fields.iter().map(|field| OpenApiProperty {
+                name: &field.name,
+                via_type: field
+                    .source
+                    .as_ref()
+                    .map(|source| source.ty.name.as_str())
+                    .unwrap_or(""),
+                optional: field.optional || field.defaulted_source().is_some(),
+                source: field.source.as_ref(),
+            });
+            let mut schema = openapi_object_schema(fields, json_case);
+            if !is_patch_params(resource, param_struct) {
+                describe_required_when(&mut schema, &resource.validations, json_case);
+            }
+            schemas.insert(param_struct.name.clone(), schema);
+        }
+
+        for action in resolve_actions(&controller) {
+            let path = route_path(&resource.name, &action);
+            let operation = render_openapi_operation(
+                resource,
+                &module_name,
+                &action,
+                &param_structs,
+                errors,
+                json_case,
+            );
+            let entry = paths
+                .entry(path)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(item) = entry {
+                item.insert(action.method.to_owned(), operation);
+            }
+        }
     }
-    map
-}
 
-fn map_builtin(name: &str) -> (String, Option<String>) {
-    match name {
-        "String" | "Text" => ("String".into(), None),
-        "Boolean" => ("bool".into(), None),
-        "Integer" => ("i64".into(), None),
-        "BigInt" => ("i128".into(), None),
-        "Float" => ("f64".into(), None),
-        "Decimal" => (
-            "f64".into(),
-            Some("Consider rust_decimal::Decimal for exact precision".into()),
-        ),
-        "DateTime" => (
-            "String".into(),
-            Some("ISO 8601 DateTime string; swap for chrono if desired".into()),
-        ),
-        "Date" => ("String".into(), Some("ISO 8601 Date string".into())),
-        "Time" => ("String".into(), Some("ISO 8601 Time string".into())),
-        "UUID" => (
-            "String".into(),
-            Some("UUID string; use uuid::Uuid for strong typing".into()),
-        ),
-        "Json" => ("serde_json::Value".into(), None),
-        other => (
-            other.to_owned(),
-            Some(format!("TODO: map Via type `{}` to a Rust type", other)),
-        ),
+    let mut error_schema = json!({
+        "type": "object",
+        "required": ["code", "message"],
+        "properties": {
+            "code": { "type": "string" },
+            "message": { "type": "string" },
+            "details": {
+                "type": "object",
+                "additionalProperties": true,
+                "description": "Field-level validation errors keyed by field name"
+            }
+        }
+    });
+    if errors {
+        error_schema["properties"]["code"]["enum"] = json!(ERROR_CODES);
     }
-}
+    schemas.insert(OPENAPI_ERROR_SCHEMA.to_owned(), error_schema);
 
-fn pluralize(word: &str) -> String {
-    let lower = word.to_case(Case::Snake);
-    if lower.ends_with('s')
-        || lower.ends_with('x')
-        || lower.ends_with("sh")
-        || lower.ends_with("ch")
-        || lower.ends_with('z')
-    {
-        format!("{}es", lower)
-    } else if lower.ends_with('y')
-        && !matches!(
-            lower.chars().nth(lower.len().saturating_sub(2)),
-            Some('a' | 'e' | 'i' | 'o' | 'u')
-        )
-    {
-        format!("{}ies", &lower[..lower.len() - 1])
-    } else {
-        format!("{}s", lower)
-    }
+    let internal_error = errors.then_some(&OPENAPI_INTERNAL_ERROR_RESPONSE);
+    let responses: Map<String, Value> = OPENAPI_ERROR_RESPONSES
+        .iter()
+        .chain(internal_error)
+        .map(|(_, name, description)| {
+            (
+                (*name).to_owned(),
+                json!({
+                    "description": description,
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": format!("#/components/schemas/{}", OPENAPI_ERROR_SCHEMA) }
+                        }
+                    }
+                }),
+            )
+        })
+        .collect();
+
+    let document = json!({
+        "openapi": "3.0.3",
+        "info": { "title": "via-generated", "version": "0.1.0" },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "responses": responses,
+        }
+    });
+
+    Ok(serde_json::to_string_pretty(&document)? + "\n")
 }
 
-fn render_models_mod(resources: &[Resource]) -> String {
-    let mut buffer = String::new();
-    buffer.push_str(GENERATED_HEADER);
-    buffer.push('\n');
-    for resource in resources {
-        let module = resource.name.to_case(Case::Snake);
-        writeln!(buffer, "pub mod {};", module).unwrap();
+fn render_openapi_operation(
+    resource: &Resource,
+    module_name: &str,
+    action: &ActionSpec,
+    param_structs: &[ParamStruct],
+    errors: bool,
+    json_case: JsonCase,
+) -> Value {
+    let model_ref = resource
+        .model
+        .as_ref()
+        .map(|_| json!({ "$ref": format!("#/components/schemas/{}", resource.name) }));
+    let params_ref = |suffix: &str| {
+        find_param_struct_name(param_structs, &format!("{}{}", resource.name, suffix))
+            .map(|name| json!({ "$ref": format!("#/components/schemas/{}", name) }))
+            .unwrap_or_else(|| json!({ "type": "object" }))
+    };
+
+    let (success_schema, request_schema, error_statuses): (Option<Value>, Option<Value>, &[&str]) =
+        match action.action_name.as_str() {
+            "index" if resource.cursor_paginated() => (
+                Some(json!({ "$ref": format!("#/components/schemas/{}Page", resource.name) })),
+                None,
+                &["400"],
+            ),
+            "index" => (
+                model_ref.map(|schema| json!({ "type": "array", "items": schema })),
+                None,
+                &["400"],
+            ),
+            "show" => (model_ref, None, &["400", "404"]),
+            "create" => (model_ref, Some(params_ref("CreateParams")), &["400", "422"]),
+            "update" => (
+                model_ref,
+                Some(params_ref("CreateParams")),
+                &["400", "404", "422"],
+            ),
+            "partial_update" => (
+                model_ref,
+                Some(params_ref("UpdateParams")),
+                &["400", "404", "422"],
+            ),
+            "destroy" => (None, None, &["400", "404"]),
+            _ => (None, None, &["400"]),
+        };
+
+    let mut success = json!({ "description": "Successful response" });
+    if let Some(schema) = success_schema {
+        success["content"] = json!({ "application/json": { "schema": schema } });
     }
-    buffer.push('\n');
-    for resource in resources {
-        let module = resource.name.to_case(Case::Snake);
-        writeln!(buffer, "pub use {}::*;", module).unwrap();
+    let mut responses = Map::new();
+    responses.insert("200".to_owned(), success);
+    for (status, name, _) in OPENAPI_ERROR_RESPONSES {
+        if error_statuses.contains(status) {
+            responses.insert(
+                (*status).to_owned(),
+                json!({ "$ref": format!("#/components/responses/{}", name) }),
+            );
+        }
+    }
+    if errors {
+        let (status, name, _) = OPENAPI_INTERNAL_ERROR_RESPONSE;
+        responses.insert(
+            status.to_owned(),
+            json!({ "$ref": format!("#/components/responses/{}", name) }),
+        );
     }
-    buffer
-}
 
-fn render_controllers_mod(resources: &[Resource]) -> String {
-    let mut buffer = String::new();
-    buffer.push_str(GENERATED_HEADER);
-    buffer.push('\n');
-    for resource in resources {
-        let module = resource.name.to_case(Case::Snake);
-        writeln!(buffer, "pub mod {};", module).unwrap();
+    let mut operation = json!({
+        "operationId": format!("{}_{}", module_name, action.handler_name),
+        "tags": [resource.name],
+        "responses": responses,
+    });
+    if resource.deprecated.is_some() {
+        operation["deprecated"] = Value::Bool(true);
     }
-    buffer.push('\n');
-    for resource in resources {
-        let module = resource.name.to_case(Case::Snake);
-        writeln!(buffer, "pub use {}::*;", module).unwrap();
+    if action.action_name == "index" && resource.cursor_paginated() {
+        operation["parameters"] = json!([
+            {
+                "name": "after",
+                "in": "query",
+                "required": false,
+                "description": format!("`{}` from the previous page", json_case.key("next_cursor")),
+                "schema": { "type": "string" }
+            },
+            {
+                "name": "limit",
+                "in": "query",
+                "required": false,
+                "schema": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_PAGE_SIZE,
+                    "default": DEFAULT_PAGE_SIZE
+                }
+            }
+        ]);
     }
-    buffer
+    if action.path.contains("{id}") {
+        operation["parameters"] = json!([{
+            "name": "id",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" }
+        }]);
+    }
+    if let Some(schema) = request_schema {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": schema } }
+        });
+    }
+    operation
 }
 
-fn render_root_lib() -> String {
-    let mut buffer = String::new();
-    buffer.push_str(GENERATED_HEADER);
-    buffer.push_str("\npub mod controllers;\npub mod models;\n");
-    buffer
-}
+/// Output-relative path of the [`HttpFileGenerator`] file.
+pub const HTTP_FILE_NAME: &str = "requests.http";
 
-fn render_manifest() -> String {
+/// Requests in the `.http` format shared by VS Code REST Client and IntelliJ. Routes and bodies
+/// follow the OpenAPI document: create and update send `<Resource>CreateParams`, partial update
+/// `<Resource>UpdateParams`, and `{id}` is filled in from the `@id` variable.
+fn render_http_file(resources: &[Resource], enums: &[EnumDecl], json_case: JsonCase) -> String {
     let mut buffer = String::new();
     buffer.push_str("# @generated by via-core. DO NOT EDIT BY HAND.\n");
-    buffer.push_str("\n[package]\n");
-    buffer.push_str("name = \"via-generated\"\n");
-    buffer.push_str("version = \"0.1.0\"\n");
-    buffer.push_str("edition = \"2021\"\n");
-    buffer.push_str("publish = false\n\n");
-    buffer.push_str("[lib]\n");
-    buffer.push_str("path = \"src/lib.rs\"\n\n");
-    buffer.push_str("[dependencies]\n");
-    buffer.push_str("loco-rs = { version = \"0.16\" }\n");
-    buffer.push_str("serde = { version = \"1\", features = [\"derive\"] }\n");
-    buffer.push_str("serde_json = { version = \"1\" }\n");
-    buffer
-}
+    buffer.push_str("@baseUrl = http://localhost:5150\n");
+    buffer.push_str("@id = 1\n");
 
-fn render_action_stub(
-    resource: &Resource,
-    action: &ActionSpec,
-    param_structs: &[ParamStruct],
-) -> String {
-    let mut buffer = String::new();
-    let message = format!("{}#{}", resource.name, action.action_name);
-    match action.action_name.as_str() {
-        "index" => {
-            writeln!(
-                buffer,
-                "pub async fn {}(State(_ctx): State<AppContext>) -> Result<Response> {{",
-                action.handler_name
-            )
-            .unwrap();
+    for resource in resources {
+        let controller = resource
+            .controller
+            .clone()
+            .unwrap_or_else(default_controller);
+        let param_structs =
+            build_param_structs(&resource.name, &controller, resource.model.as_ref());
+        for action in resolve_actions(&controller) {
+            let path = route_path(&resource.name, &action).replace("{id}", "{{id}}");
+            let body = match action.action_name.as_str() {
+                "create" | "update" => Some(format!("{}CreateParams", resource.name)),
+                "partial_update" => Some(format!("{}UpdateParams", resource.name)),
+                _ => None,
+            }
+            .and_then(|name| param_structs.iter().find(|ps| ps.name == name));
+
+            writeln!(buffer, "\n### {} {}", resource.name, action.action_name).unwrap();
             writeln!(
                 buffer,
-                "    format::json(json!({{\"todo\": \"{}\"}}))",
-                message
+                "{} {{{{baseUrl}}}}{}",
+                action.method.to_uppercase(),
+                path
             )
             .unwrap();
-            buffer.push_str("}\n\n");
+            if let Some(param_struct) = body {
+                buffer.push_str("Content-Type: application/json\n\n");
+                buffer.push_str(&render_http_body(param_struct, enums, json_case));
+            }
         }
-        "show" | "destroy" => {
-            writeln!(
-                buffer,
-                "pub async fn {}(State(_ctx): State<AppContext>, Path(id): Path<String>) -> Result<Response> {{",
-                action.handler_name
-            )
-            .unwrap();
-            writeln!(
-                buffer,
-                "    format::json(json!({{\"todo\": \"{}\", \"id\": id}}))",
-                message
-            )
-            .unwrap();
-            buffer.push_str("}\n\n");
+    }
+    buffer
+}
+
+/// A JSON object with a sample value for every required param. `UpdateParams` has no required
+/// fields, so a PATCH body carries the ones the model itself requires.
+fn render_http_body(param_struct: &ParamStruct, enums: &[EnumDecl], json_case: JsonCase) -> String {
+    let entries: Vec<String> = param_struct
+        .fields
+        .iter()
+        .filter(|field| match &field.source {
+            Some(source) => !source.optional,
+            None => !field.optional,
+        })
+        .map(|field| {
+            let value = field
+                .source
+                .as_ref()
+                .map(|source| http_sample_value(source, enums))
+                .unwrap_or_else(|| "null".to_owned());
+            format!("  {:?}: {}", json_case.key(&field.name), value)
+        })
+        .collect();
+    if entries.is_empty() {
+        return "{}\n".to_owned();
+    }
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// The first `@in` value or enum variant, else the TypeScript factory placeholder (already a
+/// JSON literal).
+fn http_sample_value(field: &Field, enums: &[EnumDecl]) -> String {
+    if let Some(first) = field.attributes.allowed.as_ref().and_then(|v| v.first()) {
+        return if is_integer_type(&field.ty.name) {
+            first.clone()
+        } else {
+            format!("{:?}", first)
+        };
+    }
+    if let Some(variant) = enums
+        .iter()
+        .find(|decl| decl.name == field.ty.name)
+        .and_then(|decl| decl.variants.first())
+    {
+        return format!("{:?}", variant);
+    }
+    ts_default_value(field)
+}
+
+struct OpenApiProperty<'a> {
+    name: &'a str,
+    via_type: &'a str,
+    optional: bool,
+    source: Option<&'a Field>,
+}
+
+fn openapi_object_schema<'a>(
+    fields: impl Iterator<Item = OpenApiProperty<'a>>,
+    json_case: JsonCase,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for property in fields {
+        let mut schema = map_via_type_to_openapi(property.via_type);
+        if let Some(allowed) = property.source.and_then(|f| f.attributes.allowed.as_ref()) {
+            let values = allowed
+                .iter()
+                .map(|value| match value.parse::<i64>() {
+                    Ok(number) if is_integer_type(property.via_type) => Value::from(number),
+                    _ => Value::String(value.clone()),
+                })
+                .collect();
+            schema["enum"] = Value::Array(values);
         }
-        "create" => {
-            let struct_name =
-                find_param_struct_name(param_structs, &format!("{}CreateParams", resource.name))
-                    .unwrap_or_else(|| "serde_json::Value".to_owned());
-            writeln!(
-                buffer,
-                "pub async fn {}(State(_ctx): State<AppContext>, Json(payload): Json<{}>) -> Result<Response> {{",
-                action.handler_name,
-                struct_name
-            )
-            .unwrap();
-            writeln!(
-                buffer,
-                "    format::json(json!({{\"todo\": \"{}\", \"payload\": payload}}))",
-                message
-            )
-            .unwrap();
-            buffer.push_str("}\n\n");
+        if let Some(attrs) = property.source.map(|f| &f.attributes) {
+            match &attrs.default {
+                Some(Literal::String(value)) => schema["default"] = Value::String(value.clone()),
+                Some(Literal::Integer(value)) => schema["default"] = Value::from(*value),
+                Some(Literal::Bool(value)) => schema["default"] = Value::Bool(*value),
+                Some(Literal::Now) | None => {}
+            }
+            if let Some(format) = &attrs.format {
+                schema["format"] = Value::String(format.clone());
+            }
+            let lengths = [("minLength", attrs.min_len), ("maxLength", attrs.max_len)];
+            for (key, bound) in lengths {
+                if let Some(bound) = bound {
+                    schema[key] = Value::from(bound);
+                }
+            }
+            let values = [("minimum", &attrs.min), ("maximum", &attrs.max)];
+            for (key, bound) in values {
+                if let Some(bound) = bound {
+                    schema[key] = openapi_number(bound);
+                }
+            }
         }
-        "update" => {
-            let struct_name =
-                find_param_struct_name(param_structs, &format!("{}UpdateParams", resource.name))
-                    .unwrap_or_else(|| "serde_json::Value".to_owned());
-            writeln!(
-                buffer,
-                "pub async fn {}(State(_ctx): State<AppContext>, Path(id): Path<String>, Json(payload): Json<{}>) -> Result<Response> {{",
-                action.handler_name,
-                struct_name
-            )
-            .unwrap();
-            writeln!(
-                buffer,
-                "    format::json(json!({{\"todo\": \"{}\", \"id\": id, \"payload\": payload}}))",
-                message
-            )
-            .unwrap();
-            buffer.push_str("}\n\n");
+        if property.source.is_some_and(|f| !f.attributes.stored()) {
+            schema["readOnly"] = Value::Bool(true);
         }
-        _ => {
-            writeln!(
-                buffer,
-                "pub async fn {}(State(_ctx): State<AppContext>) -> Result<Response> {{",
-                action.handler_name
-            )
-            .unwrap();
-            writeln!(
-                buffer,
-                "    format::json(json!({{\"todo\": \"{}\"}}))",
-                message
-            )
-            .unwrap();
-            buffer.push_str("}\n\n");
+        if let Some(note) = property
+            .source
+            .and_then(|f| f.attributes.deprecated.as_ref())
+        {
+            schema["deprecated"] = Value::Bool(true);
+            schema["description"] = Value::String(format!("Deprecated: {}", note));
+        }
+        let key = json_case.key(property.name);
+        if !property.optional {
+            required.push(Value::String(key.clone()));
         }
+        properties.insert(key, schema);
     }
-    buffer
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
 }
 
-fn find_param_struct_name(param_structs: &[ParamStruct], target: &str) -> Option<String> {
-    param_structs
-        .iter()
-        .find(|ps| ps.name == target)
-        .map(|ps| ps.name.clone())
+/// OpenAPI 3.0 has no conditional `required`, so each rule is spelled out in the description of
+/// the field it makes required.
+fn describe_required_when(schema: &mut Value, rules: &[RequiredWhen], json_case: JsonCase) {
+    for rule in rules {
+        let (field, when_key) = (json_case.key(&rule.field), json_case.key(&rule.when));
+        let properties = &mut schema["properties"];
+        let Some(when) = properties.get(&when_key) else {
+            continue;
+        };
+        let literal = if when["type"] == "integer" {
+            rule.equals.clone()
+        } else {
+            format!("{:?}", rule.equals)
+        };
+        let Some(property) = properties.get_mut(&field) else {
+            continue;
+        };
+        let note = format!("Required when `{}` is {}.", when_key, literal);
+        let description = match property.get("description").and_then(Value::as_str) {
+            Some(existing) => format!("{} {}", existing, note),
+            None => note,
+        };
+        property["description"] = Value::String(description);
+    }
 }
 
-fn render_ts_models(resource: &Resource, model: &Model, param_structs: &[ParamStruct]) -> String {
-    let mut buffer = String::new();
-    writeln!(buffer, "// @generated by via-core. DO NOT EDIT BY HAND.").unwrap();
-    buffer.push('\n');
+/// A `@min`/`@max` literal as a JSON number, keeping integers integral.
+fn openapi_number(literal: &str) -> Value {
+    literal
+        .parse::<i64>()
+        .map(Value::from)
+        .or_else(|_| literal.parse::<f64>().map(Value::from))
+        .unwrap_or(Value::Null)
+}
 
-    let interface_name = &resource.name;
-    writeln!(buffer, "export interface {} {{", interface_name).unwrap();
-    for field in &model.fields {
+fn map_via_type_to_openapi(name: &str) -> Value {
+    if name.is_empty() {
+        return json!({ "type": "string" });
+    }
+    let Some(scalar) = ViaScalar::from_name(name) else {
+        return json!({ "description": format!("TODO: map Via type `{}` to a schema", name) });
+    };
+    match scalar {
+        ViaScalar::String | ViaScalar::Text => json!({ "type": "string" }),
+        ViaScalar::Boolean => json!({ "type": "boolean" }),
+        ViaScalar::Integer => json!({ "type": "integer", "format": "int32" }),
+        ViaScalar::BigInt => json!({ "type": "integer", "format": "int64" }),
+        ViaScalar::Float | ViaScalar::Decimal => json!({ "type": "number", "format": "double" }),
+        ViaScalar::DateTime => json!({ "type": "string", "format": "date-time" }),
+        ViaScalar::Date => json!({ "type": "string", "format": "date" }),
+        ViaScalar::Time => json!({ "type": "string" }),
+        ViaScalar::Uuid => json!({ "type": "string", "format": "uuid" }),
+        ViaScalar::Json => json!({}),
+    }
+}
+
+/// Renders one resource's proto file.
+///
+/// Field numbers follow declaration order (1-based) and are assigned the same way on every run.
+/// Model fields hidden with `@serialize(false)` keep their number as a `reserved` slot, so
+/// appending fields never renumbers existing ones; reordering or removing fields does.
+fn render_proto(
+    resource: &Resource,
+    model: &Model,
+    resource_names: &[&str],
+    features: &[String],
+) -> String {
+    let controller = resource
+        .controller
+        .clone()
+        .unwrap_or_else(default_controller);
+    let param_structs = build_param_structs(&resource.name, &controller, Some(model));
+    let actions = resolve_actions(&controller);
+    let plural_snake = pluralize(&resource.name.to_case(Case::Snake));
+    let plural = plural_snake.to_case(Case::Pascal);
+
+    let mut messages = Vec::new();
+    let mut imports = BTreeSet::new();
+
+    let mut body = String::new();
+    for (index, field) in model.fields.iter().enumerate() {
+        let number = index + 1;
         if field.attributes.serialize == Some(false) {
+            body.push_str(&format!(
+                "  reserved {}; // {} (not serialized)\n",
+                number, field.name
+            ));
             continue;
         }
-        for comment in field_doc_lines(field) {
-            writeln!(buffer, "  // {}", comment).unwrap();
+        if !feature_active(field, features) {
+            body.push_str(&proto_reserved_for_feature(number, field));
+            continue;
         }
-        let (ts_type, note) = map_via_type_to_ts(&field.ty.name);
-        if let Some(note) = note {
-            writeln!(buffer, "  // {}", note).unwrap();
+        body.push_str(&render_proto_field(
+            &field.name,
+            &field.ty.name,
+            field.optional,
+            number,
+            resource_names,
+            &resource.name,
+            &mut imports,
+        ));
+    }
+    messages.push(format!("message {} {{\n{}}}\n", resource.name, body));
+
+    for param_struct in &param_structs {
+        let mut body = String::new();
+        for (index, field) in param_struct.fields.iter().enumerate() {
+            if let Some(source) = &field.source
+                && !feature_active(source, features)
+            {
+                body.push_str(&proto_reserved_for_feature(index + 1, source));
+                continue;
+            }
+            let via_type = field
+                .source
+                .as_ref()
+                .map(|source| source.ty.name.as_str())
+                .unwrap_or("String");
+            body.push_str(&render_proto_field(
+                &field.name,
+                via_type,
+                field.optional,
+                index + 1,
+                resource_names,
+                &resource.name,
+                &mut imports,
+            ));
         }
-        let optional = if field.optional { "?" } else { "" };
-        writeln!(buffer, "  {}{}: {};", field.name, optional, ts_type).unwrap();
+        messages.push(format!("message {} {{\n{}}}\n", param_struct.name, body));
     }
-    buffer.push_str("}\n\n");
 
-    for param_struct in param_structs {
-        writeln!(buffer, "export type {} = {{", param_struct.name).unwrap();
-        for field in &param_struct.fields {
-            for doc in &field.doc {
-                writeln!(buffer, "  // {}", doc).unwrap();
+    let mut rpcs = Vec::new();
+    for action in &actions {
+        let params_name = |suffix: &str| {
+            find_param_struct_name(&param_structs, &format!("{}{}", resource.name, suffix))
+        };
+        match action.action_name.as_str() {
+            "index" => {
+                let request = format!("List{}Request", plural);
+                let response = format!("List{}Response", plural);
+                messages.push(format!("message {} {{}}\n", request));
+                messages.push(format!(
+                    "message {} {{\n  repeated {} {} = 1;\n}}\n",
+                    response, resource.name, plural_snake
+                ));
+                rpcs.push(format!(
+                    "  rpc List{}({}) returns ({});\n",
+                    plural, request, response
+                ));
             }
-            let ts_type = map_rust_type_to_ts(&field.ty);
-            let optional = if field.optional { "?" } else { "" };
-            writeln!(buffer, "  {}{}: {};", field.name, optional, ts_type).unwrap();
+            "show" => {
+                let request = format!("Get{}Request", resource.name);
+                messages.push(format!("message {} {{\n  string id = 1;\n}}\n", request));
+                rpcs.push(format!(
+                    "  rpc Get{}({}) returns ({});\n",
+                    resource.name, request, resource.name
+                ));
+            }
+            "create" => {
+                let request = format!("Create{}Request", resource.name);
+                let fields = params_name("CreateParams")
+                    .map(|name| format!("  {} params = 1;\n", name))
+                    .unwrap_or_default();
+                messages.push(format!("message {} {{\n{}}}\n", request, fields));
+                rpcs.push(format!(
+                    "  rpc Create{}({}) returns ({});\n",
+                    resource.name, request, resource.name
+                ));
+            }
+            "update" => {
+                let request = format!("Update{}Request", resource.name);
+                let mut fields = String::from("  string id = 1;\n");
+                if let Some(name) = params_name("UpdateParams") {
+                    fields.push_str(&format!("  {} params = 2;\n", name));
+                }
+                messages.push(format!("message {} {{\n{}}}\n", request, fields));
+                rpcs.push(format!(
+                    "  rpc Update{}({}) returns ({});\n",
+                    resource.name, request, resource.name
+                ));
+            }
+            // `Update` already takes the all-optional params, which is PATCH semantics.
+            "partial_update" => {}
+            "destroy" => {
+                let request = format!("Delete{}Request", resource.name);
+                messages.push(format!("message {} {{\n  string id = 1;\n}}\n", request));
+                imports.insert("google/protobuf/empty.proto".to_owned());
+                rpcs.push(format!(
+                    "  rpc Delete{}({}) returns (google.protobuf.Empty);\n",
+                    resource.name, request
+                ));
+            }
+            other => rpcs.push(format!(
+                "  // TODO: custom action `{}` has no RPC mapping yet\n",
+                other
+            )),
         }
-        buffer.push_str("};\n\n");
     }
 
-    buffer.trim_end().to_owned() + "\n"
+    let mut out = String::new();
+    writeln!(out, "{}// source: {}", GENERATED_HEADER, resource.file_path).unwrap();
+    out.push('\n');
+    out.push_str("syntax = \"proto3\";\n\npackage via;\n\n");
+    if !imports.is_empty() {
+        for import in &imports {
+            out.push_str(&format!("import \"{}\";\n", import));
+        }
+        out.push('\n');
+    }
+    out.push_str(&messages.join("\n"));
+    if !rpcs.is_empty() {
+        out.push_str(&format!(
+            "\nservice {}Service {{\n{}}}\n",
+            resource.name,
+            rpcs.concat()
+        ));
+    }
+    out
 }
 
-fn render_ts_index(modules: &[String]) -> String {
-    let mut buffer = String::new();
-    buffer.push_str("// @generated by via-core. DO NOT EDIT BY HAND.\n\n");
-    for module in modules {
-        writeln!(buffer, "export * from './models/{}';", module).unwrap();
-    }
-    buffer
+fn proto_reserved_for_feature(number: usize, field: &Field) -> String {
+    format!(
+        "  reserved {}; // {} (feature `{}` not enabled)\n",
+        number,
+        field.name,
+        field.attributes.feature.as_deref().unwrap_or_default()
+    )
 }
 
-fn map_via_type_to_ts(name: &str) -> (String, Option<String>) {
-    match name {
-        "String" | "Text" => ("string".into(), None),
-        "Boolean" => ("boolean".into(), None),
-        "Integer" | "BigInt" | "Float" | "Decimal" => ("number".into(), None),
-        "DateTime" | "Date" | "Time" => ("string".into(), Some("ISO 8601 formatted string".into())),
-        "UUID" => ("string".into(), Some("UUID string".into())),
-        "Json" => ("unknown".into(), None),
-        other => (
-            "unknown".into(),
-            Some(format!("TODO: map Via type `{}` to a TS type", other)),
-        ),
+fn render_proto_field(
+    name: &str,
+    via_type: &str,
+    optional: bool,
+    number: usize,
+    resource_names: &[&str],
+    current_resource: &str,
+    imports: &mut BTreeSet<String>,
+) -> String {
+    let (ty, comment) = map_via_type_to_proto(via_type, resource_names);
+    if ty == "google.protobuf.Value" {
+        imports.insert("google/protobuf/struct.proto".to_owned());
+    } else if resource_names.contains(&via_type) && via_type != current_resource {
+        imports.insert(format!("{}.proto", via_type.to_case(Case::Snake)));
     }
+    let label = if optional { "optional " } else { "" };
+    let comment = comment
+        .map(|comment| format!(" // {}", comment))
+        .unwrap_or_default();
+    format!("  {}{} {} = {};{}\n", label, ty, name, number, comment)
 }
 
-fn map_rust_type_to_ts(ty: &str) -> String {
-    let trimmed = ty.trim();
-    if let Some(inner) = trimmed
-        .strip_prefix("Option<")
-        .and_then(|v| v.strip_suffix('>'))
-    {
-        return map_rust_type_to_ts(inner);
+/// Maps a Via type to a proto3 scalar, or to a message reference when it names another resource.
+fn map_via_type_to_proto(name: &str, resource_names: &[&str]) -> (String, Option<String>) {
+    if resource_names.contains(&name) {
+        return (name.to_owned(), None);
     }
-    if let Some(inner) = trimmed
-        .strip_prefix("Vec<")
-        .and_then(|v| v.strip_suffix('>'))
-    {
-        let inner_ts = map_rust_type_to_ts(inner);
-        return format!("{}[]", inner_ts);
-    }
-    match trimmed {
-        "String" | "&str" => "string".into(),
-        "bool" | "Boolean" => "boolean".into(),
-        "i64" | "i32" | "i16" | "i8" | "i128" | "u64" | "u32" | "u16" | "u8" | "f64" | "f32" => {
-            "number".into()
+    let Some(scalar) = ViaScalar::from_name(name) else {
+        return (
+            "string".into(),
+            Some(format!("TODO: map Via type `{}` to a proto type", name)),
+        );
+    };
+    match scalar {
+        ViaScalar::String | ViaScalar::Text => ("string".into(), None),
+        ViaScalar::Boolean => ("bool".into(), None),
+        ViaScalar::Integer => ("int32".into(), None),
+        ViaScalar::BigInt => ("int64".into(), None),
+        ViaScalar::Float | ViaScalar::Decimal => ("double".into(), None),
+        ViaScalar::DateTime | ViaScalar::Date | ViaScalar::Time => {
+            ("string".into(), Some("ISO 8601 formatted string".into()))
         }
-        "serde_json::Value" => "unknown".into(),
-        other => format!("unknown /* {} */", other),
+        ViaScalar::Uuid => ("string".into(), Some("UUID string".into())),
+        ViaScalar::Json => ("google.protobuf.Value".into(), None),
     }
 }
@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::codegen::{JsonCase, Layout};
+
+/// Project configuration file, read from the working directory unless `--config` points
+/// elsewhere.
+pub const CONFIG_FILE_NAME: &str = "via.toml";
+
+/// Settings from `via.toml`. Every key is optional; missing keys keep the built-in defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Output-relative directory for model modules (default `src/models`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models_dir: Option<PathBuf>,
+    /// Output-relative directory for controller modules (default `src/controllers`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controllers_dir: Option<PathBuf>,
+    /// Output-relative directory for TypeScript declarations (default `ts`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ts_dir: Option<PathBuf>,
+    /// JSON key spelling of generated models and params: `snake` (the default) or `camel`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_case: Option<JsonCase>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&contents).map_err(|err| anyhow!("{}: {}", path.display(), err))
+    }
+
+    /// Loads `path` when it exists; a missing file means every default applies.
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// The output layout with any configured directories applied. Directories must stay inside
+    /// the output root, and models and controllers need separate directories since each gets a
+    /// `mod.rs`.
+    pub fn layout(&self) -> Result<Layout> {
+        let defaults = Layout::default();
+        let layout = Layout {
+            models_dir: self.models_dir.clone().unwrap_or(defaults.models_dir),
+            controllers_dir: self
+                .controllers_dir
+                .clone()
+                .unwrap_or(defaults.controllers_dir),
+            ts_dir: self.ts_dir.clone().unwrap_or(defaults.ts_dir),
+        };
+        for (key, dir) in [
+            ("models_dir", &layout.models_dir),
+            ("controllers_dir", &layout.controllers_dir),
+            ("ts_dir", &layout.ts_dir),
+        ] {
+            let inside_output = dir.components().count() > 0
+                && dir
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+            if !inside_output {
+                return Err(anyhow!(
+                    "{}: `{}` must be a relative path inside the output directory (got `{}`)",
+                    CONFIG_FILE_NAME,
+                    key,
+                    dir.display()
+                ));
+            }
+        }
+        if layout.models_dir == layout.controllers_dir {
+            return Err(anyhow!(
+                "{}: `models_dir` and `controllers_dir` must differ (both `{}`)",
+                CONFIG_FILE_NAME,
+                layout.models_dir.display()
+            ));
+        }
+        Ok(layout)
+    }
+}
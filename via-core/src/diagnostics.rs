@@ -0,0 +1,58 @@
+use anstyle::{AnsiColor, Style};
+
+const ERROR: Style = AnsiColor::Red.on_default().bold();
+const WARNING: Style = AnsiColor::Yellow.on_default().bold();
+const GUTTER: Style = AnsiColor::Blue.on_default().bold();
+
+/// `error: <message>` with the label in red and any source snippet (pest's `-->`/`|` layout)
+/// highlighted. Styles are plain ANSI codes; print through `anstream` so they are stripped when
+/// color is off.
+pub fn render_error(err: &anyhow::Error) -> String {
+    let message = format!("{:#}", err);
+    // Parse errors open with the `-->` location; start the snippet on its own line.
+    let separator = if message.trim_start().starts_with("-->") {
+        "\n"
+    } else {
+        " "
+    };
+    format!(
+        "{ERROR}error{ERROR:#}:{}{}",
+        separator,
+        highlight_snippet(&message)
+    )
+}
+
+/// `warning: <message>` with the label in yellow.
+pub fn render_warning(message: &str) -> String {
+    format!(
+        "{WARNING}warning{WARNING:#}: {}",
+        highlight_snippet(message)
+    )
+}
+
+/// Colors the gutter of snippet lines and the `^` markers under the offending text.
+fn highlight_snippet(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if let Some(location) = trimmed.strip_prefix("-->") {
+                let indent = &line[..line.len() - trimmed.len()];
+                return format!("{indent}{GUTTER}-->{GUTTER:#}{location}");
+            }
+            let Some((gutter, text)) = line.split_once('|') else {
+                return line.to_owned();
+            };
+            if !gutter.trim().chars().all(|ch| ch.is_ascii_digit()) {
+                return line.to_owned();
+            }
+            let is_marker = text.contains('^') && text.chars().all(|ch| " ^-".contains(ch));
+            if is_marker {
+                format!("{GUTTER}{gutter}|{GUTTER:#}{ERROR}{text}{ERROR:#}")
+            } else {
+                format!("{GUTTER}{gutter}|{GUTTER:#}{text}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,264 @@
+use std::fmt::{self, Write};
+
+use crate::{
+    ast::{Field, Program, Resource},
+    graph::{self, Relation},
+};
+
+/// Type changes that keep every existing value representable, so clients keep working.
+const WIDENINGS: &[(&str, &str)] = &[
+    ("Integer", "BigInt"),
+    ("Integer", "Float"),
+    ("Integer", "Decimal"),
+    ("Float", "Decimal"),
+    ("String", "Text"),
+];
+
+/// Whether existing clients or stored data can break because of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Impact {
+    Breaking,
+    NonBreaking,
+}
+
+/// One structural difference between two IRs, e.g. "Article: removed field `title`".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub impact: Impact,
+    pub description: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+/// Compares resources, fields, relations, and enums by name. Spans, source paths, and other
+/// bookkeeping are ignored, so moving a declaration between files is not a change.
+pub fn diff_programs(old: &Program, new: &Program) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for resource in &old.resources {
+        match find(&new.resources, &resource.name, |r| &r.name) {
+            Some(current) => diff_resource(resource, current, &mut changes),
+            None => changes.push(breaking(format!("removed resource {}", resource.name))),
+        }
+    }
+    for resource in &new.resources {
+        if find(&old.resources, &resource.name, |r| &r.name).is_none() {
+            changes.push(non_breaking(format!("added resource {}", resource.name)));
+        }
+    }
+
+    let old_relations = graph::relations(&old.resources);
+    let new_relations = graph::relations(&new.resources);
+    for relation in &old_relations {
+        if !new_relations.contains(relation) {
+            changes.push(breaking(format!("removed relation {}", describe(relation))));
+        }
+    }
+    for relation in &new_relations {
+        if !old_relations.contains(relation) {
+            changes.push(non_breaking(format!(
+                "added relation {}",
+                describe(relation)
+            )));
+        }
+    }
+
+    for decl in &old.enums {
+        let Some(current) = find(&new.enums, &decl.name, |e| &e.name) else {
+            changes.push(breaking(format!("removed enum {}", decl.name)));
+            continue;
+        };
+        for variant in &decl.variants {
+            if !current.variants.contains(variant) {
+                changes.push(breaking(format!(
+                    "enum {}: removed variant `{}`",
+                    decl.name, variant
+                )));
+            }
+        }
+        for variant in &current.variants {
+            if !decl.variants.contains(variant) {
+                changes.push(non_breaking(format!(
+                    "enum {}: added variant `{}`",
+                    decl.name, variant
+                )));
+            }
+        }
+    }
+    for decl in &new.enums {
+        if find(&old.enums, &decl.name, |e| &e.name).is_none() {
+            changes.push(non_breaking(format!("added enum {}", decl.name)));
+        }
+    }
+    changes
+}
+
+fn diff_resource(old: &Resource, new: &Resource, changes: &mut Vec<Change>) {
+    let name = &new.name;
+    if old.table_name() != new.table_name() {
+        changes.push(breaking(format!(
+            "{}: table renamed from `{}` to `{}`",
+            name,
+            old.table_name(),
+            new.table_name()
+        )));
+    }
+    let old_fields = old.model.iter().flat_map(|model| &model.fields);
+    let new_fields: Vec<&Field> = new.model.iter().flat_map(|model| &model.fields).collect();
+    for field in old_fields.clone() {
+        match new_fields.iter().find(|current| current.name == field.name) {
+            Some(current) => diff_field(name, field, current, changes),
+            None => changes.push(breaking(format!(
+                "{}: removed field `{}`",
+                name, field.name
+            ))),
+        }
+    }
+    for field in new_fields {
+        if old_fields
+            .clone()
+            .any(|previous| previous.name == field.name)
+        {
+            continue;
+        }
+        // Existing rows have no value and existing clients don't send one, unless the column
+        // has a default to fill in.
+        let change =
+            if field.optional || !field.attributes.stored() || field.attributes.default.is_some() {
+                non_breaking
+            } else {
+                breaking
+            };
+        changes.push(change(format!(
+            "{}: added {} field `{}: {}`",
+            name,
+            if field.optional {
+                "optional"
+            } else {
+                "required"
+            },
+            field.name,
+            field.ty.name
+        )));
+    }
+}
+
+fn diff_field(resource: &str, old: &Field, new: &Field, changes: &mut Vec<Change>) {
+    let field = &new.name;
+    if old.ty.name != new.ty.name {
+        let widening = WIDENINGS.contains(&(old.ty.name.as_str(), new.ty.name.as_str()));
+        let change = if widening { non_breaking } else { breaking };
+        changes.push(change(format!(
+            "{}: field `{}` changed type from {} to {}",
+            resource, field, old.ty.name, new.ty.name
+        )));
+    }
+    match (old.optional, new.optional) {
+        (true, false) => changes.push(breaking(format!(
+            "{}: field `{}` is now required",
+            resource, field
+        ))),
+        // Readers that relied on the value being present now have to handle its absence.
+        (false, true) => changes.push(breaking(format!(
+            "{}: field `{}` is now optional",
+            resource, field
+        ))),
+        _ => {}
+    }
+    let serialized = |field: &Field| field.attributes.serialize != Some(false);
+    match (serialized(old), serialized(new)) {
+        (true, false) => changes.push(breaking(format!(
+            "{}: field `{}` is no longer serialized",
+            resource, field
+        ))),
+        (false, true) => changes.push(non_breaking(format!(
+            "{}: field `{}` is now serialized",
+            resource, field
+        ))),
+        _ => {}
+    }
+    match (&old.attributes.allowed, &new.attributes.allowed) {
+        (Some(before), Some(after)) => {
+            for value in before.iter().filter(|value| !after.contains(value)) {
+                changes.push(breaking(format!(
+                    "{}: field `{}` no longer allows {:?}",
+                    resource, field, value
+                )));
+            }
+            for value in after.iter().filter(|value| !before.contains(value)) {
+                changes.push(non_breaking(format!(
+                    "{}: field `{}` now also allows {:?}",
+                    resource, field, value
+                )));
+            }
+        }
+        (None, Some(_)) => changes.push(breaking(format!(
+            "{}: field `{}` is now restricted with @in",
+            resource, field
+        ))),
+        (Some(_), None) => changes.push(non_breaking(format!(
+            "{}: field `{}` is no longer restricted with @in",
+            resource, field
+        ))),
+        (None, None) => {}
+    }
+}
+
+/// Breaking changes first, each group in discovery order.
+pub fn render(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "No schema changes\n".to_owned();
+    }
+    let mut buffer = String::new();
+    for (impact, heading) in [
+        (Impact::Breaking, "Breaking changes"),
+        (Impact::NonBreaking, "Non-breaking changes"),
+    ] {
+        let group: Vec<&Change> = changes
+            .iter()
+            .filter(|change| change.impact == impact)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        writeln!(buffer, "{} ({}):", heading, group.len()).unwrap();
+        for change in group {
+            writeln!(buffer, "  - {}", change).unwrap();
+        }
+    }
+    buffer
+}
+
+fn describe(relation: &Relation) -> String {
+    format!(
+        "{} {} {} (via `{}`)",
+        relation.from,
+        relation.kind.label(),
+        relation.to,
+        relation.field
+    )
+}
+
+fn find<'a, T>(items: &'a [T], name: &str, key: impl Fn(&T) -> &String) -> Option<&'a T> {
+    items.iter().find(|item| key(item) == name)
+}
+
+fn breaking(description: String) -> Change {
+    Change {
+        impact: Impact::Breaking,
+        description,
+    }
+}
+
+fn non_breaking(description: String) -> Change {
+    Change {
+        impact: Impact::NonBreaking,
+        description,
+    }
+}
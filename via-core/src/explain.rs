@@ -0,0 +1,118 @@
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+
+use crate::{
+    ast::{ParamsKind, Resource},
+    codegen::{self, GenerationOutput},
+};
+
+/// Renders a human-readable summary of what the generator understood about `resource`.
+pub fn explain(resource: &Resource, generation: &GenerationOutput) -> String {
+    let mut buffer = String::new();
+    writeln!(buffer, "{} (from {})", resource.name, resource.file_path).unwrap();
+    writeln!(buffer, "Table: {}", resource.table_name()).unwrap();
+
+    buffer.push_str("\nModel fields:\n");
+    match &resource.model {
+        Some(model) if !model.fields.is_empty() => {
+            for field in &model.fields {
+                let optional = if field.optional { "?" } else { "" };
+                let (rust_ty, _) = codegen::render_field_type(field, field.optional);
+                let (ts_ty, _) = codegen::map_via_type_to_ts(&field.ty.name);
+                write!(
+                    buffer,
+                    "  {}{}: {} -> Rust `{}`, TS `{}`",
+                    field.name, optional, field.ty.name, rust_ty, ts_ty
+                )
+                .unwrap();
+                if field.attributes.serialize == Some(false) {
+                    buffer.push_str(" [serialize: false]");
+                }
+                if let Some(allowed) = &field.attributes.allowed {
+                    write!(buffer, " [in: {}]", allowed.join(", ")).unwrap();
+                }
+                if let Some(expr) = &field.attributes.computed {
+                    write!(buffer, " [computed: {}]", expr).unwrap();
+                }
+                if let Some(default) = &field.attributes.default {
+                    write!(buffer, " [default: {}]", default).unwrap();
+                }
+                if field.attributes.title {
+                    buffer.push_str(" [title]");
+                }
+                if field.attributes.transient {
+                    buffer.push_str(" [transient]");
+                }
+                buffer.push('\n');
+            }
+        }
+        Some(_) => buffer.push_str("  (none)\n"),
+        None => buffer.push_str("  (no model block)\n"),
+    }
+
+    let controller = resource
+        .controller
+        .clone()
+        .unwrap_or_else(codegen::default_controller);
+
+    buffer.push_str("\nParams:\n");
+    if controller.params.is_empty() {
+        buffer.push_str("  (none)\n");
+    }
+    for profile in &controller.params {
+        let name = match &profile.name {
+            ParamsKind::Editable => "editable".to_owned(),
+            ParamsKind::Named(name) => name.clone(),
+        };
+        let entries = profile
+            .entries
+            .iter()
+            .map(|entry| format!("{}{}", entry.name, if entry.optional { "?" } else { "" }))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let origin = if profile.wildcard {
+            " (derived from model fields)"
+        } else {
+            ""
+        };
+        writeln!(buffer, "  {}: {}{}", name, entries, origin).unwrap();
+    }
+
+    buffer.push_str("\nController:\n");
+    writeln!(
+        buffer,
+        "  respond_with: {}",
+        controller.respond_with.join(", ")
+    )
+    .unwrap();
+    for action in codegen::resolve_actions(&controller) {
+        write!(
+            buffer,
+            "  {} {} -> {}",
+            action.method.to_uppercase(),
+            codegen::route_path(&resource.name, &action),
+            action.handler_name
+        )
+        .unwrap();
+        let formats = controller.formats_for(&action.action_name);
+        if formats != controller.respond_with.as_slice() {
+            write!(buffer, " [respond_with: {}]", formats.join(", ")).unwrap();
+        }
+        buffer.push('\n');
+    }
+
+    buffer.push_str("\nGenerated files:\n");
+    let module_name = resource.name.to_case(Case::Snake);
+    for file in &generation.files {
+        let owned = file
+            .relative_path
+            .file_stem()
+            .is_some_and(|stem| stem.to_string_lossy() == module_name);
+        if owned {
+            writeln!(buffer, "  {}", file.relative_path.display()).unwrap();
+        }
+    }
+
+    buffer
+}
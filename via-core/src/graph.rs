@@ -0,0 +1,158 @@
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+
+use crate::ast::{Program, Resource};
+
+/// Default file name for `via graph --out`.
+pub const DOT_FILE_NAME: &str = "via.dot";
+
+/// How one resource points at another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    /// A `<resource>_id` foreign key on the source resource.
+    BelongsTo,
+    /// The inverse of a [`RelationKind::BelongsTo`]: the target holds keys pointing back here.
+    HasMany,
+    /// A field typed as another resource (`field author: Author`).
+    References,
+    /// One target of a `belongs_to name: (A | B)` relation; `<name>_type` picks the target.
+    Polymorphic,
+}
+
+impl RelationKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            RelationKind::BelongsTo => "belongs_to",
+            RelationKind::HasMany => "has_many",
+            RelationKind::References => "references",
+            RelationKind::Polymorphic => "polymorphic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub from: String,
+    pub to: String,
+    pub kind: RelationKind,
+    /// Field that establishes the relation (on `from` for belongs_to/references, on `to` for
+    /// has_many).
+    pub field: String,
+}
+
+/// Relations implied by model fields, inferred the same way the TypeScript schema orders
+/// resources: `<resource>_id` foreign keys (plus their has_many inverse, and including the keys
+/// `belongs_to post` declares) and fields whose type names another resource. Polymorphic
+/// `belongs_to` declarations add one relation per target.
+pub fn relations(resources: &[Resource]) -> Vec<Relation> {
+    let mut belongs_to = Vec::new();
+    let mut references = Vec::new();
+    let mut polymorphic = Vec::new();
+    for resource in resources {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        for relation in &model.polymorphic {
+            polymorphic.extend(
+                relation
+                    .targets
+                    .iter()
+                    .filter_map(|target| find_resource(resources, target))
+                    .map(|target| Relation {
+                        from: resource.name.clone(),
+                        to: target.name.clone(),
+                        kind: RelationKind::Polymorphic,
+                        field: relation.name.clone(),
+                    }),
+            );
+        }
+        for field in &model.fields {
+            if let Some(target) = find_resource(resources, &field.ty.name) {
+                references.push(Relation {
+                    from: resource.name.clone(),
+                    to: target.name.clone(),
+                    kind: RelationKind::References,
+                    field: field.name.clone(),
+                });
+            } else if let Some(target) = field
+                .name
+                .strip_suffix("_id")
+                .and_then(|target| find_by_snake_name(resources, target))
+            {
+                belongs_to.push(Relation {
+                    from: resource.name.clone(),
+                    to: target.name.clone(),
+                    kind: RelationKind::BelongsTo,
+                    field: field.name.clone(),
+                });
+            }
+        }
+    }
+
+    let has_many: Vec<Relation> = belongs_to
+        .iter()
+        .map(|relation| Relation {
+            from: relation.to.clone(),
+            to: relation.from.clone(),
+            kind: RelationKind::HasMany,
+            field: relation.field.clone(),
+        })
+        .collect();
+    belongs_to
+        .into_iter()
+        .chain(has_many)
+        .chain(references)
+        .chain(polymorphic)
+        .collect()
+}
+
+fn find_resource<'a>(resources: &'a [Resource], name: &str) -> Option<&'a Resource> {
+    resources.iter().find(|resource| resource.name == name)
+}
+
+fn find_by_snake_name<'a>(resources: &'a [Resource], snake: &str) -> Option<&'a Resource> {
+    resources
+        .iter()
+        .find(|resource| resource.name.to_case(Case::Snake) == snake)
+}
+
+/// Graphviz DOT with one node per resource and one labelled edge per relation. Inferred
+/// has_many edges are dashed so the declared side of each pair stands out.
+pub fn render_dot(program: &Program) -> String {
+    let mut buffer = String::new();
+    buffer.push_str("// @generated by via-core. DO NOT EDIT BY HAND.\n");
+    buffer.push_str("digraph via {\n");
+    buffer.push_str("  rankdir=LR;\n");
+    buffer.push_str("  node [shape=box];\n");
+    if !program.resources.is_empty() {
+        buffer.push('\n');
+    }
+    for resource in &program.resources {
+        writeln!(buffer, "  {:?};", resource.name).unwrap();
+    }
+
+    let relations = relations(&program.resources);
+    if !relations.is_empty() {
+        buffer.push('\n');
+    }
+    for relation in relations {
+        let style = if relation.kind == RelationKind::HasMany {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        writeln!(
+            buffer,
+            "  {:?} -> {:?} [label=\"{} ({})\"{}];",
+            relation.from,
+            relation.to,
+            relation.kind.label(),
+            relation.field,
+            style
+        )
+        .unwrap();
+    }
+    buffer.push_str("}\n");
+    buffer
+}
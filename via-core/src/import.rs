@@ -0,0 +1,436 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use convert_case::{Case, Casing};
+use serde_json::{Map, Value};
+
+use crate::inflect;
+
+/// Entity-crate files that never contain a `Model` struct.
+const SKIPPED_ENTITY_FILES: &[&str] = &["mod.rs", "prelude.rs", "lib.rs"];
+
+/// A `.via` resource reconstructed from one sea_orm entity file or OpenAPI schema (or, for a
+/// string schema with `enum` values, an enum declaration).
+#[derive(Debug, Clone)]
+pub struct ImportedResource {
+    pub name: String,
+    pub source: PathBuf,
+    pub contents: String,
+    /// Fields left commented out because their Rust or OpenAPI type has no Via equivalent.
+    pub unmapped: Vec<String>,
+}
+
+impl ImportedResource {
+    /// File name for the emitted `.via` file (e.g. `article.via`).
+    pub fn file_name(&self) -> String {
+        format!("{}.via", self.name.to_case(Case::Snake))
+    }
+}
+
+/// Reads every sea_orm entity file directly under `dir` and converts each `Model` into a
+/// best-effort `.via` resource. Files without a `Model` struct (e.g. `mod.rs`) are skipped.
+pub fn import_entities(dir: &Path) -> Result<Vec<ImportedResource>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read entity directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "rs")
+                && !path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| SKIPPED_ENTITY_FILES.contains(&name))
+        })
+        .collect();
+    files.sort();
+
+    let mut imported = Vec::new();
+    for path in files {
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if let Some(resource) = import_entity_source(&source, &path) {
+            imported.push(resource);
+        }
+    }
+    Ok(imported)
+}
+
+/// Converts a single entity file's source. Returns `None` when it has no `pub struct Model`.
+pub fn import_entity_source(source: &str, path: &Path) -> Option<ImportedResource> {
+    let mut table_name = None;
+    let mut in_model = false;
+    let mut found_model = false;
+    let mut pending_attrs = String::new();
+    let mut columns = Vec::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+        if !in_model {
+            if table_name.is_none() {
+                table_name = attr_string_value(line, "table_name");
+            }
+            if line.starts_with("pub struct Model") {
+                in_model = true;
+                found_model = true;
+            }
+            continue;
+        }
+        if line.starts_with('}') {
+            break;
+        }
+        if line.starts_with("#[") || !pending_attrs.is_empty() && !line.starts_with("pub ") {
+            pending_attrs.push_str(line);
+            continue;
+        }
+        if let Some((name, ty)) = line
+            .strip_prefix("pub ")
+            .and_then(|rest| rest.trim_end_matches(',').split_once(':'))
+        {
+            columns.push(EntityColumn {
+                name: name.trim().trim_start_matches("r#").to_owned(),
+                rust_type: ty.trim().to_owned(),
+                column_type: attr_string_value(&pending_attrs, "column_type"),
+                primary_key: pending_attrs.contains("primary_key"),
+            });
+        }
+        pending_attrs.clear();
+    }
+
+    if !found_model {
+        return None;
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("entity")
+        .to_owned();
+    let name = inflect::singularize(&table_name.unwrap_or(stem)).to_case(Case::Pascal);
+    let (contents, unmapped) = render_via(&name, path, &columns);
+    Some(ImportedResource {
+        name,
+        source: path.to_path_buf(),
+        contents,
+        unmapped,
+    })
+}
+
+struct EntityColumn {
+    name: String,
+    rust_type: String,
+    column_type: Option<String>,
+    primary_key: bool,
+}
+
+fn render_via(name: &str, path: &Path, columns: &[EntityColumn]) -> (String, Vec<String>) {
+    let mut unmapped = Vec::new();
+    let mut out = String::new();
+    out.push_str(&format!(
+        "# Imported from {} by `via import`.\n# Best-effort: review types, optionality, and TODOs before generating.\n",
+        path.display()
+    ));
+    out.push_str(&format!("resource {} {{\n  model {{\n", name));
+    for column in columns {
+        let (inner, optional) = match column
+            .rust_type
+            .strip_prefix("Option<")
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            Some(inner) => (inner.trim(), true),
+            None => (column.rust_type.as_str(), false),
+        };
+        let mark = if optional { "?" } else { "" };
+        if column.primary_key && column.name != "id" {
+            out.push_str(&format!(
+                "    # TODO: `{}` is the primary key; Via treats `id` as the primary key\n",
+                column.name
+            ));
+        }
+        match map_rust_type_to_via(inner, column.column_type.as_deref()) {
+            Some(via_type) => {
+                out.push_str(&format!(
+                    "    field {}{}: {}\n",
+                    column.name, mark, via_type
+                ));
+            }
+            None => {
+                out.push_str(&format!(
+                    "    # TODO: could not map Rust type `{}`; pick a Via type and uncomment\n",
+                    column.rust_type
+                ));
+                out.push_str(&format!("    # field {}{}: {}\n", column.name, mark, inner));
+                unmapped.push(column.name.clone());
+            }
+        }
+    }
+    out.push_str("  }\n}\n");
+    (out, unmapped)
+}
+
+/// Maps the Rust type sea-orm-cli emits for a column to the closest Via type.
+fn map_rust_type_to_via(rust_type: &str, column_type: Option<&str>) -> Option<&'static str> {
+    let via_type = match rust_type {
+        "String" if column_type.is_some_and(|ty| ty.starts_with("Text")) => "Text",
+        "String" => "String",
+        "bool" => "Boolean",
+        "i8" | "i16" | "i32" | "u8" | "u16" => "Integer",
+        "i64" | "u32" | "u64" => "BigInt",
+        "f32" | "f64" => "Float",
+        "Decimal" => "Decimal",
+        "DateTime"
+        | "DateTimeWithTimeZone"
+        | "DateTimeUtc"
+        | "DateTimeLocal"
+        | "TimeDateTime"
+        | "TimeDateTimeWithTimeZone" => "DateTime",
+        "Date" | "TimeDate" => "Date",
+        "Time" | "TimeTime" => "Time",
+        "Uuid" => "UUID",
+        "Json" | "serde_json::Value" => "Json",
+        _ => return None,
+    };
+    Some(via_type)
+}
+
+/// Pulls `key = "value"` out of a `#[sea_orm(...)]` attribute line.
+fn attr_string_value(attrs: &str, key: &str) -> Option<String> {
+    let start = attrs.find(&format!("{} = \"", key))? + key.len() + 4;
+    let len = attrs[start..].find('"')?;
+    Some(attrs[start..start + len].to_owned())
+}
+
+/// Reads an OpenAPI 3 document (JSON, or YAML by extension) and converts each schema under
+/// `components.schemas` into a best-effort `.via` resource.
+pub fn import_openapi(path: &Path) -> Result<Vec<ImportedResource>> {
+    let source =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let yaml = path
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    let document: Value = if yaml {
+        serde_yaml::from_str(&source).map_err(|err| anyhow!("{}: {}", path.display(), err))?
+    } else {
+        serde_json::from_str(&source).map_err(|err| anyhow!("{}: {}", path.display(), err))?
+    };
+    Ok(import_openapi_document(&document, path))
+}
+
+/// Object schemas become resources and string schemas with `enum` values become enums. Schemas
+/// that only wrap another one (`<Name>CreateParams`, `<Name>Page`, ...) and other non-object
+/// schemas are skipped.
+pub fn import_openapi_document(document: &Value, path: &Path) -> Vec<ImportedResource> {
+    let Some(schemas) = document
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+    else {
+        return Vec::new();
+    };
+    let mut imported = Vec::new();
+    for (name, schema) in schemas {
+        if is_wrapper_schema(name, schemas) {
+            continue;
+        }
+        let name = name.to_case(Case::Pascal);
+        let header = format!(
+            "# Imported from {} by `via import`.\n# Best-effort: review types, optionality, and TODOs before generating.\n",
+            path.display()
+        );
+        let (contents, unmapped) = if let Some(variants) = string_enum(schema) {
+            (header + &render_openapi_enum(&name, &variants), Vec::new())
+        } else if is_object_schema(schema) {
+            let (body, unmapped) = render_openapi_resource(&name, schema, schemas);
+            (header + &body, unmapped)
+        } else {
+            continue;
+        };
+        imported.push(ImportedResource {
+            name,
+            source: path.to_path_buf(),
+            contents,
+            unmapped,
+        });
+    }
+    imported
+}
+
+/// Suffixes `via gen --openapi` (and many other generators) put on request and page schemas.
+const WRAPPER_SUFFIXES: &[&str] = &["CreateParams", "UpdateParams", "Params", "Page"];
+
+fn is_wrapper_schema(name: &str, schemas: &Map<String, Value>) -> bool {
+    WRAPPER_SUFFIXES.iter().any(|suffix| {
+        name.strip_suffix(suffix)
+            .is_some_and(|base| !base.is_empty() && schemas.contains_key(base))
+    })
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        || schema.get("properties").is_some()
+        || composition_keyword(schema).is_some()
+}
+
+fn composition_keyword(schema: &Value) -> Option<&'static str> {
+    ["allOf", "oneOf", "anyOf"]
+        .into_iter()
+        .find(|keyword| schema.get(keyword).is_some())
+}
+
+fn string_enum(schema: &Value) -> Option<Vec<String>> {
+    if schema.get("type").and_then(Value::as_str) != Some("string") {
+        return None;
+    }
+    let values = schema.get("enum")?.as_array()?;
+    values
+        .iter()
+        .map(|value| value.as_str().map(str::to_owned))
+        .collect()
+}
+
+fn is_ident(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn render_openapi_enum(name: &str, variants: &[String]) -> String {
+    if variants.iter().all(|variant| is_ident(variant)) {
+        return format!("enum {} {{ {} }}\n", name, variants.join(", "));
+    }
+    format!(
+        "# TODO: enum values {:?} aren't all identifiers; use `@in(...)` on the fields instead\n# enum {} {{ {} }}\n",
+        variants,
+        name,
+        variants.join(", ")
+    )
+}
+
+fn render_openapi_resource(
+    name: &str,
+    schema: &Value,
+    schemas: &Map<String, Value>,
+) -> (String, Vec<String>) {
+    let mut unmapped = Vec::new();
+    let mut out = String::new();
+    out.push_str(&format!("resource {} {{\n  model {{\n", name));
+    if let Some(keyword) = composition_keyword(schema) {
+        out.push_str(&format!(
+            "    # TODO: `{}` isn't supported; copy the fields of the combined schemas here\n",
+            keyword
+        ));
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (property, field_schema) in properties.into_iter().flatten() {
+        let field_name = property.to_case(Case::Snake);
+        if field_name != *property {
+            out.push_str(&format!(
+                "    # TODO: renamed from `{}`; the generated JSON uses `{}`\n",
+                property, field_name
+            ));
+        }
+        let nullable = field_schema.get("nullable").and_then(Value::as_bool) == Some(true);
+        let mark = if required.contains(&property.as_str()) && !nullable {
+            ""
+        } else {
+            "?"
+        };
+        match map_openapi_schema_to_via(field_schema, schemas) {
+            Ok(via_type) => {
+                out.push_str(&format!(
+                    "    field {}{}: {}{}\n",
+                    field_name,
+                    mark,
+                    via_type,
+                    openapi_field_attrs(field_schema)
+                ));
+            }
+            Err(reason) => {
+                out.push_str(&format!(
+                    "    # TODO: {}; pick a Via type and uncomment\n",
+                    reason
+                ));
+                out.push_str(&format!("    # field {}{}: ?\n", field_name, mark));
+                unmapped.push(field_name);
+            }
+        }
+    }
+    out.push_str("  }\n}\n");
+    (out, unmapped)
+}
+
+/// The Via type for a property schema, or why there is none.
+fn map_openapi_schema_to_via(
+    schema: &Value,
+    schemas: &Map<String, Value>,
+) -> Result<String, String> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix("#/components/schemas/")
+            .filter(|target| schemas.contains_key(*target))
+            .ok_or_else(|| format!("could not resolve `$ref: {}`", reference))?;
+        return Ok(target.to_case(Case::Pascal));
+    }
+    if let Some(keyword) = composition_keyword(schema) {
+        return Err(format!("`{}` has no Via equivalent", keyword));
+    }
+    let format = schema.get("format").and_then(Value::as_str);
+    let via_type = match (schema.get("type").and_then(Value::as_str), format) {
+        (Some("string"), Some("date-time")) => "DateTime",
+        (Some("string"), Some("date")) => "Date",
+        (Some("string"), Some("time")) => "Time",
+        (Some("string"), Some("uuid")) => "UUID",
+        (Some("string"), _) => "String",
+        (Some("integer"), Some("int64")) => "BigInt",
+        (Some("integer"), _) => "Integer",
+        (Some("number"), Some("decimal")) => "Decimal",
+        (Some("number"), _) => "Float",
+        (Some("boolean"), _) => "Boolean",
+        (Some("object"), _) | (None, _) => "Json",
+        (Some("array"), _) => return Err("arrays have no Via equivalent".to_owned()),
+        (Some(other), _) => return Err(format!("unknown type `{}`", other)),
+    };
+    Ok(via_type.to_owned())
+}
+
+/// `@in`, `@format`, `@min_len`/`@max_len`, and `@min`/`@max` from the matching schema
+/// keywords. Only string formats without a Via type of their own become `@format`.
+fn openapi_field_attrs(schema: &Value) -> String {
+    let mut attrs = String::new();
+    if let Some(format @ ("email" | "uri")) = schema.get("format").and_then(Value::as_str) {
+        attrs.push_str(&format!(" @format({})", format));
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let literals: Vec<String> = values
+            .iter()
+            .filter_map(|value| match value {
+                Value::String(text) => Some(format!("{:?}", text)),
+                Value::Number(number) if number.is_i64() => Some(number.to_string()),
+                _ => None,
+            })
+            .collect();
+        if literals.len() == values.len() && !literals.is_empty() {
+            attrs.push_str(&format!(" @in({})", literals.join(", ")));
+        }
+    }
+    for (keyword, attr) in [("minLength", "@min_len"), ("maxLength", "@max_len")] {
+        if let Some(bound) = schema.get(keyword).and_then(Value::as_u64) {
+            attrs.push_str(&format!(" {}({})", attr, bound));
+        }
+    }
+    for (keyword, attr) in [("minimum", "@min"), ("maximum", "@max")] {
+        if let Some(bound) = schema.get(keyword).filter(|bound| bound.is_number()) {
+            attrs.push_str(&format!(" {}({})", attr, bound));
+        }
+    }
+    attrs
+}
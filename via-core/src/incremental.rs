@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::{ast::Program, config, graph};
+
+/// What `via gen --since` regenerates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plan {
+    /// Regenerate everything, for the given reason.
+    Full(String),
+    /// Regenerate these resources (changed ones plus their direct relations) and the shared
+    /// files (aggregators, manifest, schemas) every resource contributes to.
+    Partial(BTreeSet<String>),
+}
+
+/// Paths (relative to the working directory) that differ from `since`: tracked files changed
+/// in the working tree or in commits since the ref, plus untracked files that aren't ignored.
+pub fn changed_files(since: &str) -> Result<Vec<PathBuf>> {
+    let mut changed = git(&["diff", "--name-only", "--relative", since, "--"])?;
+    changed.extend(git(&["ls-files", "--others", "--exclude-standard"])?);
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+fn git(args: &[&str]) -> Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Decides what to regenerate given the files changed since the ref. Partial regeneration is
+/// unsafe, and so falls back to [`Plan::Full`], when a `.via` file was deleted (its outputs would
+/// go stale), when `via.toml` changed, or when a changed file declares enums that other files may
+/// use.
+pub fn plan(program: &Program, changed: &[PathBuf]) -> Plan {
+    let mut changed_sources = BTreeSet::new();
+    for path in changed {
+        if path
+            .file_name()
+            .is_some_and(|name| name == config::CONFIG_FILE_NAME)
+        {
+            return Plan::Full(format!("{} changed", path.display()));
+        }
+        if path.extension().is_none_or(|ext| ext != "via") {
+            continue;
+        }
+        match path.canonicalize() {
+            Ok(path) => {
+                changed_sources.insert(path);
+            }
+            Err(_) => return Plan::Full(format!("{} was deleted", path.display())),
+        }
+    }
+    let changed_source = |file_path: &str| {
+        Path::new(file_path)
+            .canonicalize()
+            .is_ok_and(|path| changed_sources.contains(&path))
+    };
+
+    let shared = program
+        .enums
+        .iter()
+        .map(|decl| &decl.file_path)
+        .find(|file_path| changed_source(file_path));
+    if let Some(file_path) = shared {
+        return Plan::Full(format!("{} declares enums other files may use", file_path));
+    }
+
+    let mut affected: BTreeSet<String> = program
+        .resources
+        .iter()
+        .filter(|resource| changed_source(&resource.file_path))
+        .map(|resource| resource.name.clone())
+        .collect();
+    // Generated code only names directly related resources, so one hop in each direction covers
+    // every file that can mention a changed resource.
+    let related: Vec<String> = graph::relations(&program.resources)
+        .into_iter()
+        .filter_map(|relation| {
+            if affected.contains(&relation.from) {
+                Some(relation.to)
+            } else if affected.contains(&relation.to) {
+                Some(relation.from)
+            } else {
+                None
+            }
+        })
+        .collect();
+    affected.extend(related);
+    Plan::Partial(affected)
+}
@@ -0,0 +1,31 @@
+use convert_case::{Case, Casing};
+
+/// Plural snake_case form of a resource name, used for table names and route segments:
+/// `Article` -> `articles`, `Person` -> `people`, `BlogPost` -> `blog_posts`.
+///
+/// Only the last word is inflected, and the rules come from `cruet` (the inflector loco uses),
+/// so names line up with what `cargo loco generate` produces.
+pub fn pluralize(word: &str) -> String {
+    let snake = word.to_case(Case::Snake);
+    let (head, last) = split_last_word(&snake);
+    format!("{}{}", head, cruet::to_plural(last))
+}
+
+/// Irregular plurals `cruet` can produce but not reverse.
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[("person", "people"), ("child", "children")];
+
+/// Reverse of [`pluralize`]: `categories` -> `category`, `people` -> `person`.
+pub fn singularize(word: &str) -> String {
+    let snake = word.to_case(Case::Snake);
+    let (head, last) = split_last_word(&snake);
+    let singular = IRREGULAR_PLURALS
+        .iter()
+        .find(|(_, plural)| *plural == last)
+        .map(|(singular, _)| (*singular).to_owned())
+        .unwrap_or_else(|| cruet::to_singular(last));
+    format!("{}{}", head, singular)
+}
+
+fn split_last_word(snake: &str) -> (&str, &str) {
+    snake.split_at(snake.rfind('_').map_or(0, |index| index + 1))
+}
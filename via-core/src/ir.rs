@@ -0,0 +1,95 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ast::Program, bundle::Bundle};
+
+/// Layout version written as the IR's top-level `ir_version`. Bump it whenever the serialized
+/// [`Program`] changes shape, and teach [`migrate`] to bring the previous version forward.
+///
+/// - 1: no `ir_version` field; resources in declaration order.
+/// - 2: `ir_version` added; resources ordered by name.
+pub const IR_VERSION: u64 = 2;
+
+#[derive(Serialize)]
+struct Versioned<'a> {
+    ir_version: u64,
+    #[serde(flatten)]
+    program: &'a Program,
+}
+
+/// The IR for `program` as pretty JSON, stamped with [`IR_VERSION`].
+pub fn to_json(program: &Program) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&versioned(program))?)
+}
+
+/// The IR for `program` as YAML, stamped with [`IR_VERSION`].
+pub fn to_yaml(program: &Program) -> Result<String> {
+    Ok(serde_yaml::to_string(&versioned(program))?)
+}
+
+fn versioned(program: &Program) -> Versioned<'_> {
+    Versioned {
+        ir_version: IR_VERSION,
+        program,
+    }
+}
+
+/// Reads an IR written by any `via` so far: JSON, YAML (by `.yaml`/`.yml` extension), or the
+/// program inside a `via bundle` artifact. Older IR versions are migrated forward; newer or
+/// unrecognized ones are rejected.
+pub fn load(path: &Path) -> Result<Program> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read IR {}", path.display()))?;
+    if let Ok(bundle) = Bundle::from_bytes(&bytes) {
+        return Ok(bundle.program);
+    }
+    let yaml = path
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    let value = if yaml {
+        serde_yaml::from_slice(&bytes).map_err(anyhow::Error::from)
+    } else {
+        serde_json::from_slice(&bytes).map_err(anyhow::Error::from)
+    };
+    value
+        .map_err(|err| anyhow!("not a via IR file: {}", err))
+        .and_then(from_value)
+        .map_err(|err| anyhow!("{}: {}", path.display(), err))
+}
+
+/// Deserializes an IR document, migrating it to [`IR_VERSION`] first. A missing `ir_version`
+/// means version 1, which predates the field.
+pub fn from_value(mut value: Value) -> Result<Program> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("not a via IR file: expected an object at the top level"))?;
+    let version = match object.remove("ir_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| anyhow!("`ir_version` must be a positive integer, got {version}"))?,
+    };
+    if version == 0 || version > IR_VERSION {
+        return Err(anyhow!(
+            "IR version {} is not supported by via {} (expected {} or older); regenerate your IR \
+             with this version of via",
+            version,
+            env!("CARGO_PKG_VERSION"),
+            IR_VERSION
+        ));
+    }
+    migrate(&mut value, version);
+    serde_json::from_value(value).map_err(|err| anyhow!("not a via IR file: {}", err))
+}
+
+/// Upgrades an IR document one version at a time from `version` to [`IR_VERSION`].
+fn migrate(value: &mut Value, version: u64) {
+    if version < 2 {
+        // v1 kept resources in declaration order; resolve now orders them by name.
+        if let Some(resources) = value.get_mut("resources").and_then(Value::as_array_mut) {
+            resources.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        }
+    }
+}
@@ -1,4 +1,17 @@
 pub mod ast;
+pub mod bundle;
 pub mod codegen;
+pub mod config;
+pub mod diagnostics;
+pub mod diff;
+pub mod explain;
+pub mod graph;
+pub mod import;
+pub mod incremental;
+pub mod inflect;
+pub mod ir;
 pub mod parser;
+pub mod resolve;
+pub mod stats;
+pub mod watch;
 pub mod writer;
@@ -1,130 +1,1146 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::{Context, Result, anyhow};
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use walkdir::WalkDir;
 
-use via_core::{codegen, parser, writer};
+use via_core::{
+    ast::Program,
+    bundle::{self, Bundle},
+    codegen,
+    config::{self, Config},
+    diagnostics, diff, explain, graph, import,
+    incremental::{self, Plan},
+    ir, parser, resolve,
+    stats::GenStats,
+    watch,
+    writer::{self, CleanMode},
+};
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    match cli.command {
+    anstream::ColorChoice::write_global(match cli.color {
+        ColorMode::Auto => anstream::ColorChoice::Auto,
+        ColorMode::Always => anstream::ColorChoice::Always,
+        ColorMode::Never => anstream::ColorChoice::Never,
+    });
+    let result = match cli.command {
         Commands::Gen(args) => run_gen(args),
         Commands::Check(args) => run_check(args),
+        Commands::Explain(args) => run_explain(args),
+        Commands::Import(args) => run_import(args),
+        Commands::Verify(args) => run_verify(args),
+        Commands::Graph(args) => run_graph(args),
+        Commands::Bundle(args) => run_bundle(args),
+        Commands::Watch(args) => run_watch(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            anstream::eprintln!("{}", diagnostics::render_error(&err));
+            ExitCode::FAILURE
+        }
     }
 }
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Via CLI (Viaduct MVP)", long_about = None)]
 struct Cli {
+    /// Color errors and warnings; `auto` colors only on a terminal and honors `NO_COLOR`
+    #[arg(long, global = true, value_enum, default_value_t = ColorMode::Auto)]
+    color: ColorMode,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Parse Via files and emit generated Rust code
     Gen(GenArgs),
     /// Parse Via files and report errors without emitting files
     Check(CheckArgs),
+    /// Print the resolved model, params, and generated files for one resource
+    Explain(ExplainArgs),
+    /// Bootstrap .via files from existing sea_orm entity files (best-effort)
+    Import(ImportArgs),
+    /// Generate, then run `cargo check` in the output crate (skipped if it has no Cargo.toml)
+    Verify(VerifyArgs),
+    /// Print (or write) a Graphviz DOT graph of resources and the relations between them
+    Graph(GraphArgs),
+    /// Resolve the project and write it, with its config, as one portable `.viabundle` for
+    /// `via gen --from-ir`
+    Bundle(BundleArgs),
+    /// Generate, then stay resident and regenerate whenever a .via file under --app changes
+    Watch(WatchArgs),
 }
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone)]
 struct GenArgs {
-    /// Directory containing .via files (defaults to ./app)
+    /// Directories (or glob patterns) containing .via files, or `-` for one document on stdin;
+    /// repeatable (defaults to ./app)
     #[arg(long, default_value = "app")]
-    app: PathBuf,
+    app: Vec<PathBuf>,
 
     /// Output directory for generated Rust code (defaults to ./generated)
     #[arg(long, default_value = "generated")]
     out: PathBuf,
 
-    /// Optional path for serialized IR (defaults to <out>/via.ir.json)
+    /// Generate from a `via bundle` artifact instead of parsing .via files; its config applies
+    /// unless --config is given
+    #[arg(long, value_name = "BUNDLE", conflicts_with_all = ["app", "strict", "fail_on_empty"])]
+    from_ir: Option<PathBuf>,
+
+    /// Optional path for serialized IR (defaults to <out>/via.ir.json, or via.ir.yaml)
     #[arg(long)]
     ir: Option<PathBuf>,
 
+    /// Print the IR to stdout instead of writing it to disk; other output is suppressed so it
+    /// can be piped (combine with --dry-run to skip codegen)
+    #[arg(long)]
+    print_ir: bool,
+
+    /// Keep each resource's and field's source span (file, start and end line/column) in the IR
+    #[arg(long)]
+    ir_spans: bool,
+
+    /// Serialization format for the IR
+    #[arg(long, value_name = "FORMAT", default_value = "json", value_parser = ["json", "yaml"])]
+    ir_format: String,
+
+    /// File layout: `nested` (`src/models/<resource>.rs`, ...) or `flat`
+    /// (`src/<resource>_model.rs`, `src/<resource>_controller.rs`, `ts/<resource>_model.ts`)
+    #[arg(long, value_name = "FORMAT", default_value = "nested", value_parser = ["nested", "flat"])]
+    out_format: String,
+
     /// Parse and report resources without writing files
     #[arg(long)]
     dry_run: bool,
+
+    /// Parse, resolve, and write only the IR file, skipping codegen (for tools that only read
+    /// the IR)
+    #[arg(long, conflicts_with_all = ["dry_run", "print_ir", "emit_only", "since"])]
+    emit_ir_only: bool,
+
+    /// Exit non-zero when no .via files are found (for CI), instead of printing a notice
+    #[arg(long)]
+    fail_on_empty: bool,
+
+    /// Treat warnings (e.g. redundant optional marks) as errors
+    #[arg(long)]
+    strict: bool,
+
+    /// Emit branded `<Resource>Id` types for ids and foreign keys in TypeScript output
+    #[arg(long)]
+    ts_branded_ids: bool,
+
+    /// Emit all TypeScript declarations into a single `ts/schema.ts`
+    #[arg(long)]
+    ts_single_file: bool,
+
+    /// Emit `ts/factories/<resource>.ts` with a `make<Resource>(overrides?)` mock builder per model
+    #[arg(long)]
+    ts_factories: bool,
+
+    /// Emit TypeScript enums as `as const` objects (iterable at runtime) with a derived union type
+    #[arg(long)]
+    ts_enum_runtime: bool,
+
+    /// Emit an OpenAPI document (`openapi.json`) for the generated routes
+    #[arg(long)]
+    openapi: bool,
+
+    /// Emit a `requests.http` with a sample request per generated route (VS Code REST Client,
+    /// IntelliJ HTTP Client)
+    #[arg(long)]
+    http_file: bool,
+
+    /// Emit a fluent `<Model>Builder` for each model (for tests and seeders)
+    #[arg(long)]
+    gen_builders: bool,
+
+    /// Emit a `<Resource>Error` enum per controller and return it from the CRUD handlers
+    #[arg(long)]
+    gen_errors: bool,
+
+    /// Emit a loco `Task` stub per model with required columns (`src/tasks/backfill_<table>.rs`)
+    /// for backfilling a required column a migration adds to a populated table
+    #[arg(long)]
+    gen_tasks: bool,
+
+    /// Emit expanded sea_orm entities (Entity, Column, PrimaryKey, Relation) under src/entities
+    #[arg(long)]
+    explicit_entities: bool,
+
+    /// Add seaography `RelatedEntity` enums to the explicit entities, enabled through the
+    /// generated crate's `seaography` feature
+    #[arg(long, requires = "explicit_entities")]
+    seaography: bool,
+
+    /// Emit a `GET /_via/resources` endpoint serving the embedded IR, behind the generated
+    /// crate's default `introspection` feature
+    #[arg(long)]
+    introspection: bool,
+
+    /// Wrap each generated controller action in a `tracing` span (`<resource>.<action>`)
+    #[arg(long)]
+    tracing: bool,
+
+    /// Emit Protobuf message and gRPC service definitions (`proto/<resource>.proto`)
+    #[arg(long)]
+    proto: bool,
+
+    /// Emit a `.gitattributes` marking the generated files as `linguist-generated`
+    #[arg(long)]
+    gitattributes: bool,
+
+    /// Emit a loco `create_table` migration per model into `<out>/migration/src` and register it
+    /// in the migration crate's `lib.rs`; tables keep the timestamp of their first migration
+    #[arg(long)]
+    migrations: bool,
+
+    /// Treat this cargo feature as enabled, so its `@feature` fields appear in the TypeScript,
+    /// OpenAPI, and Protobuf output; repeatable or comma-separated
+    #[arg(long = "feature", value_name = "NAME", value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Project config file (defaults to ./via.toml when present)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Only write generated files whose output-relative path matches this glob (e.g. "**/*.ts");
+    /// everything else on disk is left untouched
+    #[arg(long, value_name = "GLOB")]
+    emit_only: Option<glob::Pattern>,
+
+    /// Only render and write the files of resources whose .via files changed since this git ref
+    /// (and of resources related to them), plus shared files. Regenerates everything when git
+    /// fails or a partial run would be unsafe
+    #[arg(long, value_name = "REF", conflicts_with_all = ["from_ir", "emit_only"])]
+    since: Option<String>,
+
+    /// Skip a built-in generator by name (rust, typescript, openapi, http, proto, migrations);
+    /// repeatable
+    #[arg(
+        long = "skip-generator",
+        value_name = "NAME",
+        value_parser = ["rust", "typescript", "openapi", "http", "proto", "migrations"]
+    )]
+    skip_generators: Vec<String>,
+
+    /// Format the generated `.ts` files with the locally installed `prettier` (and its config
+    /// for the output directory); falls back to unformatted output with a warning when it isn't
+    /// on PATH
+    #[arg(long)]
+    prettier: bool,
+
+    /// Overwrite a hand-written `Cargo.toml`, view template, or migration in the output
+    /// directory with the generated one
+    #[arg(long)]
+    force: bool,
+
+    /// What to do with files a previous run generated that this one doesn't: `safe` removes
+    /// only those listed in `<out>/via.manifest`, `full` clears everything under `src/` that
+    /// isn't regenerated (including hand-added files), `none` leaves them. Defaults to `safe`
+    /// once a manifest exists, `full` before that
+    #[arg(long, value_name = "MODE", value_parser = ["full", "safe", "none"])]
+    out_root_clean: Option<String>,
+
+    /// Write generated files on several threads (8, or `--parallel-writes=N`), for network or
+    /// otherwise slow filesystems; without it files are written one at a time
+    #[arg(long, value_name = "THREADS", num_args = 0..=1, require_equals = true, default_missing_value = "8")]
+    parallel_writes: Option<NonZeroUsize>,
+
+    /// Print resource/field/relation counts, files and bytes per generator, and parse/codegen
+    /// timings once generation finishes
+    #[arg(long)]
+    stats: bool,
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    #[command(flatten)]
+    generate: GenArgs,
+
+    /// Cargo target directory for the check (passed through as `--target-dir`)
+    #[arg(long)]
+    target_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct WatchArgs {
+    #[command(flatten)]
+    generate: GenArgs,
 }
 
 #[derive(Args, Debug)]
 struct CheckArgs {
-    /// Directory containing .via files (defaults to ./app)
+    /// Directories (or glob patterns) containing .via files, or `-` for one document on stdin;
+    /// repeatable (defaults to ./app)
+    #[arg(long, default_value = "app")]
+    app: Vec<PathBuf>,
+
+    /// Exit non-zero when no .via files are found (for CI), instead of printing a notice
+    #[arg(long)]
+    fail_on_empty: bool,
+
+    /// Treat warnings (e.g. redundant optional marks) as errors
+    #[arg(long)]
+    strict: bool,
+
+    /// Compare against a previous IR (`via.ir.json`/`.yaml`, or a bundle) and list added,
+    /// removed, and changed resources, fields, relations, and enums, breaking changes first
+    #[arg(long, value_name = "OLD_IR")]
+    diff_ir: Option<PathBuf>,
+
+    /// Print at most this many errors, then how many more were found
+    #[arg(long, value_name = "N", default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    max_errors: u32,
+
+    /// Stay resident and re-check whenever a .via file is added, removed, or changed; never
+    /// writes files
+    #[arg(long)]
+    watch: bool,
+}
+
+#[derive(Args, Debug)]
+struct ExplainArgs {
+    /// Resource name to explain (e.g. Article)
+    resource: String,
+
+    /// Directories (or glob patterns) containing .via files, or `-` for one document on stdin;
+    /// repeatable (defaults to ./app)
+    #[arg(long, default_value = "app")]
+    app: Vec<PathBuf>,
+
+    /// Project config file (defaults to ./via.toml when present)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct GraphArgs {
+    /// Directories (or glob patterns) containing .via files, or `-` for one document on stdin;
+    /// repeatable (defaults to ./app)
+    #[arg(long, default_value = "app")]
+    app: Vec<PathBuf>,
+
+    /// Write the graph to this file instead of stdout (`--out` alone writes ./via.dot)
+    #[arg(long, num_args = 0..=1, default_missing_value = graph::DOT_FILE_NAME)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct BundleArgs {
+    /// Directories (or glob patterns) containing .via files, or `-` for one document on stdin;
+    /// repeatable (defaults to ./app)
+    #[arg(long, default_value = "app")]
+    app: Vec<PathBuf>,
+
+    /// Bundle file to write (defaults to ./via.viabundle)
+    #[arg(long, default_value = bundle::BUNDLE_FILE_NAME)]
+    out: PathBuf,
+
+    /// Gzip the bundle; `gen --from-ir` detects compressed bundles on its own
+    #[arg(long)]
+    compress: bool,
+
+    /// Project config file to embed (defaults to ./via.toml when present)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Treat warnings (e.g. redundant optional marks) as errors
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Args, Debug)]
+struct ImportArgs {
+    /// Directory of sea_orm entity files (e.g. src/models/_entities)
+    #[arg(long, required_unless_present = "openapi", conflicts_with = "openapi")]
+    entities: Option<PathBuf>,
+
+    /// OpenAPI 3 document (JSON or YAML) whose `components.schemas` become resources
+    #[arg(long)]
+    openapi: Option<PathBuf>,
+
+    /// Directory to write the imported .via files into (defaults to ./app)
     #[arg(long, default_value = "app")]
-    app: PathBuf,
+    out: PathBuf,
+
+    /// Overwrite .via files that already exist in the output directory
+    #[arg(long)]
+    force: bool,
 }
 
 fn run_gen(args: GenArgs) -> Result<()> {
-    let files = collect_via_files(&args.app)?;
-    if files.is_empty() {
-        println!("No .via files found under {}", args.app.display());
-        return Ok(());
+    // With --print-ir, stdout carries nothing but the IR.
+    let quiet = args.print_ir;
+    let started = Instant::now();
+    let (mut program, bundled_config) = match &args.from_ir {
+        Some(path) => {
+            let bundle = Bundle::load(path)?;
+            (bundle.program, Some(bundle.config))
+        }
+        None => {
+            if args.since.is_some() && reads_stdin(&args.app) {
+                return Err(anyhow!(
+                    "--since compares files against git; it can't be combined with `--app -`"
+                ));
+            }
+            let files = collect_via_files(&args.app)?;
+            if files.is_empty() {
+                ensure_found(&args.app, args.fail_on_empty)?;
+                if !quiet {
+                    println!("No .via files found under {}", display_roots(&args.app));
+                    return Ok(());
+                }
+            }
+            (load_program(&files, args.strict)?, None)
+        }
+    };
+    if !args.ir_spans {
+        program.strip_spans();
     }
+    let mut stats = GenStats::new(&program, started.elapsed());
 
-    let mut resources = Vec::new();
-    for file in files {
-        let mut parsed = parser::parse_file(&file)?;
-        resources.append(&mut parsed);
+    if args.print_ir {
+        let ir = render_ir(&program, &args.ir_format)?;
+        // A consumer like `head` closing the pipe early is not an error.
+        if let Err(err) = writeln!(io::stdout(), "{}", ir.trim_end())
+            && err.kind() != io::ErrorKind::BrokenPipe
+        {
+            return Err(err.into());
+        }
+    } else if let Some(path) = &args.from_ir {
+        println!(
+            "Loaded {} resource(s) from {}",
+            program.resources.len(),
+            path.display()
+        );
+    } else {
+        println!("Parsed {} resource(s)", program.resources.len());
     }
 
-    println!("Parsed {} resource(s)", resources.len());
-
     if args.dry_run {
-        for resource in &resources {
-            println!(" - {} (from {})", resource.name, resource.file_path);
+        if !quiet {
+            for resource in &program.resources {
+                println!(" - {} (from {})", resource.name, resource.file_path);
+            }
+        }
+        if args.stats {
+            print_stats(&stats, quiet);
         }
         return Ok(());
     }
 
-    writer::clean_output_root(&args.out)?;
+    let ir_path = ir_path(&args);
+    if args.emit_ir_only {
+        writer::write_ir_file(&ir_path, &render_ir(&program, &args.ir_format)?)?;
+        println!("IR written to {}", ir_path.display());
+        if args.stats {
+            print_stats(&stats, quiet);
+        }
+        return Ok(());
+    }
 
-    let generation = codegen::generate(&resources)?;
-    writer::write_files(&args.out, &generation.files)?;
+    let config = match (args.config.as_deref(), bundled_config) {
+        (None, Some(config)) => config,
+        (path, _) => load_config(path)?,
+    };
+    let only = match &args.since {
+        Some(since) => incremental_resources(&program, since, quiet),
+        None => None,
+    };
+    let partial = only.is_some();
+    let options = codegen::GenerateOptions {
+        ts_branded_ids: args.ts_branded_ids,
+        ts_single_file: args.ts_single_file,
+        ts_factories: args.ts_factories,
+        ts_enum_runtime: args.ts_enum_runtime,
+        openapi: args.openapi,
+        proto: args.proto,
+        http_file: args.http_file,
+        tracing: args.tracing,
+        errors: args.gen_errors,
+        builders: args.gen_builders,
+        tasks: args.gen_tasks,
+        explicit_entities: args.explicit_entities,
+        seaography: args.seaography,
+        introspection: args.introspection,
+        gitattributes: args.gitattributes,
+        migrations: args.migrations,
+        migration_names: if args.migrations {
+            writer::existing_migrations(&args.out)?
+        } else {
+            BTreeMap::new()
+        },
+        migration_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+        features: args.features,
+        layout: config.layout()?,
+        json_case: config.json_case.unwrap_or_default(),
+        out_format: match args.out_format.as_str() {
+            "flat" => codegen::OutFormat::Flat,
+            _ => codegen::OutFormat::Nested,
+        },
+        skip_generators: args.skip_generators,
+        only,
+    };
+    let started = Instant::now();
+    let mut generation = codegen::generate_with_options(&program, &options)?;
+    stats.record_generation(&generation, started.elapsed());
+    let mut write_ir = !args.print_ir;
+    // Only a run that writes the full file set replaces the manifest.
+    let mut manifest: Option<BTreeSet<PathBuf>> = None;
+    if let Some(pattern) = &args.emit_only {
+        generation
+            .files
+            .retain(|file| emit_only_matches(pattern, &file.relative_path));
+        write_ir &= emit_only_matches(pattern, ir_path.strip_prefix(&args.out).unwrap_or(&ir_path));
+    } else if !partial {
+        let previous = writer::read_manifest(&args.out);
+        let mode = match (args.out_root_clean.as_deref(), &previous) {
+            (Some("full"), _) | (None, None) => CleanMode::Full,
+            (Some("none"), _) => CleanMode::None,
+            _ => CleanMode::Safe,
+        };
+        let previous = previous.unwrap_or_default();
+        manifest = Some(match mode {
+            CleanMode::Full => {
+                writer::clean_output_root(&args.out, &generation.files)?;
+                BTreeSet::new()
+            }
+            CleanMode::Safe => {
+                writer::clean_listed_files(&args.out, &previous, &generation.files)?;
+                BTreeSet::new()
+            }
+            // Files left in place are still generated ones; a later `safe` run should remove them.
+            CleanMode::None => previous
+                .into_iter()
+                .filter(|path| args.out.join(path).is_file())
+                .collect(),
+        });
+    }
+    // The manifest, view templates, and migrations are meant to be customized; once their header
+    // is gone, they belong to the user.
+    if !args.force {
+        generation.files.retain(|file| {
+            let path = args.out.join(&file.relative_path);
+            let owned = (file.relative_path == Path::new("Cargo.toml")
+                || file.relative_path.starts_with("assets/views")
+                || file.relative_path.starts_with(codegen::MIGRATION_DIR))
+                && writer::is_hand_written(&path);
+            if owned && !quiet {
+                println!(
+                    " - kept {} (not generated by via; pass --force to overwrite)",
+                    path.display()
+                );
+            }
+            !owned
+        });
+    }
+    if args.prettier
+        && !writer::format_typescript(&args.out, &mut generation.files, Path::new("prettier"))?
+    {
+        anstream::eprintln!(
+            "{}",
+            diagnostics::render_warning(
+                "`prettier` not found on PATH; writing TypeScript unformatted"
+            )
+        );
+    }
+    let summary = match args.parallel_writes {
+        Some(threads) => writer::write_files_parallel(&args.out, &generation.files, threads)?,
+        None => writer::write_files(&args.out, &generation.files)?,
+    };
 
-    let ir_path = args.ir.unwrap_or_else(|| args.out.join("via.ir.json"));
-    let ir_json = serde_json::to_string_pretty(&resources)?;
-    writer::write_ir_file(&ir_path, &ir_json)?;
+    if args.migrations {
+        let modules: Vec<String> = generation
+            .files
+            .iter()
+            .filter(|file| file.relative_path.starts_with(codegen::MIGRATION_DIR))
+            .filter_map(|file| file.relative_path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .collect();
+        writer::register_migrations(&args.out, &modules)?;
+    }
 
-    println!(
-        "Wrote {} generated file(s) into {}",
-        generation.files.len(),
-        args.out.display()
-    );
-    println!("IR written to {}", ir_path.display());
+    if let Some(mut manifest) = manifest {
+        manifest.extend(
+            generation
+                .files
+                .iter()
+                .map(|file| file.relative_path.clone()),
+        );
+        writer::write_manifest(&args.out, &manifest)?;
+    }
+    if write_ir {
+        writer::write_ir_file(&ir_path, &render_ir(&program, &args.ir_format)?)?;
+    }
+
+    if !quiet {
+        println!(
+            "Wrote {} generated file(s) into {}{}",
+            generation.files.len(),
+            args.out.display(),
+            match summary.unchanged {
+                0 => String::new(),
+                unchanged => format!(" ({} unchanged)", unchanged),
+            }
+        );
+    }
+    if write_ir {
+        println!("IR written to {}", ir_path.display());
+    }
+    if args.stats {
+        print_stats(&stats, quiet);
+    }
+
+    Ok(())
+}
+
+/// `--ir`, or the IR file in the output directory named for `--ir-format`.
+fn ir_path(args: &GenArgs) -> PathBuf {
+    args.ir
+        .clone()
+        .unwrap_or_else(|| match args.ir_format.as_str() {
+            "yaml" => args.out.join("via.ir.yaml"),
+            _ => args.out.join(codegen::IR_FILE_NAME),
+        })
+}
+
+/// Stats go to stderr when stdout is reserved for `--print-ir`.
+fn print_stats(stats: &GenStats, quiet: bool) {
+    if quiet {
+        eprint!("{}", stats.render());
+    } else {
+        print!("{}", stats.render());
+    }
+}
+
+fn render_ir(program: &Program, format: &str) -> Result<String> {
+    match format {
+        "yaml" => ir::to_yaml(program),
+        _ => ir::to_json(program),
+    }
+}
 
+fn run_verify(args: VerifyArgs) -> Result<()> {
+    let out = args.generate.out.clone();
+    let dry_run = args.generate.dry_run;
+    run_gen(args.generate)?;
+    if dry_run {
+        return Ok(());
+    }
+
+    let manifest = out.join("Cargo.toml");
+    if !manifest.is_file() {
+        println!("Skipping cargo check: no Cargo.toml in {}", out.display());
+        return Ok(());
+    }
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command.arg("check").arg("--manifest-path").arg(&manifest);
+    if let Some(target_dir) = &args.target_dir {
+        command.arg("--target-dir").arg(target_dir);
+    }
+    println!("Running cargo check in {}", out.display());
+    let status = command
+        .status()
+        .with_context(|| format!("failed to run cargo check for {}", manifest.display()))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "cargo check failed for the generated crate in {} ({})",
+            out.display(),
+            status
+        ));
+    }
+
+    println!("OK: generated crate in {} compiles", out.display());
     Ok(())
 }
 
 fn run_check(args: CheckArgs) -> Result<()> {
+    if !args.watch {
+        return check_once(&args);
+    }
+    if reads_stdin(&args.app) {
+        return Err(anyhow!(
+            "--watch can't re-read stdin; pass a directory with --app"
+        ));
+    }
+    let stop = watch::stop_on_ctrl_c()?;
+    println!(
+        "Watching {} for changes (Ctrl-C to stop)",
+        display_roots(&args.app)
+    );
+    let watcher = watch::Watcher::new(&expand_roots(&args.app)?)?;
+    let mut last = watch::Snapshot::capture(&collect_via_files(&args.app)?);
+    loop {
+        // Failures are reported and watched past; only a broken app root ends the loop.
+        if let Err(err) = check_once(&args) {
+            anstream::eprintln!("{}", diagnostics::render_error(&err));
+        }
+        let Some((snapshot, changed)) =
+            watcher.wait_for_change(&last, &stop, || collect_via_files(&args.app))?
+        else {
+            println!("\nStopped watching");
+            return Ok(());
+        };
+        last = snapshot;
+        let changed: Vec<String> = changed
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!("\nChanged: {}", changed.join(", "));
+    }
+}
+
+fn run_watch(args: WatchArgs) -> Result<()> {
+    let args = args.generate;
+    if args.from_ir.is_some() {
+        return Err(anyhow!(
+            "via watch regenerates from .via files; drop --from-ir, or run `via gen --from-ir` once"
+        ));
+    }
+    if reads_stdin(&args.app) {
+        return Err(anyhow!(
+            "via watch can't re-read stdin; pass a directory with --app"
+        ));
+    }
+    let stop = watch::stop_on_ctrl_c()?;
+    println!(
+        "Watching {} for changes (Ctrl-C to stop)",
+        display_roots(&args.app)
+    );
+    let watcher = watch::Watcher::new(&expand_roots(&args.app)?)?;
+    let mut last = watch::Snapshot::capture(&collect_via_files(&args.app)?);
+    loop {
+        // A failed run (say, a parse error mid-edit) is reported and watched past; only a
+        // broken app root ends the loop.
+        if let Err(err) = run_gen(args.clone()) {
+            anstream::eprintln!("{}", diagnostics::render_error(&err));
+        }
+        let Some((snapshot, changed)) =
+            watcher.wait_for_change(&last, &stop, || collect_via_files(&args.app))?
+        else {
+            println!("\nStopped watching");
+            return Ok(());
+        };
+        last = snapshot;
+        println!("\nRegenerating for {}", describe_changes(&changed));
+    }
+}
+
+/// `app/posts.via (Post, Comment), app/old.via (removed)`: each changed file with the resources
+/// it now declares, as far as it parses.
+fn describe_changes(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| {
+            if !path.exists() {
+                return format!("{} (removed)", path.display());
+            }
+            match parser::parse_file(path) {
+                Ok(program) if !program.resources.is_empty() => format!(
+                    "{} ({})",
+                    path.display(),
+                    program
+                        .resources
+                        .iter()
+                        .map(|resource| resource.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                _ => path.display().to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn check_once(args: &CheckArgs) -> Result<()> {
     let files = collect_via_files(&args.app)?;
     if files.is_empty() {
-        println!("No .via files found under {}", args.app.display());
+        ensure_found(&args.app, args.fail_on_empty)?;
+        println!("No .via files found under {}", display_roots(&args.app));
         return Ok(());
     }
 
-    let mut total = 0usize;
-    for file in files {
-        let parsed = parser::parse_file(&file)?;
-        total += parsed.len();
+    let program = match collect_program(&files, args.strict) {
+        Ok(program) => program,
+        Err(mut errors) if errors.len() == 1 => return Err(errors.remove(0)),
+        Err(errors) => return Err(report_errors(&errors, args.max_errors as usize)),
+    };
+
+    println!("OK: parsed {} resource(s)", program.resources.len());
+    if let Some(path) = &args.diff_ir {
+        let previous = ir::load(path)?;
+        print!(
+            "{}",
+            diff::render(&diff::diff_programs(&previous, &program))
+        );
+    }
+    Ok(())
+}
+
+fn run_explain(args: ExplainArgs) -> Result<()> {
+    let files = collect_via_files(&args.app)?;
+    let program = load_program(&files, false)?;
+
+    let resource = program
+        .resources
+        .iter()
+        .find(|resource| resource.name.eq_ignore_ascii_case(&args.resource))
+        .ok_or_else(|| {
+            let known = program
+                .resources
+                .iter()
+                .map(|resource| resource.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow!(
+                "Resource `{}` not found under {} (known: {})",
+                args.resource,
+                display_roots(&args.app),
+                known
+            )
+        })?;
+
+    let options = codegen::GenerateOptions {
+        layout: load_config(args.config.as_deref())?.layout()?,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    print!("{}", explain::explain(resource, &generation));
+    Ok(())
+}
+
+fn run_graph(args: GraphArgs) -> Result<()> {
+    let files = collect_via_files(&args.app)?;
+    let program = load_program(&files, false)?;
+    let dot = graph::render_dot(&program);
+
+    match args.out {
+        Some(path) => {
+            fs::write(&path, dot).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Graph written to {}", path.display());
+        }
+        None => {
+            if let Err(err) = io::stdout().write_all(dot.as_bytes())
+                && err.kind() != io::ErrorKind::BrokenPipe
+            {
+                return Err(err.into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_bundle(args: BundleArgs) -> Result<()> {
+    let files = collect_via_files(&args.app)?;
+    if files.is_empty() {
+        return Err(anyhow!(
+            "No .via files found under {}",
+            display_roots(&args.app)
+        ));
+    }
+    let program = load_program(&files, args.strict)?;
+    let config = load_config(args.config.as_deref())?;
+    // Fail here rather than on the generating machine.
+    config.layout()?;
+
+    let resources = program.resources.len();
+    let bytes = Bundle::new(program, config).to_bytes(args.compress)?;
+    writer::write_bundle_file(&args.out, &bytes)?;
+    println!(
+        "Bundled {} resource(s) into {} ({} bytes)",
+        resources,
+        args.out.display(),
+        bytes.len()
+    );
+    Ok(())
+}
+
+fn run_import(args: ImportArgs) -> Result<()> {
+    let imported = match (&args.entities, &args.openapi) {
+        (Some(entities), _) => {
+            let imported = import::import_entities(entities)?;
+            if imported.is_empty() {
+                println!("No sea_orm entities found under {}", entities.display());
+                return Ok(());
+            }
+            imported
+        }
+        (None, Some(spec)) => {
+            let imported = import::import_openapi(spec)?;
+            if imported.is_empty() {
+                println!("No component schemas found in {}", spec.display());
+                return Ok(());
+            }
+            imported
+        }
+        (None, None) => return Err(anyhow!("pass --entities or --openapi")),
+    };
+
+    fs::create_dir_all(&args.out)
+        .with_context(|| format!("failed to create {}", args.out.display()))?;
+    let mut written = 0;
+    for resource in &imported {
+        let path = args.out.join(resource.file_name());
+        if path.exists() && !args.force {
+            println!(
+                " - skipped {} ({} exists; pass --force to overwrite)",
+                resource.name,
+                path.display()
+            );
+            continue;
+        }
+        fs::write(&path, &resource.contents)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        written += 1;
+        if resource.unmapped.is_empty() {
+            println!(" - {} -> {}", resource.name, path.display());
+        } else {
+            println!(
+                " - {} -> {} (TODO: unmapped field(s): {})",
+                resource.name,
+                path.display(),
+                resource.unmapped.join(", ")
+            );
+        }
     }
 
-    println!("OK: parsed {} resource(s)", total);
+    println!(
+        "Imported {} resource(s) into {}",
+        written,
+        args.out.display()
+    );
     Ok(())
 }
 
-fn collect_via_files(root: &Path) -> Result<Vec<PathBuf>> {
-    if !root.exists() {
-        return Err(anyhow!("Via directory not found: {}", root.display()));
+/// The resources `--since` limits rendering to, or `None` to render everything.
+fn incremental_resources(program: &Program, since: &str, quiet: bool) -> Option<BTreeSet<String>> {
+    let plan = match incremental::changed_files(since) {
+        Ok(changed) => incremental::plan(program, &changed),
+        Err(err) => Plan::Full(format!("{:#}", err)),
+    };
+    match plan {
+        Plan::Full(reason) => {
+            if !quiet {
+                println!("Regenerating everything: {}", reason);
+            }
+            None
+        }
+        Plan::Partial(resources) => {
+            if !quiet {
+                if resources.is_empty() {
+                    println!("No resources changed since {}", since);
+                } else {
+                    println!(
+                        "Regenerating {} resource(s) changed since {} or related to one: {}",
+                        resources.len(),
+                        since,
+                        resources.iter().cloned().collect::<Vec<_>>().join(", ")
+                    );
+                }
+            }
+            Some(resources)
+        }
+    }
+}
+
+/// `*` stays within one path segment; use `**` to cross directories.
+fn emit_only_matches(pattern: &glob::Pattern, relative_path: &Path) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    pattern.matches_path_with(relative_path, options)
+}
+
+/// An explicit `--config` must exist; the default `via.toml` is optional.
+fn load_config(path: Option<&Path>) -> Result<Config> {
+    match path {
+        Some(path) => Config::load(path),
+        None => Config::load_or_default(Path::new(config::CONFIG_FILE_NAME)),
+    }
+}
+
+/// `--app -` reads a single document from stdin; diagnostics name it `<stdin>`.
+const STDIN_ROOT: &str = "-";
+const STDIN_PATH: &str = "<stdin>";
+
+fn reads_stdin(roots: &[PathBuf]) -> bool {
+    roots.iter().any(|root| root == Path::new(STDIN_ROOT))
+}
+
+/// Parses one collected file, where [`STDIN_ROOT`] stands for the document on stdin.
+fn parse_via_file(file: &Path) -> Result<Program> {
+    if file != Path::new(STDIN_ROOT) {
+        return parser::parse_file(file);
+    }
+    let src = io::read_to_string(io::stdin()).context("Failed to read Via source from stdin")?;
+    parser::parse_str(&src, Path::new(STDIN_PATH))
+}
+
+fn load_program(files: &[PathBuf], strict: bool) -> Result<Program> {
+    let mut program = Program::default();
+    for file in files {
+        program.extend(parse_via_file(file)?);
+    }
+    let options = resolve::ResolveOptions { strict };
+    for warning in resolve::resolve_with_options(&mut program, &options)? {
+        anstream::eprintln!("{}", diagnostics::render_warning(&warning));
+    }
+    Ok(program)
+}
+
+/// Like [`load_program`], but reports every file that fails to parse, and, when they all parse,
+/// every resource that fails to resolve.
+fn collect_program(files: &[PathBuf], strict: bool) -> Result<Program, Vec<anyhow::Error>> {
+    let mut program = Program::default();
+    let mut errors = Vec::new();
+    for file in files {
+        match parse_via_file(file) {
+            Ok(parsed) => program.extend(parsed),
+            Err(err) => errors.push(err),
+        }
+    }
+    // Resolving a partial program would report references into the files that failed.
+    if !errors.is_empty() {
+        return Err(errors);
     }
+    let options = resolve::ResolveOptions { strict };
+    for warning in resolve::resolve_collecting(&mut program, &options)? {
+        anstream::eprintln!("{}", diagnostics::render_warning(&warning));
+    }
+    Ok(program)
+}
+
+/// Prints the first `max` errors and a count of the rest; the returned error fails the command.
+fn report_errors(errors: &[anyhow::Error], max: usize) -> anyhow::Error {
+    for err in errors.iter().take(max) {
+        anstream::eprintln!("{}", diagnostics::render_error(err));
+    }
+    if errors.len() > max {
+        anstream::eprintln!("... and {} more", errors.len() - max);
+    }
+    anyhow!("could not check the schema due to {} errors", errors.len())
+}
 
-    let mut files = Vec::new();
-    for entry in WalkDir::new(root) {
-        let entry = entry.with_context(|| "Failed to walk directory entry")?;
-        if entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "via") {
-            files.push(entry.into_path());
+/// Walks every app root (expanding glob patterns) and returns the sorted, de-duplicated set of
+/// `.via` files. Overlapping roots contribute each file only once. A `-` root is kept as is, for
+/// [`parse_via_file`] to read from stdin.
+fn collect_via_files(roots: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let (stdin, roots): (Vec<PathBuf>, Vec<PathBuf>) = roots
+        .iter()
+        .cloned()
+        .partition(|root| root == Path::new(STDIN_ROOT));
+    let mut files: Vec<PathBuf> = stdin.into_iter().take(1).collect();
+    let mut seen = HashSet::new();
+    for root in expand_roots(&roots)? {
+        let ignore = load_viaignore(&root)?;
+        let walker = WalkDir::new(&root)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry));
+        for entry in walker {
+            let entry = entry.with_context(|| "Failed to walk directory entry")?;
+            if entry.file_type().is_file()
+                && entry.path().extension().is_some_and(|ext| ext == "via")
+                && !ignore
+                    .matched_path_or_any_parents(entry.path(), false)
+                    .is_ignore()
+            {
+                let key =
+                    fs::canonicalize(entry.path()).unwrap_or_else(|_| entry.path().to_path_buf());
+                if seen.insert(key) {
+                    files.push(entry.into_path());
+                }
+            }
         }
     }
     files.sort();
     Ok(files)
 }
+
+/// Loads gitignore-style patterns from `<root>/.viaignore`, if present.
+fn load_viaignore(root: &Path) -> Result<Gitignore> {
+    let path = root.join(".viaignore");
+    if !path.is_file() {
+        return Ok(Gitignore::empty());
+    }
+    let mut builder = GitignoreBuilder::new(root);
+    if let Some(err) = builder.add(&path) {
+        return Err(anyhow!("Failed to read {}: {}", path.display(), err));
+    }
+    builder
+        .build()
+        .with_context(|| format!("Invalid pattern in {}", path.display()))
+}
+
+fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.file_name().to_string_lossy().starts_with('.')
+}
+
+fn expand_roots(roots: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for root in roots {
+        let pattern = root.to_string_lossy();
+        if !pattern.contains(['*', '?', '[']) {
+            if !root.exists() {
+                return Err(anyhow!("Via directory not found: {}", root.display()));
+            }
+            expanded.push(root.clone());
+            continue;
+        }
+
+        let matches = glob::glob(&pattern)
+            .with_context(|| format!("Invalid --app glob pattern {}", pattern))?
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Failed to expand --app glob pattern {}", pattern))?;
+        if matches.is_empty() {
+            return Err(anyhow!("No directories match --app pattern {}", pattern));
+        }
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+fn display_roots(roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// With `--fail-on-empty`, an app root with no .via files is an error rather than a no-op.
+fn ensure_found(roots: &[PathBuf], fail_on_empty: bool) -> Result<()> {
+    if fail_on_empty {
+        return Err(anyhow!(
+            "No .via files found under {} (--fail-on-empty)",
+            display_roots(roots)
+        ));
+    }
+    Ok(())
+}
@@ -1,28 +1,54 @@
-use std::{fs, path::Path};
+use std::{collections::HashSet, fs, path::Path};
 
 use anyhow::{Context, Result, anyhow};
+use convert_case::{Case, Casing};
 use pest::Parser;
 use pest_derive::Parser;
 
-use crate::ast::*;
+use crate::{ast::*, inflect::singularize};
 
 #[derive(Parser)]
 #[grammar = "via.pest"]
 struct ViaParser;
 
-pub fn parse_file(path: &Path) -> Result<Vec<Resource>> {
+/// Bounds checked before a source reaches the grammar, so hostile input (e.g. served to a
+/// language server or an upload endpoint) fails with an error instead of exhausting memory or
+/// the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Largest accepted source, in bytes.
+    pub max_bytes: usize,
+    /// Deepest accepted nesting of `{`, `[`, and `(`, counting brackets in manual action bodies.
+    pub max_depth: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 4 * 1024 * 1024,
+            max_depth: 32,
+        }
+    }
+}
+
+pub fn parse_file(path: &Path) -> Result<Program> {
     let src = fs::read_to_string(path)
         .with_context(|| format!("Failed to read Via file at {}", path.display()))?;
     parse_str(&src, path)
 }
 
-pub fn parse_str(src: &str, path: &Path) -> Result<Vec<Resource>> {
+pub fn parse_str(src: &str, path: &Path) -> Result<Program> {
+    parse_str_with_limits(src, path, &ParseLimits::default())
+}
+
+pub fn parse_str_with_limits(src: &str, path: &Path, limits: &ParseLimits) -> Result<Program> {
+    check_limits(src, path, limits)?;
     let pairs = ViaParser::parse(Rule::file, src).map_err(|err| {
         let path_display = path.to_string_lossy();
         anyhow!("{}", err.with_path(path_display.as_ref()))
     })?;
 
-    let mut resources = Vec::new();
+    let mut program = Program::default();
     let mut pairs_iter = pairs.into_iter();
     if let Some(file_pair) = pairs_iter.next() {
         if file_pair.as_rule() != Rule::file {
@@ -33,73 +59,504 @@ pub fn parse_str(src: &str, path: &Path) -> Result<Vec<Resource>> {
         }
         for pair in file_pair.into_inner() {
             match pair.as_rule() {
-                Rule::resource => resources.push(parse_resource(pair, path)?),
+                Rule::resource => {
+                    let resource = parse_resource(pair, path)?;
+                    program.enums.extend(inline_enums(&resource, path));
+                    program.resources.push(resource);
+                }
+                Rule::enum_decl => program.enums.push(parse_enum(pair, path)?),
                 Rule::EOI => {}
                 other => {
-                    return Err(anyhow!("Unexpected rule inside file: {:?}", other));
+                    return Err(error_at(
+                        path,
+                        pair.as_span(),
+                        format!("unexpected rule inside file `{:?}`", other),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+/// Rejects oversized input, and nesting beyond `limits.max_depth` at the bracket that crosses it.
+/// Brackets inside strings and comments don't count. Manual action bodies are Rust, so they're
+/// scanned like the grammar's `action_code`: `#` starts no comment there, and string and char
+/// literals may contain escapes.
+fn check_limits(src: &str, path: &Path, limits: &ParseLimits) -> Result<()> {
+    if src.len() > limits.max_bytes {
+        return Err(anyhow!(
+            "{}: input is {} bytes, over the {} byte limit",
+            path.display(),
+            src.len(),
+            limits.max_bytes
+        ));
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut in_comment = false;
+    // Depth inside an `actions { ... }` list; anything deeper is an action body.
+    let mut actions_depth: Option<usize> = None;
+    let (mut word, mut last_word) = (String::new(), String::new());
+    let (mut line, mut column) = (1, 0);
+    let mut chars = src.char_indices().peekable();
+    while let Some((at, ch)) = chars.next() {
+        column += 1;
+        let in_body = actions_depth.is_some_and(|actions| depth > actions);
+        if in_comment || in_string || in_body {
+            word.clear();
+        } else if ch.is_ascii_alphanumeric() || ch == '_' {
+            word.push(ch);
+        } else if !word.is_empty() {
+            last_word = std::mem::take(&mut word);
+        }
+        match ch {
+            '\n' => {
+                (line, column) = (line + 1, 0);
+                in_string = false;
+                in_comment = false;
+            }
+            _ if in_comment => {}
+            '"' | '\'' if in_body => {
+                let Some(len) = rust_literal_len(&src[at..]) else {
+                    continue;
+                };
+                while let Some(&(next, skipped)) = chars.peek() {
+                    if next >= at + len {
+                        break;
+                    }
+                    chars.next();
+                    column += 1;
+                    if skipped == '\n' {
+                        (line, column) = (line + 1, 0);
+                    }
+                }
+            }
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '#' if !in_body => in_comment = true,
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '/') => in_comment = true,
+            '{' | '[' | '(' => {
+                if ch == '{' && !in_body && last_word == "actions" {
+                    actions_depth = Some(depth + 1);
+                }
+                last_word.clear();
+                depth += 1;
+                if depth > limits.max_depth {
+                    return Err(anyhow!(
+                        "{}:{}:{}: nesting is deeper than {} levels",
+                        path.display(),
+                        line,
+                        column,
+                        limits.max_depth
+                    ));
+                }
+            }
+            '}' | ']' | ')' => {
+                if actions_depth == Some(depth) {
+                    actions_depth = None;
                 }
+                depth = depth.saturating_sub(1);
             }
+            _ => {}
         }
     }
+    Ok(())
+}
 
-    Ok(resources)
+/// Byte length of the Rust string or char literal that `code` starts with, matching the
+/// grammar's `action_string` and `action_char`. `None` when it isn't one (e.g. a lifetime).
+fn rust_literal_len(code: &str) -> Option<usize> {
+    let mut chars = code.char_indices();
+    match chars.next()? {
+        (_, '"') => {
+            while let Some((at, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => return Some(at + 1),
+                    _ => {}
+                }
+            }
+            None
+        }
+        (_, '\'') => match chars.next()? {
+            (_, '\\') => {
+                chars.next()?;
+                chars
+                    .take_while(|&(_, ch)| ch != '\n')
+                    .find(|&(_, ch)| ch == '\'')
+                    .map(|(at, _)| at + 1)
+            }
+            (_, '\'') => None,
+            _ => chars
+                .next()
+                .filter(|&(_, ch)| ch == '\'')
+                .map(|(at, _)| at + 1),
+        },
+        _ => None,
+    }
 }
 
 fn parse_resource(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<Resource> {
+    let span = span_of(&pair, path);
+    let at = pair.as_span();
     let mut inner = pair.into_inner();
     let name_pair = inner
         .next()
-        .ok_or_else(|| anyhow!("Resource missing identifier"))?;
+        .ok_or_else(|| error_at(path, at, "resource missing identifier"))?;
     let name = name_pair.as_str().to_owned();
 
+    let mut table: Option<String> = None;
+    let mut sti: Option<String> = None;
+    let mut default_order: Option<DefaultOrder> = None;
+    let mut default_scope: Vec<ScopeCondition> = Vec::new();
+    let mut pagination: Option<String> = None;
+    let mut derives: Vec<String> = Vec::new();
+    let mut deprecated: Option<String> = None;
+    let mut validations: Vec<RequiredWhen> = Vec::new();
     let mut model: Option<Model> = None;
     let mut controller: Option<Controller> = None;
 
     for item in inner {
+        let at = item.as_span();
         match item.as_rule() {
+            Rule::table_decl => {
+                let value = item
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| error_at(path, at, "table declaration missing name"))?;
+                table = Some(parse_literal_text(value));
+            }
+            Rule::sti_decl => {
+                let field = item
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| error_at(path, at, "sti declaration missing field name"))?;
+                sti = Some(field.as_str().to_owned());
+            }
+            Rule::default_order_decl => {
+                let mut parts = item.into_inner();
+                let field = parts
+                    .next()
+                    .ok_or_else(|| error_at(path, at, "default_order missing field name"))?;
+                default_order = Some(DefaultOrder {
+                    field: field.as_str().to_owned(),
+                    direction: parts.next().map(|direction| direction.as_str().to_owned()),
+                });
+            }
+            Rule::default_scope_decl => {
+                for condition in item.into_inner() {
+                    let at = condition.as_span();
+                    let mut parts = condition.into_inner();
+                    let (Some(field), Some(equals)) = (parts.next(), parts.next()) else {
+                        return Err(error_at(
+                            path,
+                            at,
+                            "incomplete `where ... == ...` condition",
+                        ));
+                    };
+                    default_scope.push(ScopeCondition {
+                        field: field.as_str().to_owned(),
+                        equals: parse_literal_text(equals),
+                    });
+                }
+            }
+            Rule::pagination_decl => {
+                let style = item
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| error_at(path, at, "pagination declaration missing style"))?;
+                pagination = Some(style.as_str().to_owned());
+            }
+            Rule::derives_decl => {
+                derives.extend(item.into_inner().map(|name| name.as_str().to_owned()));
+            }
+            Rule::deprecated_attr => {
+                let note = item
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| error_at(path, at, "deprecated attribute missing note"))?;
+                deprecated = Some(parse_literal_text(note));
+            }
+            Rule::validate_section => {
+                for rule in item.into_inner() {
+                    let at = rule.as_span();
+                    let mut parts = rule.into_inner();
+                    let (Some(field), Some(when), Some(equals)) =
+                        (parts.next(), parts.next(), parts.next())
+                    else {
+                        return Err(error_at(path, at, "incomplete `require ... when ...` rule"));
+                    };
+                    validations.push(RequiredWhen {
+                        field: field.as_str().to_owned(),
+                        when: when.as_str().to_owned(),
+                        equals: parse_literal_text(equals),
+                    });
+                }
+            }
             Rule::model_section => {
-                model = Some(parse_model(item)?);
+                model = Some(parse_model(item, &name, path)?);
             }
             Rule::controller_section => {
-                controller = Some(parse_controller(item)?);
+                controller = Some(parse_controller(item, path)?);
             }
             other => {
-                return Err(anyhow!("Unsupported resource item: {:?}", other));
+                return Err(error_at(
+                    path,
+                    at,
+                    format!("unsupported resource item `{:?}`", other),
+                ));
             }
         }
     }
 
     Ok(Resource {
         name,
+        table,
+        sti,
+        default_order,
+        default_scope,
+        pagination,
+        derives,
+        deprecated,
+        validations,
         model,
         controller,
         file_path: path.to_string_lossy().into_owned(),
+        span: Some(span),
+    })
+}
+
+fn span_of(pair: &pest::iterators::Pair<'_, Rule>, path: &Path) -> Span {
+    let (start_line, start_column) = pair.as_span().start_pos().line_col();
+    let (end_line, end_column) = pair.as_span().end_pos().line_col();
+    Span {
+        file: path.to_string_lossy().into_owned(),
+        start: SourceLocation {
+            line: start_line,
+            column: start_column,
+        },
+        end: SourceLocation {
+            line: end_line,
+            column: end_column,
+        },
+    }
+}
+
+/// `path:line:col: message`, pointing at where `at` starts in the source. Every error the
+/// parser raises past the grammar is located this way.
+fn error_at(path: &Path, at: pest::Span<'_>, message: impl std::fmt::Display) -> anyhow::Error {
+    let (line, column) = at.start_pos().line_col();
+    anyhow!("{}:{}:{}: {}", path.display(), line, column, message)
+}
+
+/// The `enum(...)` types written on `resource`'s fields, as declarations codegen can emit.
+fn inline_enums(resource: &Resource, path: &Path) -> Vec<EnumDecl> {
+    resource
+        .model
+        .iter()
+        .flat_map(|model| &model.fields)
+        .filter_map(|field| match &field.ty.kind {
+            TypeKind::Enum(variants) => Some(EnumDecl {
+                name: field.ty.name.clone(),
+                variants: variants.clone(),
+                file_path: path.to_string_lossy().into_owned(),
+                location: field.location,
+            }),
+            TypeKind::Named => None,
+        })
+        .collect()
+}
+
+fn parse_enum(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<EnumDecl> {
+    let (line, column) = pair.line_col();
+    let at = pair.as_span();
+    let mut inner = pair.into_inner();
+    let name = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "enum missing identifier"))?
+        .as_str()
+        .to_owned();
+    Ok(EnumDecl {
+        name,
+        variants: inner.map(|variant| variant.as_str().to_owned()).collect(),
+        file_path: path.to_string_lossy().into_owned(),
+        location: Some(SourceLocation { line, column }),
     })
 }
 
-fn parse_model(pair: pest::iterators::Pair<'_, Rule>) -> Result<Model> {
+/// Two `field`s with the same name would give the model struct a duplicate member. Clashes with
+/// the key columns relations add are left to resolve, which names the relation.
+fn parse_model(
+    pair: pest::iterators::Pair<'_, Rule>,
+    resource: &str,
+    path: &Path,
+) -> Result<Model> {
     let mut fields = Vec::new();
+    let mut polymorphic = Vec::new();
+    let mut relations = Vec::new();
+    let mut declared = HashSet::new();
 
     for item in pair.into_inner() {
         match item.as_rule() {
-            Rule::field_decl => fields.push(parse_field(item)?),
-            other => return Err(anyhow!("Unsupported model item: {:?}", other)),
+            Rule::field_decl => {
+                let at = item.as_span();
+                let field = parse_field(item, resource, path)?;
+                if !declared.insert(field.name.clone()) {
+                    return Err(error_at(
+                        path,
+                        at,
+                        format!("duplicate field `{}` in resource {}", field.name, resource),
+                    ));
+                }
+                fields.push(field);
+            }
+            Rule::belongs_to_decl => {
+                let (relation, columns) = parse_belongs_to(item, path)?;
+                polymorphic.push(relation);
+                fields.extend(columns);
+            }
+            Rule::relation_decl => {
+                let (relation, key) = parse_relation(item, path)?;
+                relations.push(relation);
+                fields.extend(key);
+            }
+            other => {
+                return Err(error_at(
+                    path,
+                    item.as_span(),
+                    format!("unsupported model item `{:?}`", other),
+                ));
+            }
         }
     }
 
-    Ok(Model { fields })
+    Ok(Model {
+        fields,
+        polymorphic,
+        relations,
+    })
+}
+
+/// `belongs_to post` plus its `post_id` key column, or `has_many comments`, which adds no
+/// column. The key is typed `Integer` until resolve copies the target's id type onto it.
+fn parse_relation(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<(Relation, Option<Field>)> {
+    let (line, column) = pair.line_col();
+    let span = span_of(&pair, path);
+    let at = pair.as_span();
+    let mut inner = pair.into_inner();
+    let kind = match inner.next().map(|kind| kind.as_rule()) {
+        Some(Rule::belongs_to_kind) => RelationKind::BelongsTo,
+        Some(Rule::has_many_kind) => RelationKind::HasMany,
+        _ => return Err(error_at(path, at, "relation missing belongs_to/has_many")),
+    };
+    let name_pair = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "relation missing name"))?;
+    let (name, optional) = match kind {
+        RelationKind::BelongsTo => parse_name_opt(name_pair, path)?,
+        RelationKind::HasMany => (name_pair.as_str().to_owned(), false),
+    };
+    let target = match kind {
+        RelationKind::BelongsTo => name.to_case(Case::Pascal),
+        RelationKind::HasMany => singularize(&name).to_case(Case::Pascal),
+    };
+    let relation = Relation {
+        kind,
+        name,
+        target,
+        optional,
+    };
+    let key = (kind == RelationKind::BelongsTo).then(|| Field {
+        name: relation.key_field(),
+        ty: TypeRef {
+            name: "Integer".to_owned(),
+            optional: false,
+            kind: TypeKind::Named,
+        },
+        optional,
+        attributes: FieldAttributes::default(),
+        name_optional: optional,
+        location: Some(SourceLocation { line, column }),
+        span: Some(span),
+    });
+    Ok((relation, key))
 }
 
-fn parse_field(pair: pest::iterators::Pair<'_, Rule>) -> Result<Field> {
+/// `belongs_to commentable: (Post | Image)` plus the columns it stands for:
+/// `commentable_type: String @in("Post", "Image")` and `commentable_id: Integer`.
+fn parse_belongs_to(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<(PolymorphicRelation, [Field; 2])> {
+    let (line, column) = pair.line_col();
+    let span = span_of(&pair, path);
+    let at = pair.as_span();
     let mut inner = pair.into_inner();
-    let name_pair = inner.next().ok_or_else(|| anyhow!("Field missing name"))?;
-    let (name, opt_flag) = parse_name_opt(name_pair)?;
-    let ty_pair = inner.next().ok_or_else(|| anyhow!("Field missing type"))?;
-    let ty = parse_type(ty_pair)?;
+    let name_pair = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "belongs_to missing name"))?;
+    let (name, optional) = parse_name_opt(name_pair, path)?;
+    let relation = PolymorphicRelation {
+        name,
+        optional,
+        targets: inner.map(|target| target.as_str().to_owned()).collect(),
+    };
+    let stored = |name: String, ty: &str, allowed: Option<Vec<String>>| Field {
+        name,
+        ty: TypeRef {
+            name: ty.to_owned(),
+            optional: false,
+            kind: TypeKind::Named,
+        },
+        optional,
+        attributes: FieldAttributes {
+            allowed,
+            ..Default::default()
+        },
+        name_optional: optional,
+        location: Some(SourceLocation { line, column }),
+        span: Some(span.clone()),
+    };
+    let columns = [
+        stored(
+            relation.type_field(),
+            "String",
+            Some(relation.targets.clone()),
+        ),
+        stored(relation.id_field(), "Integer", None),
+    ];
+    Ok((relation, columns))
+}
+
+fn parse_field(
+    pair: pest::iterators::Pair<'_, Rule>,
+    resource: &str,
+    path: &Path,
+) -> Result<Field> {
+    let (line, column) = pair.line_col();
+    let span = span_of(&pair, path);
+    let at = pair.as_span();
+    let mut inner = pair.into_inner();
+    let name_pair = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "field missing name"))?;
+    let (name, opt_flag) = parse_name_opt(name_pair, path)?;
+    let ty_pair = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, format!("field `{}` missing type", name)))?;
+    let enum_name = format!("{}{}", resource, name.to_case(Case::Pascal));
+    let ty = parse_type(ty_pair, &enum_name, path)?;
 
     let mut attributes = FieldAttributes::default();
     for attr_pair in inner {
-        parse_field_attr(attr_pair, &mut attributes)?;
+        parse_field_attr(attr_pair, &name, &mut attributes, path)?;
     }
 
     Ok(Field {
@@ -107,17 +564,23 @@ fn parse_field(pair: pest::iterators::Pair<'_, Rule>) -> Result<Field> {
         optional: opt_flag || ty.optional,
         ty,
         attributes,
+        name_optional: opt_flag,
+        location: Some(SourceLocation { line, column }),
+        span: Some(span),
     })
 }
 
 fn parse_field_attr(
     pair: pest::iterators::Pair<'_, Rule>,
+    field: &str,
     attrs: &mut FieldAttributes,
+    path: &Path,
 ) -> Result<()> {
+    let at = pair.as_span();
     match pair.as_rule() {
         Rule::field_attr => {
             for inner in pair.into_inner() {
-                parse_field_attr(inner, attrs)?;
+                parse_field_attr(inner, field, attrs, path)?;
             }
             Ok(())
         }
@@ -125,29 +588,231 @@ fn parse_field_attr(
             let mut inner = pair.into_inner();
             let value_pair = inner
                 .next()
-                .ok_or_else(|| anyhow!("serialize attribute missing value"))?;
-            attrs.serialize = Some(parse_bool(value_pair)?);
+                .ok_or_else(|| error_at(path, at, "serialize attribute missing value"))?;
+            attrs.serialize = Some(parse_bool(value_pair, path)?);
+            Ok(())
+        }
+        Rule::in_attr => {
+            let values = pair.into_inner().map(parse_literal_text).collect();
+            attrs.allowed = Some(values);
+            Ok(())
+        }
+        Rule::computed_attr => {
+            let expr = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "computed attribute missing expression"))?;
+            attrs.computed = Some(parse_literal_text(expr));
+            Ok(())
+        }
+        Rule::default_attr => {
+            let value = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "default missing value"))?;
+            attrs.default = Some(match value.as_rule() {
+                Rule::now_call => Literal::Now,
+                Rule::bool => Literal::Bool(parse_bool(value, path)?),
+                Rule::int => Literal::Integer(value.as_str().parse().map_err(|_| {
+                    error_at(
+                        path,
+                        value.as_span(),
+                        format!("default `{}` does not fit in 64 bits", value.as_str()),
+                    )
+                })?),
+                _ => Literal::String(parse_literal_text(value)),
+            });
+            Ok(())
+        }
+        Rule::encrypted_attr => {
+            attrs.encrypted = true;
+            Ok(())
+        }
+        Rule::sensitive_attr => {
+            attrs.sensitive = true;
+            Ok(())
+        }
+        Rule::transient_attr => {
+            attrs.transient = true;
+            Ok(())
+        }
+        Rule::title_attr => {
+            attrs.title = true;
+            Ok(())
+        }
+        Rule::feature_attr => {
+            let name = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "feature attribute missing name"))?;
+            attrs.feature = Some(parse_literal_text(name));
+            Ok(())
+        }
+        Rule::on_delete_attr => {
+            let action = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "on_delete attribute missing action"))?;
+            attrs.on_delete = Some(action.as_str().to_owned());
             Ok(())
         }
-        other => Err(anyhow!("Unsupported field attribute variant: {:?}", other)),
+        Rule::deprecated_attr => {
+            let note = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "deprecated attribute missing note"))?;
+            attrs.deprecated = Some(parse_literal_text(note));
+            Ok(())
+        }
+        Rule::min_len_attr | Rule::max_len_attr => {
+            let is_min = pair.as_rule() == Rule::min_len_attr;
+            let name = if is_min { "@min_len" } else { "@max_len" };
+            let value = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, format!("{} attribute missing length", name)))?
+                .as_str();
+            let length = value.parse::<u64>().map_err(|_| {
+                error_at(
+                    path,
+                    at,
+                    format!("{} length {} is out of range", name, value),
+                )
+            })?;
+            if is_min {
+                attrs.min_len = Some(length);
+            } else {
+                attrs.max_len = Some(length);
+            }
+            Ok(())
+        }
+        Rule::length_attr => {
+            let fail = |problem: &str| {
+                error_at(
+                    path,
+                    at,
+                    format!("`{}` on field `{}` {}", at.as_str(), field, problem),
+                )
+            };
+            let (mut min, mut max) = (None, None);
+            for bound in pair.into_inner() {
+                let length = bound
+                    .as_str()
+                    .parse::<u64>()
+                    .map_err(|_| fail("has a length that is out of range"))?;
+                if bound.as_rule() == Rule::length_start {
+                    min = Some(length);
+                } else {
+                    max = Some(length);
+                }
+            }
+            match (min, max) {
+                (None, None) => return Err(fail("has neither a minimum nor a maximum")),
+                (Some(min), Some(max)) if min > max => {
+                    return Err(fail("has its minimum above its maximum"));
+                }
+                _ => {}
+            }
+            attrs.min_len = min.or(attrs.min_len);
+            attrs.max_len = max.or(attrs.max_len);
+            Ok(())
+        }
+        Rule::min_attr | Rule::max_attr => {
+            let is_min = pair.as_rule() == Rule::min_attr;
+            let value = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "bound attribute missing value"))?
+                .as_str()
+                .to_owned();
+            if is_min {
+                attrs.min = Some(value);
+            } else {
+                attrs.max = Some(value);
+            }
+            Ok(())
+        }
+        Rule::format_attr => {
+            let name = pair
+                .into_inner()
+                .next()
+                .ok_or_else(|| error_at(path, at, "format attribute missing name"))?;
+            if !FIELD_FORMATS.contains(&name.as_str()) {
+                return Err(error_at(
+                    path,
+                    name.as_span(),
+                    format!(
+                        "unknown @format `{}` (expected one of: {})",
+                        name.as_str(),
+                        FIELD_FORMATS.join(", ")
+                    ),
+                ));
+            }
+            attrs.format = Some(name.as_str().to_owned());
+            Ok(())
+        }
+        Rule::label_attr => {
+            for item in pair.into_inner() {
+                if item.as_rule() == Rule::string {
+                    attrs.label = Some(parse_literal_text(item));
+                    continue;
+                }
+                let at = item.as_span();
+                let mut parts = item.into_inner();
+                let (Some(locale), Some(text)) = (parts.next(), parts.next()) else {
+                    return Err(error_at(path, at, "label attribute missing locale or text"));
+                };
+                let text = parse_literal_text(text);
+                if attrs
+                    .labels
+                    .insert(locale.as_str().to_owned(), text)
+                    .is_some()
+                {
+                    return Err(error_at(
+                        path,
+                        at,
+                        format!("duplicate @label locale `{}`", locale.as_str()),
+                    ));
+                }
+            }
+            Ok(())
+        }
+        other => Err(error_at(
+            path,
+            at,
+            format!("unsupported field attribute `{:?}`", other),
+        )),
     }
 }
 
-fn parse_controller(pair: pest::iterators::Pair<'_, Rule>) -> Result<Controller> {
+fn parse_controller(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<Controller> {
     let mut controller = Controller::default();
     for item in pair.into_inner() {
+        let at = item.as_span();
         match item.as_rule() {
             Rule::params_section => {
-                controller.params = parse_params_section(item)?;
+                controller.params = parse_params_section(item, path)?;
             }
             Rule::respond_with_section => {
                 controller.respond_with = parse_respond_with(item)?;
             }
             Rule::actions_section => {
-                controller.actions = ControllerActions::AutoCrud;
+                controller.actions = parse_actions_section(item, path)?;
+            }
+            Rule::authorize_section => {
+                controller.authorize = parse_authorize(item, path)?;
+            }
+            Rule::action_block => {
+                controller
+                    .action_overrides
+                    .push(parse_action_block(item, path)?);
             }
             other => {
-                return Err(anyhow!("Unsupported controller item: {:?}", other));
+                return Err(error_at(
+                    path,
+                    at,
+                    format!("unsupported controller item `{:?}`", other),
+                ));
             }
         }
     }
@@ -155,54 +820,166 @@ fn parse_controller(pair: pest::iterators::Pair<'_, Rule>) -> Result<Controller>
     Ok(controller)
 }
 
-fn parse_params_section(pair: pest::iterators::Pair<'_, Rule>) -> Result<Vec<ParamsProfile>> {
+fn parse_params_section(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<Vec<ParamsProfile>> {
     let mut profiles = Vec::new();
     for profile_pair in pair.into_inner() {
         match profile_pair.as_rule() {
-            Rule::params_profile => profiles.push(parse_params_profile(profile_pair)?),
-            other => return Err(anyhow!("Unsupported params profile: {:?}", other)),
+            Rule::params_profile => profiles.push(parse_params_profile(profile_pair, path)?),
+            other => {
+                return Err(error_at(
+                    path,
+                    profile_pair.as_span(),
+                    format!("unsupported params profile `{:?}`", other),
+                ));
+            }
         }
     }
 
     Ok(profiles)
 }
 
-fn parse_params_profile(pair: pest::iterators::Pair<'_, Rule>) -> Result<ParamsProfile> {
+fn parse_params_profile(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<ParamsProfile> {
+    let at = pair.as_span();
     let mut inner = pair.into_inner();
     let name_pair = inner
         .next()
-        .ok_or_else(|| anyhow!("Params profile missing name"))?;
+        .ok_or_else(|| error_at(path, at, "params profile missing name"))?;
     let kind = match name_pair.as_str() {
         "editable" => ParamsKind::Editable,
         other => ParamsKind::Named(other.to_owned()),
     };
 
-    let mut entries = Vec::new();
+    let mut entries: Vec<ParamEntry> = Vec::new();
+    let mut wildcard = false;
     if let Some(list_pair) = inner.next() {
-        match list_pair.as_rule() {
-            Rule::param_entry_list => {
-                for entry_pair in list_pair.into_inner() {
-                    entries.push(parse_param_entry(entry_pair)?);
-                }
+        let entry_pairs: Vec<_> = match list_pair.as_rule() {
+            Rule::param_entry_list => list_pair.into_inner().collect(),
+            Rule::param_entry => vec![list_pair],
+            Rule::wildcard => {
+                wildcard = true;
+                Vec::new()
             }
-            Rule::param_entry => {
-                entries.push(parse_param_entry(list_pair)?);
+            _ => Vec::new(),
+        };
+        for entry_pair in entry_pairs {
+            let at = entry_pair.as_span();
+            let entry = parse_param_entry(entry_pair, path)?;
+            if entries.iter().any(|seen| seen.name == entry.name) {
+                return Err(error_at(
+                    path,
+                    at,
+                    format!(
+                        "duplicate param `{}` in params profile {}",
+                        entry.name,
+                        name_pair.as_str()
+                    ),
+                ));
             }
-            _ => {}
+            entries.push(entry);
         }
     }
 
     Ok(ParamsProfile {
         name: kind,
         entries,
+        wildcard,
     })
 }
 
-fn parse_param_entry(pair: pest::iterators::Pair<'_, Rule>) -> Result<ParamEntry> {
-    let (name, optional) = parse_name_opt(pair)?;
+fn parse_param_entry(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<ParamEntry> {
+    let (name, optional) = parse_name_opt(pair, path)?;
     Ok(ParamEntry { name, optional })
 }
 
+/// `actions auto_crud`, or `actions { publish { ... } archive }` listing the actions by hand.
+/// A `{ ... }` after a name is kept verbatim (without the outer braces) as the handler body.
+fn parse_actions_section(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<ControllerActions> {
+    let mut actions: Vec<Action> = Vec::new();
+    for action_pair in pair.into_inner() {
+        let at = action_pair.as_span();
+        let mut inner = action_pair.into_inner();
+        let name = inner
+            .next()
+            .ok_or_else(|| error_at(path, at, "action missing name"))?
+            .as_str()
+            .to_owned();
+        if actions.iter().any(|action| action.name == name) {
+            return Err(error_at(path, at, format!("duplicate action `{}`", name)));
+        }
+        let body = inner
+            .next()
+            .and_then(|block| block.into_inner().next())
+            .map(|code| code.as_str().to_owned());
+        actions.push(Action { name, body });
+    }
+    Ok(if actions.is_empty() {
+        ControllerActions::AutoCrud
+    } else {
+        ControllerActions::Manual(actions)
+    })
+}
+
+fn parse_authorize(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<Option<Authorize>> {
+    let at = pair.as_span();
+    let value = pair
+        .into_inner()
+        .next()
+        .ok_or_else(|| error_at(path, at, "authorize missing value"))?;
+    match value.as_rule() {
+        Rule::bool => Ok(parse_bool(value, path)?.then_some(Authorize::All)),
+        Rule::action_list => Ok(Some(Authorize::Actions(
+            value
+                .into_inner()
+                .map(|action| action.as_str().to_owned())
+                .collect(),
+        ))),
+        other => Err(error_at(
+            path,
+            value.as_span(),
+            format!("unsupported authorize value `{:?}`", other),
+        )),
+    }
+}
+
+fn parse_action_block(
+    pair: pest::iterators::Pair<'_, Rule>,
+    path: &Path,
+) -> Result<ActionOverride> {
+    let at = pair.as_span();
+    let mut inner = pair.into_inner();
+    let name = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "action block missing name"))?
+        .as_str()
+        .to_owned();
+    let mut respond_with = None;
+    for item in inner {
+        match item.as_rule() {
+            Rule::respond_with_section => respond_with = Some(parse_respond_with(item)?),
+            other => {
+                return Err(error_at(
+                    path,
+                    item.as_span(),
+                    format!("unsupported action item `{:?}`", other),
+                ));
+            }
+        }
+    }
+    Ok(ActionOverride { name, respond_with })
+}
+
 fn parse_respond_with(pair: pest::iterators::Pair<'_, Rule>) -> Result<Vec<String>> {
     let mut formats = Vec::new();
     if let Some(list_pair) = pair.into_inner().next() {
@@ -219,30 +996,69 @@ fn parse_respond_with(pair: pest::iterators::Pair<'_, Rule>) -> Result<Vec<Strin
     Ok(formats)
 }
 
-fn parse_type(pair: pest::iterators::Pair<'_, Rule>) -> Result<TypeRef> {
+/// A field's type. An inline `enum(...)` is named `enum_name`, and must list at least one
+/// variant, each once.
+fn parse_type(
+    pair: pest::iterators::Pair<'_, Rule>,
+    enum_name: &str,
+    path: &Path,
+) -> Result<TypeRef> {
+    let at = pair.as_span();
     let mut inner = pair.into_inner();
     let ident = inner
         .next()
-        .ok_or_else(|| anyhow!("Type missing identifier"))?;
+        .ok_or_else(|| error_at(path, at, "type missing identifier"))?;
     let optional = inner
         .next()
         .map(|mark| mark.as_rule() == Rule::optional_mark)
         .unwrap_or(false);
 
+    if ident.as_rule() != Rule::inline_enum {
+        return Ok(TypeRef {
+            name: ident.as_str().to_owned(),
+            optional,
+            kind: TypeKind::Named,
+        });
+    }
+    let mut variants: Vec<String> = Vec::new();
+    for variant in ident.clone().into_inner() {
+        if variants.iter().any(|seen| seen == variant.as_str()) {
+            return Err(error_at(
+                path,
+                variant.as_span(),
+                format!(
+                    "enum variant `{}` is listed more than once",
+                    variant.as_str()
+                ),
+            ));
+        }
+        variants.push(variant.as_str().to_owned());
+    }
+    if variants.is_empty() {
+        return Err(error_at(
+            path,
+            ident.as_span(),
+            "enum(...) needs at least one variant",
+        ));
+    }
     Ok(TypeRef {
-        name: ident.as_str().to_owned(),
+        name: enum_name.to_owned(),
         optional,
+        kind: TypeKind::Enum(variants),
     })
 }
 
-fn parse_name_opt(pair: pest::iterators::Pair<'_, Rule>) -> Result<(String, bool)> {
+fn parse_name_opt(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<(String, bool)> {
     let preview = pair.clone().into_inner();
     if preview.clone().count() == 0 {
         return Ok((pair.as_str().to_owned(), false));
     }
 
+    let at = pair.as_span();
     let mut inner = pair.into_inner();
-    let ident_pair = inner.next().ok_or_else(|| anyhow!("Missing identifier"))?;
+    let ident_pair = inner
+        .next()
+        .ok_or_else(|| error_at(path, at, "missing identifier"))?;
     let optional = inner
         .next()
         .map(|mark| mark.as_rule() == Rule::optional_mark)
@@ -251,10 +1067,25 @@ fn parse_name_opt(pair: pest::iterators::Pair<'_, Rule>) -> Result<(String, bool
     Ok((ident_pair.as_str().to_owned(), optional))
 }
 
-fn parse_bool(pair: pest::iterators::Pair<'_, Rule>) -> Result<bool> {
+fn parse_bool(pair: pest::iterators::Pair<'_, Rule>, path: &Path) -> Result<bool> {
     match pair.as_str() {
         "true" => Ok(true),
         "false" => Ok(false),
-        other => Err(anyhow!("Unexpected bool literal: {other}")),
+        other => Err(error_at(
+            path,
+            pair.as_span(),
+            format!("unexpected bool literal `{}`", other),
+        )),
+    }
+}
+
+fn parse_literal_text(pair: pest::iterators::Pair<'_, Rule>) -> String {
+    match pair.as_rule() {
+        Rule::string => pair
+            .into_inner()
+            .next()
+            .map(|inner| inner.as_str().to_owned())
+            .unwrap_or_default(),
+        _ => pair.as_str().to_owned(),
     }
 }
@@ -0,0 +1,1689 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, anyhow};
+use convert_case::{Case, Casing};
+
+use crate::{
+    ast::{
+        self, Authorize, EnumDecl, Literal, MODEL_DERIVES, Model, ParamEntry, ParamsKind,
+        ParamsProfile, Program, Resource,
+    },
+    codegen::{ViaScalar, default_controller, orderable_fields, resolve_actions, sti_variants},
+    graph::{self, Relation, RelationKind},
+};
+
+/// Via types that `@in(...)` can constrain, split by how the literals are interpreted.
+const STRING_TYPES: &[&str] = &["String", "Text"];
+const INTEGER_TYPES: &[&str] = &["Integer", "BigInt"];
+
+/// Whether `value` is a literal of the integer type `ty`. Integer is 32-bit everywhere (loco's
+/// `int4` columns and auto-increment keys), BigInt 64-bit.
+fn fits_integer(ty: &str, value: &str) -> bool {
+    match ty {
+        "Integer" => value.parse::<i32>().is_ok(),
+        _ => value.parse::<i64>().is_ok(),
+    }
+}
+
+/// Via types `@min`/`@max` can bound.
+const NUMERIC_TYPES: &[&str] = &["Integer", "BigInt", "Float", "Decimal"];
+
+/// Formats `respond_with [...]` may list; each one needs matching support in the controller
+/// generator.
+const SUPPORTED_FORMATS: &[&str] = &["html", "json", "xml"];
+
+/// Referential actions `@on_delete(...)` accepts; each maps to a sea-orm `ForeignKeyAction`.
+pub const ON_DELETE_ACTIONS: &[&str] = &["cascade", "restrict", "set_null"];
+
+/// Field names treated as the primary key and therefore never editable.
+const PRIMARY_KEY_FIELDS: &[&str] = &["id"];
+
+/// Rust keywords (strict and reserved, edition 2024). Field names become struct fields and
+/// resource names become module names, neither of which may be a keyword.
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Words reserved by PostgreSQL, MySQL, or SQLite that fail as unquoted column or table names.
+const SQL_RESERVED_WORDS: &[&str] = &[
+    "all",
+    "alter",
+    "and",
+    "as",
+    "asc",
+    "between",
+    "by",
+    "case",
+    "check",
+    "column",
+    "constraint",
+    "create",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "drop",
+    "else",
+    "end",
+    "exists",
+    "foreign",
+    "from",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "index",
+    "insert",
+    "into",
+    "is",
+    "join",
+    "like",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "then",
+    "to",
+    "union",
+    "unique",
+    "update",
+    "user",
+    "values",
+    "when",
+    "where",
+];
+
+/// Knobs for the resolve pass. Defaults match plain `via check`/`via gen`.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Treat warnings (e.g. redundant `?` marks) as errors.
+    pub strict: bool,
+}
+
+/// Runs after parsing and before codegen: expands shorthand and checks cross-item consistency.
+/// On success the resources are sorted by name, so the IR and every generated aggregator list
+/// them in the same order no matter which file declares which resource.
+pub fn resolve(program: &mut Program) -> Result<()> {
+    resolve_with_options(program, &ResolveOptions::default()).map(|_| ())
+}
+
+/// Like [`resolve`], but returns the non-fatal warnings it found, or fails on them when
+/// `options.strict` is set.
+pub fn resolve_with_options(
+    program: &mut Program,
+    options: &ResolveOptions,
+) -> Result<Vec<String>> {
+    resolve_collecting(program, options).map_err(|errors| {
+        errors
+            .into_iter()
+            .next()
+            .expect("resolve_collecting fails with at least one error")
+    })
+}
+
+/// Like [`resolve_with_options`], but keeps going after a failed check so `via check` can report
+/// every broken resource at once. Each resource contributes at most its first error, since later
+/// checks on a broken resource mostly repeat it. The first error is the one
+/// [`resolve_with_options`] would return.
+pub fn resolve_collecting(
+    program: &mut Program,
+    options: &ResolveOptions,
+) -> std::result::Result<Vec<String>, Vec<anyhow::Error>> {
+    let mut errors = Vec::new();
+    errors.extend(check_enums(&program.enums).err());
+    let resources = &mut program.resources;
+    errors.extend(check_duplicate_resources(resources).err());
+    errors.extend(resolve_declared_relations(resources).err());
+    for resource in resources.iter_mut() {
+        errors.extend(check_resource(resource, &program.enums).err());
+    }
+    errors.extend(check_on_delete(resources).err());
+    errors.extend(check_transient_keys(resources).err());
+    errors.extend(check_relation_optionality(resources).err());
+    errors.extend(check_required_relation_cycles(resources).err());
+    errors.extend(check_polymorphic_relations(resources).err());
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut warnings: Vec<String> = resources.iter().flat_map(lint_optional_marks).collect();
+    warnings.extend(lint_unused_declarations(program));
+    if options.strict && !warnings.is_empty() {
+        return Err(vec![anyhow!("{}", warnings.join("\n"))]);
+    }
+    // Names are unique by now, so this order is total.
+    program.resources.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(warnings)
+}
+
+fn check_resource(resource: &mut Resource, enums: &[EnumDecl]) -> Result<()> {
+    check_reserved_identifiers(resource)?;
+    expand_params_wildcards(resource)?;
+    check_allowed_values(resource)?;
+    check_bounds(resource)?;
+    check_formats(resource)?;
+    check_computed_fields(resource)?;
+    resolve_field_defaults(resource, enums)?;
+    resolve_encrypted_fields(resource)?;
+    check_feature_fields(resource)?;
+    check_title_field(resource)?;
+    check_respond_formats(resource)?;
+    check_table_name(resource)?;
+    check_sti(resource, enums)?;
+    check_default_order(resource)?;
+    check_default_scope(resource, enums)?;
+    check_pagination(resource)?;
+    check_derives(resource, enums)?;
+    check_validations(resource)?;
+    check_authorize_actions(resource)?;
+    check_action_overrides(resource)
+}
+
+/// Enums that no field refers to.
+fn lint_unused_declarations(program: &Program) -> Vec<String> {
+    let used: HashSet<&str> = program
+        .resources
+        .iter()
+        .filter_map(|resource| resource.model.as_ref())
+        .flat_map(|model| &model.fields)
+        .map(|field| field.ty.name.as_str())
+        .collect();
+    program
+        .enums
+        .iter()
+        .filter(|decl| !used.contains(decl.name.as_str()))
+        .map(|decl| {
+            let location = decl
+                .location
+                .map(|loc| format!(":{}:{}", loc.line, loc.column))
+                .unwrap_or_default();
+            format!(
+                "{}{}: enum `{}` is declared but no field uses it",
+                decl.file_path, location, decl.name
+            )
+        })
+        .collect()
+}
+
+/// `title?: String?` marks the same field optional twice; one `?` is enough and the duplicate
+/// usually means one of them was meant to go.
+fn lint_optional_marks(resource: &Resource) -> Vec<String> {
+    let Some(model) = &resource.model else {
+        return Vec::new();
+    };
+    model
+        .fields
+        .iter()
+        .filter(|field| field.name_optional && field.ty.optional)
+        .map(|field| {
+            let location = field
+                .location
+                .map(|loc| format!(":{}:{}", loc.line, loc.column))
+                .unwrap_or_default();
+            format!(
+                "{}{}: field `{}` of {} is marked optional on both the name and the type (`{}?: {}?`); keep a single `?`",
+                resource.file_path, location, field.name, resource.name, field.name, field.ty.name
+            )
+        })
+        .collect()
+}
+
+/// Every variant becomes a Rust enum variant (PascalCase) and a TS literal, so variants must be
+/// present, distinct, and still distinct after case normalization (`Draft` vs `draft`). Names
+/// must be unique too, including the `<Resource><Field>` names inline `enum(...)` types get.
+fn check_enums(enums: &[EnumDecl]) -> Result<()> {
+    let mut declared: HashMap<&str, &EnumDecl> = HashMap::new();
+    for decl in enums {
+        if let Some(first) = declared.insert(&decl.name, decl) {
+            return Err(anyhow!(
+                "{}: duplicate enum `{}` (first defined in {})",
+                decl.file_path,
+                decl.name,
+                first.file_path
+            ));
+        }
+        if decl.variants.is_empty() {
+            return Err(anyhow!(
+                "{}: enum `{}` has no variants",
+                decl.file_path,
+                decl.name
+            ));
+        }
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for variant in &decl.variants {
+            let normalized = variant.to_case(Case::Pascal);
+            if let Some(previous) = seen.insert(normalized.clone(), variant) {
+                return Err(if previous == variant {
+                    anyhow!(
+                        "{}: enum `{}` lists variant `{}` more than once",
+                        decl.file_path,
+                        decl.name,
+                        variant
+                    )
+                } else {
+                    anyhow!(
+                        "{}: enum `{}` variants `{}` and `{}` both become `{}` in generated code",
+                        decl.file_path,
+                        decl.name,
+                        previous,
+                        variant,
+                        normalized
+                    )
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_duplicate_resources(resources: &[Resource]) -> Result<()> {
+    let mut seen: HashMap<&str, &Resource> = HashMap::new();
+    for resource in resources {
+        if let Some(first) = seen.insert(&resource.name, resource) {
+            return Err(anyhow!(
+                "{}: duplicate resource `{}` (first defined in {})",
+                resource.file_path,
+                resource.name,
+                first.file_path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A field typed as another resource (`owner?: Owner`) and the foreign key backing it
+/// (`owner_id`) must agree on optionality: the key's column is NOT NULL exactly when the key
+/// field is required, so an optional relation over a required key could never be left empty.
+fn check_relation_optionality(resources: &[Resource]) -> Result<()> {
+    let relations = graph::relations(resources);
+    for reference in relations
+        .iter()
+        .filter(|relation| relation.kind == RelationKind::References)
+    {
+        let key = format!("{}_id", reference.field);
+        let backed = relations.iter().any(|relation| {
+            relation.kind == RelationKind::BelongsTo
+                && relation.from == reference.from
+                && relation.to == reference.to
+                && relation.field == key
+        });
+        if !backed {
+            continue;
+        }
+        let Some((resource, model)) = resources
+            .iter()
+            .find(|resource| resource.name == reference.from)
+            .and_then(|resource| Some((resource, resource.model.as_ref()?)))
+        else {
+            continue;
+        };
+        let optional = |name: &str| {
+            model
+                .fields
+                .iter()
+                .any(|field| field.name == name && field.optional)
+        };
+        match (optional(&reference.field), optional(&key)) {
+            (true, false) => {
+                return Err(anyhow!(
+                    "{}: {}.{} is optional but its foreign key `{}` is required, so its column \
+                     would be NOT NULL; mark the key optional (`{}?`)",
+                    resource.file_path,
+                    resource.name,
+                    reference.field,
+                    key,
+                    key
+                ));
+            }
+            (false, true) => {
+                return Err(anyhow!(
+                    "{}: {}.{} is required but its foreign key `{}` is optional, so its column \
+                     would be nullable; mark the relation optional (`{}?`) or the key required",
+                    resource.file_path,
+                    resource.name,
+                    reference.field,
+                    key,
+                    reference.field
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Required `<resource>_id` keys fix which table has to be filled first, so a cycle of them
+/// leaves no row that can ever be inserted (and no order for the migrations). A cycle through an
+/// optional key is fine: that row goes in with NULL and is pointed at its target afterwards.
+fn check_required_relation_cycles(resources: &[Resource]) -> Result<()> {
+    let required: HashSet<(&str, &str)> = resources
+        .iter()
+        .filter_map(|resource| Some((resource, resource.model.as_ref()?)))
+        .flat_map(|(resource, model)| {
+            model
+                .fields
+                .iter()
+                .filter(|field| !field.optional)
+                .map(|field| (resource.name.as_str(), field.name.as_str()))
+        })
+        .collect();
+    let relations = graph::relations(resources);
+    let mut edges: HashMap<&str, Vec<&Relation>> = HashMap::new();
+    for relation in relations.iter().filter(|relation| {
+        relation.kind == RelationKind::BelongsTo
+            && required.contains(&(relation.from.as_str(), relation.field.as_str()))
+    }) {
+        edges.entry(&relation.from).or_default().push(relation);
+    }
+
+    let mut finished = HashSet::new();
+    for resource in resources {
+        let Some(cycle) = find_cycle(&resource.name, &edges, &mut Vec::new(), &mut finished) else {
+            continue;
+        };
+        let file_path = resources
+            .iter()
+            .find(|resource| resource.name == cycle[0].from)
+            .map_or("", |resource| resource.file_path.as_str());
+        let links: Vec<String> = cycle
+            .iter()
+            .map(|relation| format!("{}.{} -> {}", relation.from, relation.field, relation.to))
+            .collect();
+        let last = cycle[cycle.len() - 1];
+        return Err(anyhow!(
+            "{}: required foreign keys form a cycle ({}), so no row can be inserted first; \
+             make one of them optional (e.g. `{}?` on {})",
+            file_path,
+            links.join(", "),
+            last.field,
+            last.from
+        ));
+    }
+    Ok(())
+}
+
+/// Depth-first search from `node` along `edges`. `path` holds the edges taken to reach `node`;
+/// a cycle is returned as the edges from where it starts back round to that resource.
+fn find_cycle<'a>(
+    node: &'a str,
+    edges: &HashMap<&'a str, Vec<&'a Relation>>,
+    path: &mut Vec<&'a Relation>,
+    finished: &mut HashSet<&'a str>,
+) -> Option<Vec<&'a Relation>> {
+    if finished.contains(node) {
+        return None;
+    }
+    for &relation in edges.get(node).into_iter().flatten() {
+        let start = if relation.to == node {
+            Some(path.len())
+        } else {
+            path.iter().position(|taken| taken.from == relation.to)
+        };
+        if let Some(start) = start {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(relation);
+            return Some(cycle);
+        }
+        path.push(relation);
+        let cycle = find_cycle(&relation.to, edges, path, finished);
+        path.pop();
+        if cycle.is_some() {
+            return cycle;
+        }
+    }
+    finished.insert(node);
+    None
+}
+
+/// `belongs_to post` must name a resource with a model, and its `post_id` key takes that
+/// resource's id type. `has_many comments` needs `Comment` to hold a key pointing back here,
+/// declared with `belongs_to` or as a plain `<resource>_id` field.
+fn resolve_declared_relations(resources: &mut [Resource]) -> Result<()> {
+    let mut key_types = Vec::new();
+    for (index, resource) in resources.iter().enumerate() {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        for relation in &model.relations {
+            let keyword = relation.kind.keyword();
+            let Some(target_model) = resources
+                .iter()
+                .find(|candidate| candidate.name == relation.target)
+                .and_then(|candidate| candidate.model.as_ref())
+            else {
+                return Err(anyhow!(
+                    "{}: `{} {}` on {} points at `{}`, which is not a resource with a model",
+                    resource.file_path,
+                    keyword,
+                    relation.name,
+                    resource.name,
+                    relation.target
+                ));
+            };
+            match relation.kind {
+                ast::RelationKind::BelongsTo => {
+                    let key = relation.key_field();
+                    if model.fields.iter().filter(|f| f.name == key).count() > 1 {
+                        return Err(anyhow!(
+                            "{}: {} declares `{}` both as a field and through `belongs_to {}`",
+                            resource.file_path,
+                            resource.name,
+                            key,
+                            relation.name
+                        ));
+                    }
+                    let id_type = target_model
+                        .fields
+                        .iter()
+                        .find(|field| field.name == "id")
+                        .map_or("Integer", |field| field.ty.name.as_str());
+                    key_types.push((index, key, id_type.to_owned()));
+                }
+                ast::RelationKind::HasMany => {
+                    let key = format!("{}_id", resource.name.to_case(Case::Snake));
+                    if !target_model.fields.iter().any(|field| field.name == key) {
+                        return Err(anyhow!(
+                            "{}: `has_many {}` on {} needs {} to `belongs_to {}` (or declare a \
+                             `{}` field)",
+                            resource.file_path,
+                            relation.name,
+                            resource.name,
+                            relation.target,
+                            resource.name.to_case(Case::Snake),
+                            key
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    for (index, key, id_type) in key_types {
+        let fields = resources[index]
+            .model
+            .iter_mut()
+            .flat_map(|model| &mut model.fields);
+        for field in fields.filter(|field| field.name == key) {
+            field.ty.name = id_type.clone();
+        }
+    }
+    Ok(())
+}
+
+/// Each `belongs_to name: (A | B)` target must be a distinct resource with a model and an
+/// integer id, since one `<name>_id` column holds ids of every target.
+fn check_polymorphic_relations(resources: &[Resource]) -> Result<()> {
+    for resource in resources {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        for relation in &model.polymorphic {
+            for column in [relation.type_field(), relation.id_field()] {
+                if model.fields.iter().filter(|f| f.name == column).count() > 1 {
+                    return Err(anyhow!(
+                        "{}: {} declares `{}` both as a field and through `belongs_to {}`",
+                        resource.file_path,
+                        resource.name,
+                        column,
+                        relation.name
+                    ));
+                }
+            }
+            for (index, target) in relation.targets.iter().enumerate() {
+                if relation.targets[..index].contains(target) {
+                    return Err(anyhow!(
+                        "{}: `belongs_to {}` on {} lists `{}` twice",
+                        resource.file_path,
+                        relation.name,
+                        resource.name,
+                        target
+                    ));
+                }
+                let Some(target_model) = resources
+                    .iter()
+                    .find(|candidate| &candidate.name == target)
+                    .and_then(|candidate| candidate.model.as_ref())
+                else {
+                    return Err(anyhow!(
+                        "{}: `belongs_to {}` on {} targets `{}`, which is not a resource with a \
+                         model",
+                        resource.file_path,
+                        relation.name,
+                        resource.name,
+                        target
+                    ));
+                };
+                let id_type = target_model
+                    .fields
+                    .iter()
+                    .find(|field| field.name == "id")
+                    .map_or("Integer", |field| field.ty.name.as_str());
+                if id_type != "Integer" {
+                    return Err(anyhow!(
+                        "{}: `belongs_to {}` on {} targets `{}`, whose id is {}; `{}` holds \
+                         Integer ids",
+                        resource.file_path,
+                        relation.name,
+                        resource.name,
+                        target,
+                        id_type,
+                        relation.id_field()
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `@on_delete` only means something on a key that becomes a foreign key constraint, and
+/// `set_null` needs a column that can hold NULL.
+fn check_on_delete(resources: &[Resource]) -> Result<()> {
+    let foreign_keys: HashSet<(String, String)> = graph::relations(resources)
+        .into_iter()
+        .filter(|relation| relation.kind == RelationKind::BelongsTo)
+        .map(|relation| (relation.from, relation.field))
+        .collect();
+    for resource in resources {
+        let Some(model) = &resource.model else {
+            continue;
+        };
+        for field in &model.fields {
+            let Some(action) = &field.attributes.on_delete else {
+                continue;
+            };
+            if !ON_DELETE_ACTIONS.contains(&action.as_str()) {
+                return Err(anyhow!(
+                    "{}: unknown @on_delete action `{}` on {}.{} (expected one of: {})",
+                    resource.file_path,
+                    action,
+                    resource.name,
+                    field.name,
+                    ON_DELETE_ACTIONS.join(", ")
+                ));
+            }
+            if !foreign_keys.contains(&(resource.name.clone(), field.name.clone())) {
+                return Err(anyhow!(
+                    "{}: @on_delete on {}.{} needs a foreign key named `<resource>_id`",
+                    resource.file_path,
+                    resource.name,
+                    field.name
+                ));
+            }
+            if action == "set_null" && !field.optional {
+                return Err(anyhow!(
+                    "{}: @on_delete(set_null) on {}.{} needs an optional field (`{}?`)",
+                    resource.file_path,
+                    resource.name,
+                    field.name,
+                    field.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A `<resource>_id` key backs a relation on its column, so it can't be `@transient`.
+fn check_transient_keys(resources: &[Resource]) -> Result<()> {
+    for relation in graph::relations(resources)
+        .iter()
+        .filter(|relation| relation.kind == RelationKind::BelongsTo)
+    {
+        let Some(resource) = resources
+            .iter()
+            .find(|resource| resource.name == relation.from)
+        else {
+            continue;
+        };
+        let transient = resource
+            .model
+            .iter()
+            .flat_map(|model| &model.fields)
+            .any(|field| field.name == relation.field && field.attributes.transient);
+        if transient {
+            return Err(anyhow!(
+                "{}: foreign key {}.{} can't be @transient; it needs a column for the relation to {}",
+                resource.file_path,
+                resource.name,
+                relation.field,
+                relation.to
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Names that would only surface later as a `cargo check` failure or a SQL syntax error:
+/// Rust keywords as module or field names, and SQL reserved words as table or column names.
+fn check_reserved_identifiers(resource: &Resource) -> Result<()> {
+    let module_name = resource.name.to_case(Case::Snake);
+    if RUST_KEYWORDS.contains(&module_name.as_str()) {
+        return Err(anyhow!(
+            "{}: resource {} would generate module `{}`, which is a Rust keyword; rename the \
+             resource",
+            resource.file_path,
+            resource.name,
+            module_name
+        ));
+    }
+    let table = resource.table_name().to_lowercase();
+    if SQL_RESERVED_WORDS.contains(&table.as_str()) {
+        return Err(anyhow!(
+            "{}: table name `{}` of {} is a reserved SQL word; pick another with `table \"...\"`",
+            resource.file_path,
+            table,
+            resource.name
+        ));
+    }
+
+    let fields = resource
+        .model
+        .iter()
+        .flat_map(|model| &model.fields)
+        .map(|field| field.name.as_str());
+    let params = resource
+        .controller
+        .iter()
+        .flat_map(|controller| &controller.params)
+        .flat_map(|profile| &profile.entries)
+        .map(|entry| entry.name.as_str());
+    for name in fields.clone().chain(params) {
+        if RUST_KEYWORDS.contains(&name) {
+            return Err(anyhow!(
+                "{}: field `{}` of {} is a Rust keyword and can't be a struct field; rename it \
+                 (e.g. `{}_{}`)",
+                resource.file_path,
+                name,
+                resource.name,
+                module_name,
+                name
+            ));
+        }
+    }
+    for name in fields {
+        if SQL_RESERVED_WORDS.contains(&name.to_lowercase().as_str()) {
+            return Err(anyhow!(
+                "{}: field `{}` of {} is a reserved SQL word and can't be an unquoted column; \
+                 rename it (e.g. `{}_{}`)",
+                resource.file_path,
+                name,
+                resource.name,
+                module_name,
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_respond_formats(resource: &Resource) -> Result<()> {
+    let Some(controller) = &resource.controller else {
+        return Ok(());
+    };
+    let overrides = controller.action_overrides.iter().filter_map(|entry| {
+        entry
+            .respond_with
+            .as_ref()
+            .map(|formats| (format!(" (action `{}`)", entry.name), formats))
+    });
+    for (scope, formats) in
+        std::iter::once((String::new(), &controller.respond_with)).chain(overrides)
+    {
+        if let Some(format) = formats
+            .iter()
+            .find(|format| !SUPPORTED_FORMATS.contains(&format.as_str()))
+        {
+            return Err(anyhow!(
+                "{}: unsupported respond_with format `{}` in {}{} (expected one of: {})",
+                resource.file_path,
+                format,
+                resource.name,
+                scope,
+                SUPPORTED_FORMATS.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `table "..."` is spliced into generated SQL and entity attributes, so keep it to a plain
+/// identifier.
+fn check_table_name(resource: &Resource) -> Result<()> {
+    let Some(table) = &resource.table else {
+        return Ok(());
+    };
+    let valid = table
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && table
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if !valid {
+        return Err(anyhow!(
+            "{}: table name `{}` of {} must be a plain identifier (letters, digits, `_`)",
+            resource.file_path,
+            table,
+            resource.name
+        ));
+    }
+    Ok(())
+}
+
+/// `sti: kind` splits the TypeScript model into one interface per variant, so the discriminator
+/// has to be a required, serialized field with a closed set of values: an enum, or a `String`
+/// restricted with `@in(...)`.
+fn check_sti(resource: &Resource, enums: &[EnumDecl]) -> Result<()> {
+    let Some(discriminator) = &resource.sti else {
+        return Ok(());
+    };
+    let field = resource
+        .model
+        .as_ref()
+        .and_then(|model| {
+            model
+                .fields
+                .iter()
+                .find(|field| &field.name == discriminator)
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "{}: sti names unknown field `{}` of {}",
+                resource.file_path,
+                discriminator,
+                resource.name
+            )
+        })?;
+    if field.optional || field.attributes.serialize == Some(false) {
+        return Err(anyhow!(
+            "{}: sti field `{}` of {} must be required and serialized",
+            resource.file_path,
+            discriminator,
+            resource.name
+        ));
+    }
+    let Some(variants) = sti_variants(field, enums) else {
+        return Err(anyhow!(
+            "{}: sti field `{}` of {} must be an enum or a String with @in(...)",
+            resource.file_path,
+            discriminator,
+            resource.name
+        ));
+    };
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for variant in &variants {
+        let suffix = variant.to_case(Case::Pascal);
+        let valid = suffix
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic())
+            && suffix.chars().all(|ch| ch.is_ascii_alphanumeric());
+        if !valid {
+            return Err(anyhow!(
+                "{}: sti value `{}` of {} does not make a valid type name",
+                resource.file_path,
+                variant,
+                resource.name
+            ));
+        }
+        if let Some(previous) = seen.insert(suffix.clone(), variant) {
+            return Err(anyhow!(
+                "{}: sti values `{}` and `{}` of {} both become `{}{}`",
+                resource.file_path,
+                previous,
+                variant,
+                resource.name,
+                resource.name,
+                suffix
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `default_order` is applied by the generated `index`, so it needs that action, a column it
+/// can sort on, and an `asc`/`desc` direction.
+fn check_default_order(resource: &Resource) -> Result<()> {
+    let Some(order) = &resource.default_order else {
+        return Ok(());
+    };
+    if !matches!(order.direction(), "asc" | "desc") {
+        return Err(anyhow!(
+            "{}: default_order direction `{}` of {} must be `asc` or `desc`",
+            resource.file_path,
+            order.direction(),
+            resource.name
+        ));
+    }
+    let orderable = orderable_fields(resource.model.as_ref());
+    if !orderable.contains(&order.field) {
+        return Err(anyhow!(
+            "{}: default_order names unknown field `{}` of {} (orderable: {})",
+            resource.file_path,
+            order.field,
+            resource.name,
+            orderable.join(", ")
+        ));
+    }
+    let controller = resource
+        .controller
+        .clone()
+        .unwrap_or_else(default_controller);
+    if !resolve_actions(&controller)
+        .iter()
+        .any(|action| action.action_name == "index")
+    {
+        return Err(anyhow!(
+            "{}: default_order on {} needs an `index` action",
+            resource.file_path,
+            resource.name
+        ));
+    }
+    Ok(())
+}
+
+/// Each `default_scope` condition compares a stored column with a literal of its type: `true`
+/// or `false` for a Boolean, an integer for Integer/BigInt, a variant for an enum, and one of
+/// the `@in` values when the field has them.
+fn check_default_scope(resource: &Resource, enums: &[EnumDecl]) -> Result<()> {
+    if resource.default_scope.is_empty() {
+        return Ok(());
+    }
+    let Some(model) = &resource.model else {
+        return Err(anyhow!(
+            "{}: default_scope on {} requires a model block",
+            resource.file_path,
+            resource.name
+        ));
+    };
+    for condition in &resource.default_scope {
+        let Some(field) = model
+            .fields
+            .iter()
+            .find(|field| field.name == condition.field)
+        else {
+            return Err(anyhow!(
+                "{}: default_scope of {} names unknown field `{}`",
+                resource.file_path,
+                resource.name,
+                condition.field
+            ));
+        };
+        let unfilterable = if field.attributes.computed.is_some() {
+            Some("computed")
+        } else if field.attributes.transient {
+            Some("@transient")
+        } else if field.attributes.encrypted {
+            Some("@encrypted")
+        } else if field.attributes.feature.is_some() {
+            Some("behind a @feature")
+        } else {
+            None
+        };
+        if let Some(reason) = unfilterable {
+            return Err(anyhow!(
+                "{}: default_scope of {} can't filter on `{}` because it is {}",
+                resource.file_path,
+                resource.name,
+                field.name,
+                reason
+            ));
+        }
+        let ty = field.ty.name.as_str();
+        let expected = if let Some(decl) = enums.iter().find(|decl| decl.name == ty) {
+            (!decl.variants.contains(&condition.equals))
+                .then(|| format!("one of {} ({})", decl.name, decl.variants.join(", ")))
+        } else if let Some(allowed) = &field.attributes.allowed {
+            (!allowed.contains(&condition.equals))
+                .then(|| format!("one of its @in values ({})", allowed.join(", ")))
+        } else if ty == "Boolean" {
+            (!matches!(condition.equals.as_str(), "true" | "false"))
+                .then(|| "`true` or `false`".to_owned())
+        } else if INTEGER_TYPES.contains(&ty) {
+            (!fits_integer(ty, &condition.equals)).then(|| {
+                if condition.equals.parse::<i64>().is_ok() {
+                    format!("an integer that fits in {}", ty)
+                } else {
+                    "an integer".to_owned()
+                }
+            })
+        } else if STRING_TYPES.contains(&ty) {
+            None
+        } else {
+            return Err(anyhow!(
+                "{}: default_scope of {} can't filter on `{}`: only Boolean, String, Text, \
+                 Integer, BigInt, and enum fields can be compared",
+                resource.file_path,
+                resource.name,
+                field.name
+            ));
+        };
+        if let Some(expected) = expected {
+            return Err(anyhow!(
+                "{}: `where {} == {:?}` in default_scope of {} must compare with {}",
+                resource.file_path,
+                field.name,
+                condition.equals,
+                resource.name,
+                expected
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `pagination: cursor` pages `index` by keyset on the `default_order` field (or `id`), so that
+/// field can't be null and the resource needs a model to page over.
+fn check_pagination(resource: &Resource) -> Result<()> {
+    let Some(style) = &resource.pagination else {
+        return Ok(());
+    };
+    if style != "cursor" {
+        return Err(anyhow!(
+            "{}: unknown pagination `{}` of {} (expected `cursor`)",
+            resource.file_path,
+            style,
+            resource.name
+        ));
+    }
+    let Some(model) = &resource.model else {
+        return Err(anyhow!(
+            "{}: cursor pagination on {} needs a model",
+            resource.file_path,
+            resource.name
+        ));
+    };
+    if let Some(order) = &resource.default_order
+        && model
+            .fields
+            .iter()
+            .any(|field| field.name == order.field && field.optional)
+    {
+        return Err(anyhow!(
+            "{}: cursor pagination on {} orders by `{}`, which must be a required field",
+            resource.file_path,
+            resource.name,
+            order.field
+        ));
+    }
+    let controller = resource
+        .controller
+        .clone()
+        .unwrap_or_else(default_controller);
+    if !resolve_actions(&controller)
+        .iter()
+        .any(|action| action.action_name == "index")
+    {
+        return Err(anyhow!(
+            "{}: cursor pagination on {} needs an `index` action",
+            resource.file_path,
+            resource.name
+        ));
+    }
+    Ok(())
+}
+
+/// `require <field> when <other> == <value>` compiles to a comparison in the generated validator,
+/// so both fields must exist as plain columns and the literal must fit the compared type.
+fn check_validations(resource: &Resource) -> Result<()> {
+    let Some(rule) = resource.validations.first() else {
+        return Ok(());
+    };
+    let Some(model) = &resource.model else {
+        return Err(anyhow!(
+            "{}: `require {} when ...` on {} needs a model",
+            resource.file_path,
+            rule.field,
+            resource.name
+        ));
+    };
+    let find = |name: &str| {
+        model
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "{}: validate rule on {} refers to unknown field `{}`",
+                    resource.file_path,
+                    resource.name,
+                    name
+                )
+            })
+    };
+    for rule in &resource.validations {
+        let field = find(&rule.field)?;
+        let when = find(&rule.when)?;
+        if !field.optional {
+            return Err(anyhow!(
+                "{}: `require {}` on {} has no effect because the field is always required; \
+                 make it optional (`{}?`)",
+                resource.file_path,
+                field.name,
+                resource.name,
+                field.name
+            ));
+        }
+        if !field.attributes.stored() {
+            return Err(anyhow!(
+                "{}: `require {}` on {} names a {} field, which callers can't supply",
+                resource.file_path,
+                field.name,
+                resource.name,
+                if field.attributes.transient {
+                    "transient"
+                } else {
+                    "computed"
+                }
+            ));
+        }
+        if let Some(gated) = [field, when]
+            .into_iter()
+            .find(|field| field.attributes.feature.is_some())
+        {
+            return Err(anyhow!(
+                "{}: validate rule on {} can't refer to `@feature` field `{}`",
+                resource.file_path,
+                resource.name,
+                gated.name
+            ));
+        }
+        let ty = when.ty.name.as_str();
+        if when.attributes.encrypted || !(STRING_TYPES.contains(&ty) || INTEGER_TYPES.contains(&ty))
+        {
+            return Err(anyhow!(
+                "{}: `when {} == ...` on {} requires a String, Text, Integer, or BigInt field that \
+                 isn't @encrypted",
+                resource.file_path,
+                when.name,
+                resource.name
+            ));
+        }
+        if INTEGER_TYPES.contains(&ty) && !fits_integer(ty, &rule.equals) {
+            return Err(anyhow!(
+                "{}: `when {} == {:?}` on {} compares a {} field with a value that isn't one",
+                resource.file_path,
+                when.name,
+                rule.equals,
+                resource.name,
+                ty
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_authorize_actions(resource: &Resource) -> Result<()> {
+    let Some(controller) = &resource.controller else {
+        return Ok(());
+    };
+    let Some(Authorize::Actions(names)) = &controller.authorize else {
+        return Ok(());
+    };
+    let actions = resolve_actions(controller);
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !actions.iter().any(|action| &action.action_name == *name))
+    {
+        let known = actions
+            .iter()
+            .map(|action| action.action_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(anyhow!(
+            "{}: authorize lists unknown action `{}` in {} (actions: {})",
+            resource.file_path,
+            unknown,
+            resource.name,
+            known
+        ));
+    }
+    Ok(())
+}
+
+/// `action <name> { ... }` blocks must name a generated action, at most once each.
+fn check_action_overrides(resource: &Resource) -> Result<()> {
+    let Some(controller) = &resource.controller else {
+        return Ok(());
+    };
+    let actions = resolve_actions(controller);
+    let mut seen = HashSet::new();
+    for entry in &controller.action_overrides {
+        if !actions
+            .iter()
+            .any(|action| action.action_name == entry.name)
+        {
+            let known = actions
+                .iter()
+                .map(|action| action.action_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(anyhow!(
+                "{}: action block names unknown action `{}` in {} (actions: {})",
+                resource.file_path,
+                entry.name,
+                resource.name,
+                known
+            ));
+        }
+        if !seen.insert(entry.name.as_str()) {
+            return Err(anyhow!(
+                "{}: action `{}` of {} has more than one action block",
+                resource.file_path,
+                entry.name,
+                resource.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `@encrypted` fields hold ciphertext at rest, so they must be text, can't be constrained or
+/// derived by value, and are forced to `serialize: false` so they never reach a response.
+fn resolve_encrypted_fields(resource: &mut Resource) -> Result<()> {
+    let Some(model) = &mut resource.model else {
+        return Ok(());
+    };
+    for field in model
+        .fields
+        .iter_mut()
+        .filter(|field| field.attributes.encrypted)
+    {
+        let conflict = if !STRING_TYPES.contains(&field.ty.name.as_str()) {
+            Some(format!(
+                "has type {} (expected String or Text)",
+                field.ty.name
+            ))
+        } else if field.attributes.allowed.is_some() {
+            Some("also uses `@in`".to_owned())
+        } else if field.attributes.computed.is_some() {
+            Some("is computed".to_owned())
+        } else if field.attributes.transient {
+            Some("is transient".to_owned())
+        } else if field.attributes.serialize == Some(true) {
+            Some("is marked `serialize: true`".to_owned())
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            return Err(anyhow!(
+                "{}: `@encrypted` field `{}` of {} {}",
+                resource.file_path,
+                field.name,
+                resource.name,
+                conflict
+            ));
+        }
+        field.attributes.serialize = Some(false);
+    }
+    Ok(())
+}
+
+/// A default must be a value of its field's type (an enum variant or `@in` value where those
+/// apply, `now()` only for `DateTime`) on a stored, unencrypted field. The field is made
+/// non-optional: rows always get a value, so its column is NOT NULL even when written `name?`.
+fn resolve_field_defaults(resource: &mut Resource, enums: &[EnumDecl]) -> Result<()> {
+    let Some(model) = &mut resource.model else {
+        return Ok(());
+    };
+    for field in &mut model.fields {
+        let Some(default) = &field.attributes.default else {
+            continue;
+        };
+        let ty = field.ty.name.as_str();
+        let variants = enums
+            .iter()
+            .find(|decl| decl.name == ty)
+            .map(|decl| &decl.variants);
+        let conflict = if field.attributes.transient {
+            Some("is on a transient field, which is never stored".to_owned())
+        } else if field.attributes.encrypted {
+            Some("is on an encrypted field".to_owned())
+        } else {
+            let fits = match (default, variants) {
+                (Literal::String(value), Some(variants)) => variants.contains(value),
+                (Literal::String(_), None) => STRING_TYPES.contains(&ty),
+                (Literal::Integer(value), None) => {
+                    INTEGER_TYPES.contains(&ty) && fits_integer(ty, &value.to_string())
+                }
+                (Literal::Bool(_), None) => ty == "Boolean",
+                (Literal::Now, None) => ty == "DateTime",
+                _ => false,
+            };
+            let allowed = field.attributes.allowed.as_ref().is_none_or(|allowed| {
+                allowed.contains(&match default {
+                    Literal::String(value) => value.clone(),
+                    other => other.to_string(),
+                })
+            });
+            if !fits {
+                Some(format!("does not match its type {}", ty))
+            } else if !allowed {
+                Some("is not one of its `@in` values".to_owned())
+            } else {
+                None
+            }
+        };
+        if let Some(conflict) = conflict {
+            return Err(anyhow!(
+                "{}: default `{}` for `{}` of {} {}",
+                resource.file_path,
+                default,
+                field.name,
+                resource.name,
+                conflict
+            ));
+        }
+        field.optional = false;
+        field.ty.optional = false;
+    }
+    Ok(())
+}
+
+/// `@feature("...")` names become cargo features of the generated crate, so they must be valid
+/// feature names. Encrypted fields can't be gated: `ENCRYPTED_FIELDS` is a plain array.
+fn check_feature_fields(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    for field in &model.fields {
+        let Some(feature) = &field.attributes.feature else {
+            continue;
+        };
+        let valid = feature
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && feature
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(anyhow!(
+                "{}: feature `{}` on field `{}` of {} must be a cargo feature name (letters, digits, `_`, `-`)",
+                resource.file_path,
+                feature,
+                field.name,
+                resource.name
+            ));
+        }
+        if field.attributes.encrypted {
+            return Err(anyhow!(
+                "{}: `@encrypted` field `{}` of {} cannot also use `@feature`",
+                resource.file_path,
+                field.name,
+                resource.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `@title` marks at most one String/Text field per model, and one the TypeScript `summary`
+/// helper can read: serialized (so not `@encrypted`) and not behind a `@feature`.
+fn check_title_field(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    let titles: Vec<&str> = model
+        .fields
+        .iter()
+        .filter(|field| field.attributes.title)
+        .map(|field| field.name.as_str())
+        .collect();
+    if titles.len() > 1 {
+        return Err(anyhow!(
+            "{}: {} marks more than one field @title ({}); keep one",
+            resource.file_path,
+            resource.name,
+            titles.join(", ")
+        ));
+    }
+    let Some(field) = model.fields.iter().find(|field| field.attributes.title) else {
+        return Ok(());
+    };
+    let problem = if !STRING_TYPES.contains(&field.ty.name.as_str()) {
+        Some(format!("is {}, not String or Text", field.ty.name))
+    } else if field.attributes.serialize == Some(false) {
+        Some("isn't serialized".to_owned())
+    } else if field.attributes.feature.is_some() {
+        Some("is behind a @feature".to_owned())
+    } else {
+        None
+    };
+    if let Some(problem) = problem {
+        return Err(anyhow!(
+            "{}: @title field `{}` of {} {}",
+            resource.file_path,
+            field.name,
+            resource.name,
+            problem
+        ));
+    }
+    Ok(())
+}
+
+/// Computed fields are derived on read and transient ones set per request, so neither can be
+/// assigned through editable params. A transient field has no column, so it can't also be
+/// computed (which selects into one) or be the primary key.
+fn check_computed_fields(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    if let Some(field) = model.fields.iter().find(|field| {
+        field.attributes.transient && (field.attributes.computed.is_some() || field.name == "id")
+    }) {
+        return Err(anyhow!(
+            "{}: `{}` of {} can't be @transient{}",
+            resource.file_path,
+            field.name,
+            resource.name,
+            if field.name == "id" {
+                " because it is the primary key"
+            } else {
+                " and computed; drop one of them"
+            }
+        ));
+    }
+    let Some(controller) = &resource.controller else {
+        return Ok(());
+    };
+    let editable = controller
+        .params
+        .iter()
+        .filter(|profile| profile.name == ParamsKind::Editable)
+        .flat_map(|profile| &profile.entries);
+    for entry in editable {
+        let Some(field) = model
+            .fields
+            .iter()
+            .find(|field| field.name == entry.name && !field.attributes.stored())
+        else {
+            continue;
+        };
+        return Err(anyhow!(
+            "{}: {} field `{}` of {} cannot be listed in editable params",
+            resource.file_path,
+            if field.attributes.transient {
+                "transient"
+            } else {
+                "computed"
+            },
+            entry.name,
+            resource.name
+        ));
+    }
+    Ok(())
+}
+
+/// Expands `editable: *` into one entry per editable model field and folds explicit profiles of
+/// the same name into it. Explicit entries take precedence: their `?` mark replaces the derived
+/// entry's optionality, and entries that don't name a model field are appended as-is.
+fn expand_params_wildcards(resource: &mut Resource) -> Result<()> {
+    let Some(controller) = resource.controller.as_mut() else {
+        return Ok(());
+    };
+    if !controller.params.iter().any(|profile| profile.wildcard) {
+        return Ok(());
+    }
+    let model = resource.model.as_ref().ok_or_else(|| {
+        anyhow!(
+            "{}: params wildcard in resource {} requires a model block",
+            resource.file_path,
+            resource.name
+        )
+    })?;
+
+    let mut profiles: Vec<ParamsProfile> = Vec::new();
+    for profile in controller.params.drain(..) {
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) if existing.wildcard || profile.wildcard => {
+                existing.wildcard |= profile.wildcard;
+                existing.entries.extend(profile.entries);
+            }
+            _ => profiles.push(profile),
+        }
+    }
+
+    for profile in profiles.iter_mut().filter(|profile| profile.wildcard) {
+        let explicit = std::mem::take(&mut profile.entries);
+        profile.entries = derive_entries(model, &explicit);
+    }
+    controller.params = profiles;
+    Ok(())
+}
+
+fn derive_entries(model: &Model, explicit: &[ParamEntry]) -> Vec<ParamEntry> {
+    let mut entries: Vec<ParamEntry> = model
+        .fields
+        .iter()
+        .filter(|field| !PRIMARY_KEY_FIELDS.contains(&field.name.as_str()))
+        .filter(|field| field.attributes.stored())
+        .map(|field| {
+            explicit
+                .iter()
+                .find(|entry| entry.name == field.name)
+                .cloned()
+                .unwrap_or_else(|| ParamEntry {
+                    name: field.name.clone(),
+                    optional: false,
+                })
+        })
+        .collect();
+
+    for entry in explicit {
+        if !entries.iter().any(|existing| existing.name == entry.name) {
+            entries.push(entry.clone());
+        }
+    }
+    entries
+}
+
+/// `@min_len`/`@max_len` bound string length and `@min`/`@max` bound numeric values; each pair
+/// only makes sense on its own kind of type, so mixing them up is an error rather than a guess.
+fn check_bounds(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    for field in &model.fields {
+        let attrs = &field.attributes;
+        let ty = field.ty.name.as_str();
+        let length_attr = match (attrs.min_len, attrs.max_len) {
+            (Some(_), _) => Some("@min_len"),
+            (None, Some(_)) => Some("@max_len"),
+            (None, None) => None,
+        };
+        if let Some(attr) = length_attr
+            && !STRING_TYPES.contains(&ty)
+        {
+            return Err(anyhow!(
+                "{}: {} on field `{}` of {} requires a String or Text type, found {} (use @min/@max to bound numeric values)",
+                resource.file_path,
+                attr,
+                field.name,
+                resource.name,
+                ty
+            ));
+        }
+        let value_attrs = [("@min", &attrs.min), ("@max", &attrs.max)];
+        for (attr, value) in value_attrs {
+            let Some(value) = value else {
+                continue;
+            };
+            if !NUMERIC_TYPES.contains(&ty) {
+                return Err(anyhow!(
+                    "{}: {} on field `{}` of {} requires a numeric type, found {} (use @min_len/@max_len to bound string length)",
+                    resource.file_path,
+                    attr,
+                    field.name,
+                    resource.name,
+                    ty
+                ));
+            }
+            if INTEGER_TYPES.contains(&ty) && !fits_integer(ty, value) {
+                return Err(anyhow!(
+                    "{}: {} value {} on field `{}` of {} is not an integer that fits in {}",
+                    resource.file_path,
+                    attr,
+                    value,
+                    field.name,
+                    resource.name,
+                    ty
+                ));
+            }
+        }
+        let lengths_inverted =
+            matches!((attrs.min_len, attrs.max_len), (Some(min), Some(max)) if min > max);
+        let values_inverted = match (&attrs.min, &attrs.max) {
+            (Some(min), Some(max)) => {
+                min.parse::<f64>().unwrap_or_default() > max.parse::<f64>().unwrap_or_default()
+            }
+            _ => false,
+        };
+        if lengths_inverted || values_inverted {
+            return Err(anyhow!(
+                "{}: field `{}` of {} has a lower bound above its upper bound",
+                resource.file_path,
+                field.name,
+                resource.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `derives: [...]` names known derives, each at most once and alongside the derives it
+/// requires, and every model field's Rust type must implement them. Generated enums derive
+/// `PartialEq`, `Eq`, and `Hash` but not an order; other resource types derive none of them.
+fn check_derives(resource: &Resource, enums: &[EnumDecl]) -> Result<()> {
+    if resource.derives.is_empty() {
+        return Ok(());
+    }
+    let Some(model) = &resource.model else {
+        return Err(anyhow!(
+            "{}: `derives` on {} needs a model",
+            resource.file_path,
+            resource.name
+        ));
+    };
+    let mut seen = HashSet::new();
+    for derive in &resource.derives {
+        let Some((_, requires)) = MODEL_DERIVES.iter().find(|(name, _)| name == derive) else {
+            let known: Vec<&str> = MODEL_DERIVES.iter().map(|(name, _)| *name).collect();
+            return Err(anyhow!(
+                "{}: unknown derive `{}` on {} (expected one of: {})",
+                resource.file_path,
+                derive,
+                resource.name,
+                known.join(", ")
+            ));
+        };
+        if !seen.insert(derive.as_str()) {
+            return Err(anyhow!(
+                "{}: derive `{}` listed twice on {}",
+                resource.file_path,
+                derive,
+                resource.name
+            ));
+        }
+        if let Some(missing) = requires
+            .iter()
+            .find(|required| !resource.derives.iter().any(|derive| derive == *required))
+        {
+            return Err(anyhow!(
+                "{}: derive `{}` on {} also needs `{}`",
+                resource.file_path,
+                derive,
+                resource.name,
+                missing
+            ));
+        }
+        let unsupported = model.fields.iter().find(|field| {
+            let ty = field.ty.name.as_str();
+            match ViaScalar::from_name(ty) {
+                Some(scalar) => scalar.missing_derives().contains(&derive.as_str()),
+                None if enums.iter().any(|decl| decl.name == ty) => {
+                    matches!(derive.as_str(), "PartialOrd" | "Ord")
+                }
+                None => true,
+            }
+        });
+        if let Some(field) = unsupported {
+            return Err(anyhow!(
+                "{}: derive `{}` on {} is not implemented by field `{}` ({})",
+                resource.file_path,
+                derive,
+                resource.name,
+                field.name,
+                field.ty.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `@format(...)` describes the text of a string, so it only applies to String and Text fields.
+fn check_formats(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    for field in &model.fields {
+        let ty = field.ty.name.as_str();
+        if let Some(format) = &field.attributes.format
+            && !STRING_TYPES.contains(&ty)
+        {
+            return Err(anyhow!(
+                "{}: @format({}) on field `{}` of {} requires a String or Text type, found {}",
+                resource.file_path,
+                format,
+                field.name,
+                resource.name,
+                ty
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_allowed_values(resource: &Resource) -> Result<()> {
+    let Some(model) = &resource.model else {
+        return Ok(());
+    };
+    for field in &model.fields {
+        let Some(allowed) = &field.attributes.allowed else {
+            continue;
+        };
+        let ty = field.ty.name.as_str();
+        if INTEGER_TYPES.contains(&ty) {
+            if let Some(bad) = allowed.iter().find(|value| !fits_integer(ty, value)) {
+                return Err(anyhow!(
+                    "{}: @in value {:?} on field `{}` of {} is not an integer that fits in {}",
+                    resource.file_path,
+                    bad,
+                    field.name,
+                    resource.name,
+                    ty
+                ));
+            }
+        } else if !STRING_TYPES.contains(&ty) {
+            return Err(anyhow!(
+                "{}: @in on field `{}` of {} requires a String, Text, Integer, or BigInt type, found {}",
+                resource.file_path,
+                field.name,
+                resource.name,
+                ty
+            ));
+        }
+    }
+    Ok(())
+}
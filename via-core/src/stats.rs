@@ -0,0 +1,109 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{ast::Program, codegen::GenerationOutput, graph};
+
+/// Files and bytes one generator contributed to a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratorStats {
+    pub name: &'static str,
+    pub files: usize,
+    pub bytes: usize,
+}
+
+/// Metrics printed by `via gen --stats`, taken from the resolved IR and the generated file map.
+#[derive(Debug, Clone, Default)]
+pub struct GenStats {
+    pub resources: usize,
+    pub fields: usize,
+    pub relations: usize,
+    pub generators: Vec<GeneratorStats>,
+    /// Collecting, parsing, and resolving the `.via` files.
+    pub parse_time: Duration,
+    /// Running the generators; `None` when codegen was skipped (`--dry-run`).
+    pub codegen_time: Option<Duration>,
+}
+
+impl GenStats {
+    pub fn new(program: &Program, parse_time: Duration) -> Self {
+        Self {
+            resources: program.resources.len(),
+            fields: program
+                .resources
+                .iter()
+                .filter_map(|resource| resource.model.as_ref())
+                .map(|model| model.fields.len())
+                .sum(),
+            relations: graph::relations(&program.resources).len(),
+            generators: Vec::new(),
+            parse_time,
+            codegen_time: None,
+        }
+    }
+
+    pub fn record_generation(&mut self, output: &GenerationOutput, codegen_time: Duration) {
+        let mut files = output.files.iter();
+        self.generators = output
+            .generated_by
+            .iter()
+            .map(|&(name, count)| GeneratorStats {
+                name,
+                files: count,
+                bytes: files
+                    .by_ref()
+                    .take(count)
+                    .map(|file| file.contents.len())
+                    .sum(),
+            })
+            .collect();
+        self.codegen_time = Some(codegen_time);
+    }
+
+    pub fn total_files(&self) -> usize {
+        self.generators
+            .iter()
+            .map(|generator| generator.files)
+            .sum()
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.generators
+            .iter()
+            .map(|generator| generator.bytes)
+            .sum()
+    }
+
+    /// A plain-text table, one metric per line.
+    pub fn render(&self) -> String {
+        let mut buffer = String::new();
+        buffer.push_str("Codegen stats\n");
+        writeln!(buffer, "  {:<14}{:>8}", "resources", self.resources).unwrap();
+        writeln!(buffer, "  {:<14}{:>8}", "fields", self.fields).unwrap();
+        writeln!(buffer, "  {:<14}{:>8}", "relations", self.relations).unwrap();
+        if self.codegen_time.is_some() {
+            buffer.push('\n');
+            writeln!(buffer, "  {:<14}{:>8}{:>10}", "generator", "files", "bytes").unwrap();
+            for generator in &self.generators {
+                writeln!(
+                    buffer,
+                    "  {:<14}{:>8}{:>10}",
+                    generator.name, generator.files, generator.bytes
+                )
+                .unwrap();
+            }
+            writeln!(
+                buffer,
+                "  {:<14}{:>8}{:>10}",
+                "total",
+                self.total_files(),
+                self.total_bytes()
+            )
+            .unwrap();
+        }
+        buffer.push('\n');
+        writeln!(buffer, "  {:<14}{:>8.1?}", "parse", self.parse_time).unwrap();
+        if let Some(codegen_time) = self.codegen_time {
+            writeln!(buffer, "  {:<14}{:>8.1?}", "codegen", codegen_time).unwrap();
+        }
+        buffer
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, anyhow};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+/// How long the files must stay quiet before a change is acted on, so an editor's
+/// write-rename-touch sequence (or a branch switch) triggers one run instead of several.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How often a wait with no filesystem events looks at the stop flag, which a signal handler
+/// can raise but can't wake a channel for.
+const STOP_CHECK: Duration = Duration::from_millis(100);
+
+/// The modification time and length of every watched file. Length catches rewrites that land
+/// within the filesystem's mtime resolution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<(SystemTime, u64)>>);
+
+impl Snapshot {
+    /// Records `files`; ones that can't be stat'ed are recorded as missing.
+    pub fn capture(files: &[PathBuf]) -> Self {
+        Self(
+            files
+                .iter()
+                .map(|path| {
+                    let stamp = fs::metadata(path)
+                        .ok()
+                        .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+                    (path.clone(), stamp)
+                })
+                .collect(),
+        )
+    }
+
+    /// Paths added, removed, or modified since `earlier`, sorted.
+    pub fn changes(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self
+            .0
+            .iter()
+            .filter(|(path, stamp)| earlier.0.get(*path) != Some(*stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            earlier
+                .0
+                .keys()
+                .filter(|path| !self.0.contains_key(*path))
+                .cloned(),
+        );
+        changed.sort();
+        changed
+    }
+}
+
+/// A flag raised by the first Ctrl-C (or SIGTERM), so watch mode can finish the run in progress
+/// and return instead of dying mid-write. A second signal while the flag is up exits at once.
+pub fn stop_on_ctrl_c() -> Result<Arc<AtomicBool>> {
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        // Registered first, so it sees the flag as it was before this signal raised it.
+        signal_hook::flag::register_conditional_shutdown(signal, 130, Arc::clone(&stop))
+            .and_then(|_| signal_hook::flag::register(signal, Arc::clone(&stop)))
+            .context("Failed to install the Ctrl-C handler")?;
+    }
+    Ok(stop)
+}
+
+/// Filesystem notifications (inotify, FSEvents, ...) for the app roots, so watch mode sleeps
+/// until something under them changes instead of re-reading every file on a timer.
+pub struct Watcher {
+    // Dropping it stops the notifications.
+    _watcher: RecommendedWatcher,
+    events: Receiver<()>,
+}
+
+impl Watcher {
+    /// Watches each directory in `roots` recursively, and the directory holding each file root
+    /// (editors often save by renaming a new file over the old one).
+    pub fn new(roots: &[PathBuf]) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        // Errors wake the loop as well; the snapshot decides whether anything changed.
+        let mut watcher = notify::recommended_watcher(move |_: notify::Result<notify::Event>| {
+            let _ = sender.send(());
+        })
+        .context("Failed to start the file watcher")?;
+        for root in roots {
+            let (path, mode) = if root.is_dir() {
+                (root.as_path(), RecursiveMode::Recursive)
+            } else {
+                let parent = root
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty());
+                (
+                    parent.unwrap_or(Path::new(".")),
+                    RecursiveMode::NonRecursive,
+                )
+            };
+            watcher
+                .watch(path, mode)
+                .with_context(|| format!("Failed to watch {}", path.display()))?;
+        }
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Blocks until the watched files differ from `last`, then until no event has arrived for
+    /// [`DEBOUNCE`]. `files` is re-run on every wake-up so added and deleted files are noticed,
+    /// and once up front so an edit saved during the previous run isn't missed. Returns the
+    /// settled snapshot and the paths that changed, or `None` once `stop` is raised.
+    pub fn wait_for_change(
+        &self,
+        last: &Snapshot,
+        stop: &AtomicBool,
+        mut files: impl FnMut() -> Result<Vec<PathBuf>>,
+    ) -> Result<Option<(Snapshot, Vec<PathBuf>)>> {
+        // The first pass looks without waiting, to catch edits saved during the previous run.
+        let mut woken = true;
+        loop {
+            if stop.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            if woken && Snapshot::capture(&files()?) != *last {
+                if !self.settle(stop)? {
+                    return Ok(None);
+                }
+                let settled = Snapshot::capture(&files()?);
+                if settled != *last {
+                    let changed = settled.changes(last);
+                    return Ok(Some((settled, changed)));
+                }
+            }
+            woken = match self.events.recv_timeout(STOP_CHECK) {
+                Ok(()) => true,
+                Err(RecvTimeoutError::Timeout) => false,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(anyhow!("The file watcher stopped unexpectedly"));
+                }
+            };
+        }
+    }
+
+    /// Waits out a burst of events. Returns `false` if `stop` was raised meanwhile.
+    fn settle(&self, stop: &AtomicBool) -> Result<bool> {
+        loop {
+            match self.events.recv_timeout(DEBOUNCE) {
+                Ok(()) if !stop.load(Ordering::SeqCst) => {}
+                Ok(()) => return Ok(false),
+                Err(RecvTimeoutError::Timeout) => return Ok(!stop.load(Ordering::SeqCst)),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(anyhow!("The file watcher stopped unexpectedly"));
+                }
+            }
+        }
+    }
+}
@@ -1,39 +1,553 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
-use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::codegen::GeneratedFile;
+use anyhow::{Context, Result, anyhow};
+use walkdir::WalkDir;
 
-pub fn clean_output_root(out_dir: &Path) -> Result<()> {
-    if out_dir.exists() {
-        let src_dir = out_dir.join("src");
-        if src_dir.exists() {
-            fs::remove_dir_all(&src_dir).with_context(|| {
-                format!("Failed to clear generated directory {}", src_dir.display())
-            })?;
+use crate::codegen::{GITATTRIBUTES_FILE_NAME, GeneratedFile, MIGRATION_DIR};
+
+/// Opens a hand-editable region in generated code; the region name follows on the same line.
+pub const MANUAL_BEGIN: &str = "// via:manual-begin ";
+/// Closes the region opened by [`MANUAL_BEGIN`] with the same name.
+pub const MANUAL_END: &str = "// via:manual-end ";
+
+/// Output-relative paths written by the last full `via gen` run, one per line. Lets the next run
+/// remove exactly the files it no longer generates (see [`CleanMode::Safe`]).
+pub const MANIFEST_FILE_NAME: &str = "via.manifest";
+
+/// How `via gen` deals with files a previous run generated that this one doesn't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanMode {
+    /// Remove every file under the generated roots that isn't being written ([`clean_output_root`]).
+    Full,
+    /// Remove only files listed in the previous manifest ([`clean_listed_files`]), so hand-added
+    /// files next to generated ones survive.
+    Safe,
+    /// Leave the output directory alone.
+    None,
+}
+
+/// Removes stale files under `<out>/src` (those not in `files`), then any directories left
+/// empty. Files about to be rewritten stay in place so their manual regions can be carried over.
+/// Rust modules configured to live outside `src` have their top-level directory cleaned the same
+/// way; the migration crate never is. A generated `.gitattributes` that is no longer emitted is
+/// removed as well.
+pub fn clean_output_root(out_dir: &Path, files: &[GeneratedFile]) -> Result<()> {
+    let keep: HashSet<PathBuf> = files
+        .iter()
+        .map(|file| out_dir.join(&file.relative_path))
+        .collect();
+
+    let gitattributes = out_dir.join(GITATTRIBUTES_FILE_NAME);
+    if !keep.contains(&gitattributes)
+        && fs::read_to_string(&gitattributes)
+            .is_ok_and(|existing| existing.starts_with("# @generated by via-core"))
+    {
+        fs::remove_file(&gitattributes)
+            .with_context(|| format!("Failed to remove {}", gitattributes.display()))?;
+    }
+
+    let mut roots: BTreeSet<PathBuf> = BTreeSet::from([PathBuf::from("src")]);
+    roots.extend(
+        files
+            .iter()
+            .filter(|file| {
+                file.relative_path
+                    .extension()
+                    .is_some_and(|ext| ext == "rs")
+            })
+            .filter_map(|file| file.relative_path.components().next())
+            .map(|component| PathBuf::from(component.as_os_str())),
+    );
+    // The migration crate's `lib.rs`, manifest, and hand-written migrations live next to the
+    // generated ones.
+    roots.remove(Path::new(MIGRATION_DIR));
+    for root in roots {
+        clean_stale_files(&out_dir.join(root), &keep)?;
+    }
+    Ok(())
+}
+
+/// Removes the files in `previous` (a manifest read with [`read_manifest`]) that aren't in
+/// `files`, then their parent directories if that left them empty. Nothing that wasn't
+/// generated is touched.
+pub fn clean_listed_files(
+    out_dir: &Path,
+    previous: &BTreeSet<PathBuf>,
+    files: &[GeneratedFile],
+) -> Result<()> {
+    let keep: HashSet<&Path> = files
+        .iter()
+        .map(|file| file.relative_path.as_path())
+        .collect();
+    for relative in previous
+        .iter()
+        .filter(|path| !keep.contains(path.as_path()))
+    {
+        let path = out_dir.join(relative);
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to remove {}", path.display()));
+            }
+        }
+        let mut dir = path.parent();
+        while let Some(parent) = dir.filter(|parent| *parent != out_dir) {
+            if fs::read_dir(parent).map_or(true, |mut entries| entries.next().is_some()) {
+                break;
+            }
+            fs::remove_dir(parent)
+                .with_context(|| format!("Failed to remove {}", parent.display()))?;
+            dir = parent.parent();
+        }
+    }
+    Ok(())
+}
+
+/// The paths listed in `<out>/via.manifest`, or `None` when no full run has written one yet.
+pub fn read_manifest(out_dir: &Path) -> Option<BTreeSet<PathBuf>> {
+    let contents = fs::read_to_string(out_dir.join(MANIFEST_FILE_NAME)).ok()?;
+    Some(
+        contents
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+/// Records `paths` (output-relative, with `/` separators) as the generated files.
+pub fn write_manifest(out_dir: &Path, paths: &BTreeSet<PathBuf>) -> Result<()> {
+    let mut contents = String::from("# @generated by via-core. DO NOT EDIT BY HAND.\n");
+    for path in paths {
+        contents.push_str(&path.to_string_lossy().replace('\\', "/"));
+        contents.push('\n');
+    }
+    write_atomic(&out_dir.join(MANIFEST_FILE_NAME), contents.as_bytes()).map(drop)
+}
+
+fn clean_stale_files(src_dir: &Path, keep: &HashSet<PathBuf>) -> Result<()> {
+    if !src_dir.is_dir() {
+        return Ok(());
+    }
+    for entry in WalkDir::new(src_dir).contents_first(true) {
+        let entry = entry.with_context(|| {
+            format!("Failed to clear generated directory {}", src_dir.display())
+        })?;
+        let path = entry.path();
+        if entry.file_type().is_dir() {
+            if fs::read_dir(path)?.next().is_none() {
+                fs::remove_dir(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        } else if !keep.contains(path) {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
         }
     }
     Ok(())
 }
 
-pub fn write_files(out_dir: &Path, files: &[GeneratedFile]) -> Result<()> {
+/// Whether `path` exists but lacks the `@generated by via-core` header (in any of the comment
+/// styles), i.e. someone else wrote it and a regeneration shouldn't clobber it.
+pub fn is_hand_written(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|existing| {
+        !existing.starts_with("# @generated by via-core")
+            && !existing.starts_with("// @generated by via-core")
+            && !existing.starts_with("{# @generated by via-core")
+    })
+}
+
+/// How many files a write rewrote, and how many it left alone because they were unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteSummary {
+    pub written: usize,
+    pub unchanged: usize,
+}
+
+impl WriteSummary {
+    fn record(&mut self, written: bool) {
+        if written {
+            self.written += 1;
+        } else {
+            self.unchanged += 1;
+        }
+    }
+}
+
+pub fn write_files(out_dir: &Path, files: &[GeneratedFile]) -> Result<WriteSummary> {
+    let mut summary = WriteSummary::default();
     for file in files {
+        summary.record(write_generated(out_dir, file)?);
+    }
+    Ok(summary)
+}
+
+/// Like [`write_files`], but spreads the files over `threads` workers, which pays off on
+/// network or otherwise high-latency filesystems. Each file still goes through the same
+/// manual-region merge and atomic rename. When several files fail, the error reported is the
+/// one for the earliest file in `files`, so the outcome doesn't depend on scheduling.
+pub fn write_files_parallel(
+    out_dir: &Path,
+    files: &[GeneratedFile],
+    threads: NonZeroUsize,
+) -> Result<WriteSummary> {
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut results = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(file) = files.get(index) else {
+                return results;
+            };
+            results.push((index, write_generated(out_dir, file)));
+        }
+    };
+    let mut results: Vec<(usize, Result<bool>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.get().min(files.len()))
+            .map(|_| scope.spawn(worker))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("writer thread panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut summary = WriteSummary::default();
+    for (_, result) in results {
+        summary.record(result?);
+    }
+    Ok(summary)
+}
+
+/// Writes one generated file, carrying over its manual regions; false if it was unchanged.
+fn write_generated(out_dir: &Path, file: &GeneratedFile) -> Result<bool> {
+    let path = out_dir.join(&file.relative_path);
+    let contents = match fs::read_to_string(&path) {
+        Ok(existing) if existing.contains(MANUAL_BEGIN) => {
+            preserve_manual_regions(&existing, &file.contents)
+        }
+        _ => file.contents.clone(),
+    };
+    write_atomic(&path, contents.as_bytes())
+}
+
+/// Copies the body of every complete manual region in `existing` into the region of the same
+/// name in `generated`. Regions that no longer exist in `generated` are dropped, and new ones
+/// keep their generated default.
+pub fn preserve_manual_regions(existing: &str, generated: &str) -> String {
+    let mut regions: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    for line in existing.lines() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix(MANUAL_BEGIN) {
+            current = Some((name.trim(), Vec::new()));
+        } else if let Some(name) = trimmed.strip_prefix(MANUAL_END) {
+            if let Some((open, body)) = current.take()
+                && open == name.trim()
+            {
+                regions.insert(open, body);
+            }
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+
+    let mut out = String::with_capacity(generated.len());
+    let mut skipping = false;
+    for line in generated.lines() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix(MANUAL_BEGIN) {
+            out.push_str(line);
+            out.push('\n');
+            if let Some(body) = regions.get(name.trim()) {
+                for kept in body {
+                    out.push_str(kept);
+                    out.push('\n');
+                }
+                skipping = true;
+            }
+            continue;
+        }
+        if trimmed.starts_with(MANUAL_END) {
+            skipping = false;
+        }
+        if !skipping {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if !generated.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Extensions `--prettier` formats; Rust, JSON, and every other output is left as generated.
+const PRETTIER_EXTENSIONS: &[&str] = &["ts", "tsx"];
+
+/// Pipes each TypeScript file among `files` through `prettier --stdin-filepath <out>/<path>`, so
+/// the consuming project's prettier config applies to it. Returns `false`, with every file left
+/// as generated, when `prettier` can't be found.
+pub fn format_typescript(
+    out_dir: &Path,
+    files: &mut [GeneratedFile],
+    prettier: &Path,
+) -> Result<bool> {
+    let typescript = files.iter_mut().filter(|file| {
+        file.relative_path
+            .extension()
+            .is_some_and(|ext| PRETTIER_EXTENSIONS.iter().any(|known| ext == *known))
+    });
+    for file in typescript {
         let path = out_dir.join(&file.relative_path);
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        let spawned = Command::new(prettier)
+            .arg("--stdin-filepath")
+            .arg(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err).context("failed to run prettier"),
+        };
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(file.contents.as_bytes())
+            .context("failed to run prettier")?;
+        let output = child.wait_with_output().context("failed to run prettier")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "prettier failed on {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
         }
-        fs::write(&path, file.contents.as_bytes())
-            .with_context(|| format!("Failed to write {}", path.display()))?;
+        file.contents = String::from_utf8(output.stdout)
+            .with_context(|| format!("prettier printed non-UTF-8 for {}", path.display()))?;
     }
-    Ok(())
+    Ok(true)
+}
+
+/// Marker loco's `migration/src/lib.rs` keeps below the last registered migration.
+pub const MIGRATION_INJECT_MARKER: &str = "// inject-above";
+
+/// `migration/Cargo.toml` written when the output directory has no migration crate yet.
+const MIGRATION_MANIFEST: &str = r#"[package]
+name = "migration"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[lib]
+name = "migration"
+path = "src/lib.rs"
+
+[dependencies]
+loco-rs = { version = "0.16" }
+sea-orm-migration = { version = "1.1", features = ["runtime-tokio-rustls"] }
+"#;
+
+/// `migration/src/lib.rs` written when the output directory has no migration crate yet, in the
+/// shape `loco new` gives it.
+const MIGRATION_LIB: &str = r#"#![allow(elided_lifetimes_in_paths)]
+#![allow(clippy::wildcard_imports)]
+pub use sea_orm_migration::prelude::*;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            // inject-above (do not remove this comment)
+        ]
+    }
+}
+"#;
+
+/// Whether `module` is named like a loco migration: `m<YYYYMMDD>_<HHMMSS>_<name>`.
+fn is_migration_module(module: &str) -> bool {
+    let bytes = module.as_bytes();
+    bytes.len() > 17
+        && bytes[0] == b'm'
+        && bytes[1..9].iter().all(u8::is_ascii_digit)
+        && bytes[9] == b'_'
+        && bytes[10..16].iter().all(u8::is_ascii_digit)
+        && bytes[16] == b'_'
+}
+
+/// The migrations under `<out>/migration/src`, as module names by the table they're named for
+/// (`m20250913_120150_images` -> `images`). When several share a table, the oldest wins: that
+/// is the one that creates it.
+pub fn existing_migrations(out_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let dir = out_dir.join(MIGRATION_DIR).join("src");
+    let mut modules = Vec::new();
+    match fs::read_dir(&dir) {
+        Ok(entries) => {
+            for entry in entries {
+                let path = entry
+                    .with_context(|| format!("Failed to read {}", dir.display()))?
+                    .path();
+                if path.extension().is_some_and(|ext| ext == "rs")
+                    && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+                    && is_migration_module(stem)
+                {
+                    modules.push(stem.to_owned());
+                }
+            }
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(
+                anyhow::Error::new(err).context(format!("Failed to read {}", dir.display()))
+            );
+        }
+    }
+    modules.sort();
+    let mut by_table = BTreeMap::new();
+    for module in modules {
+        by_table.entry(module[17..].to_owned()).or_insert(module);
+    }
+    Ok(by_table)
+}
+
+/// Registers `modules` (in order) in `<out>/migration/src/lib.rs`: a `mod` line each, and a
+/// `Box::new(<module>::Migration)` entry above the `// inject-above` marker, as `cargo loco
+/// generate migration` does. Modules already listed are left where they are, and migration
+/// modules whose file is gone are unregistered. Writes a loco-shaped `lib.rs` and `Cargo.toml`
+/// first when the crate doesn't exist yet.
+pub fn register_migrations(out_dir: &Path, modules: &[String]) -> Result<()> {
+    let dir = out_dir.join(MIGRATION_DIR);
+    let manifest = dir.join("Cargo.toml");
+    if !manifest.exists() {
+        write_atomic(&manifest, MIGRATION_MANIFEST.as_bytes())?;
+    }
+    let lib = dir.join("src").join("lib.rs");
+    let existing = match fs::read_to_string(&lib) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => MIGRATION_LIB.to_owned(),
+        Err(err) => {
+            return Err(
+                anyhow::Error::new(err).context(format!("Failed to read {}", lib.display()))
+            );
+        }
+    };
+
+    let is_stale = |module: &str| {
+        is_migration_module(module) && !dir.join("src").join(format!("{}.rs", module)).is_file()
+    };
+    let mut lines: Vec<String> = existing
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            let module = trimmed
+                .strip_prefix("mod ")
+                .and_then(|rest| rest.strip_suffix(';'))
+                .or_else(|| {
+                    trimmed
+                        .strip_prefix("Box::new(")
+                        .and_then(|rest| rest.strip_suffix("::Migration),"))
+                });
+            !module.is_some_and(is_stale)
+        })
+        .map(str::to_owned)
+        .collect();
+
+    for module in modules {
+        let mod_line = format!("mod {};", module);
+        if lines.iter().any(|line| line.trim() == mod_line) {
+            continue;
+        }
+        let Some(marker) = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with(MIGRATION_INJECT_MARKER))
+        else {
+            return Err(anyhow!(
+                "{}: no `{}` marker to register migration `{}` above",
+                lib.display(),
+                MIGRATION_INJECT_MARKER,
+                module
+            ));
+        };
+        let indent: String = lines[marker]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+        lines.insert(
+            marker,
+            format!("{}Box::new({}::Migration),", indent, module),
+        );
+
+        let after_mods = lines
+            .iter()
+            .rposition(|line| line.starts_with("mod "))
+            .map(|index| index + 1)
+            .or_else(|| {
+                lines
+                    .iter()
+                    .position(|line| line.starts_with("pub struct Migrator"))
+            })
+            .unwrap_or(0);
+        lines.insert(after_mods, mod_line);
+    }
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    write_atomic(&lib, contents.as_bytes()).map(drop)
 }
 
 pub fn write_ir_file(path: &Path, contents: &str) -> Result<()> {
+    write_atomic(path, contents.as_bytes()).map(drop)
+}
+
+/// Writes a `via bundle` artifact, which may be gzip-compressed and so isn't UTF-8.
+pub fn write_bundle_file(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic(path, contents).map(drop)
+}
+
+/// Writes `contents` to a sibling temp file and renames it over `path`, so readers only ever
+/// observe the previous or the complete new contents. Files whose contents are unchanged are
+/// left untouched to keep mtimes stable for file watchers; for those it returns false.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<bool> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create directory {}", parent.display()))?;
     }
-    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
-    Ok(())
+
+    if fs::read(path).is_ok_and(|existing| existing == contents) {
+        return Ok(false);
+    }
+
+    let tmp_path = temp_path_for(path);
+    fs::write(&tmp_path, contents)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path).map_err(|err| {
+        let _ = fs::remove_file(&tmp_path);
+        anyhow::Error::new(err).context(format!("Failed to write {}", path.display()))
+    })?;
+    Ok(true)
+}
+
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.via-tmp", file_name))
 }
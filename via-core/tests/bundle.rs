@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{
+    bundle::{BUNDLE_VERSION, Bundle},
+    codegen,
+    config::Config,
+    parser, resolve,
+};
+
+fn blog_bundle() -> Result<Bundle> {
+    let mut program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    resolve::resolve(&mut program)?;
+    let config = Config {
+        ts_dir: Some("frontend/types".into()),
+        ..Default::default()
+    };
+    Ok(Bundle::new(program, config))
+}
+
+#[test]
+fn round_trips_to_identical_generation() -> Result<()> {
+    let bundle = blog_bundle()?;
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        layout: bundle.config.layout()?,
+        ..Default::default()
+    };
+    let expected = codegen::generate_with_options(&bundle.program, &options)?;
+
+    for compress in [false, true] {
+        let loaded = Bundle::from_bytes(&bundle.to_bytes(compress)?)?;
+        assert_eq!(loaded.config.ts_dir, bundle.config.ts_dir);
+        let options = codegen::GenerateOptions {
+            openapi: true,
+            layout: loaded.config.layout()?,
+            ..Default::default()
+        };
+        let generated = codegen::generate_with_options(&loaded.program, &options)?;
+        let files = |output: &codegen::GenerationOutput| {
+            output
+                .files
+                .iter()
+                .map(|file| (file.relative_path.clone(), file.contents.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(files(&generated), files(&expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn rejects_other_versions_and_plain_ir() -> Result<()> {
+    let mut bundle = blog_bundle()?;
+    bundle.version = BUNDLE_VERSION + 1;
+    let err = Bundle::from_bytes(&bundle.to_bytes(false)?).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "bundle version {} is not supported (expected {})",
+            BUNDLE_VERSION + 1,
+            BUNDLE_VERSION
+        )
+    );
+
+    let ir = serde_json::to_vec(&bundle.program)?;
+    let err = Bundle::from_bytes(&ir).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "not a via bundle (create one with `via bundle`)"
+    );
+    Ok(())
+}
@@ -1,9 +1,12 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use assert_cmd::Command;
 use predicates::prelude::*;
-use tempfile::tempdir;
+use tempfile::{TempDir, tempdir};
 
 fn fixtures_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
@@ -13,6 +16,14 @@ fn invalid_fixtures_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_invalid")
 }
 
+/// Copies a single fixture into a fresh app directory so it can be checked in isolation.
+fn app_with_fixture(fixture: &Path) -> Result<TempDir> {
+    let tmp = tempdir()?;
+    let file_name = fixture.file_name().expect("fixture should have a file name");
+    fs::copy(fixture, tmp.path().join(file_name))?;
+    Ok(tmp)
+}
+
 #[test]
 fn via_gen_writes_outputs() -> Result<()> {
     let tmp = tempdir()?;
@@ -41,36 +52,74 @@ fn via_gen_writes_outputs() -> Result<()> {
 
     let ir = fs::read_to_string(&ir_path)?;
     assert!(ir.contains("Article"), "IR should contain resource name");
+    let ir: serde_json::Value = serde_json::from_str(&ir)?;
+    assert_eq!(ir["resources"][0]["name"], "Article");
 
     Ok(())
 }
 
-#[test]
-fn via_gen_outputs_cargo_check() -> Result<()> {
+/// Generates `app` with `flags` and runs `cargo check` on the crate and, when one was
+/// generated, its migration crate. Every check shares one target directory, so dependencies
+/// are only built once.
+fn assert_generated_crate_checks(app: &Path, flags: &[&str]) -> Result<()> {
     let tmp = tempdir()?;
     let crate_dir = tmp.path().join("generated");
 
     Command::cargo_bin("via")?
         .arg("gen")
         .arg("--app")
-        .arg(fixtures_dir())
+        .arg(app)
         .arg("--out")
         .arg(&crate_dir)
+        .args(flags)
         .assert()
         .success();
 
-    Command::new("cargo")
-        .current_dir(&crate_dir)
-        .env("CARGO_TERM_COLOR", "never")
-        .env("CARGO_TARGET_DIR", tmp.path().join("target"))
-        .arg("check")
-        .assert()
-        .success()
-        .stderr(predicate::str::contains("Finished"));
+    for dir in [crate_dir.clone(), crate_dir.join("migration")] {
+        if !dir.join("Cargo.toml").exists() {
+            continue;
+        }
+        Command::new("cargo")
+            .current_dir(&dir)
+            .env("CARGO_TERM_COLOR", "never")
+            .env(
+                "CARGO_TARGET_DIR",
+                Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated-check"),
+            )
+            .arg("check")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Finished"));
+    }
 
     Ok(())
 }
 
+// `--seaography` is deliberately missing from the flags below: the generated manifest then lists
+// the `seaography` crate, which cargo resolves even as an optional dependency, and it isn't
+// available to offline builds. Its `RelatedEntity` output is only covered by the
+// `annotates_explicit_entities_for_seaography_when_enabled` snapshots in codegen_snapshots.rs.
+#[test]
+fn via_gen_outputs_cargo_check() -> Result<()> {
+    assert_generated_crate_checks(
+        &fixtures_dir(),
+        &[
+            "--explicit-entities",
+            "--gen-errors",
+            "--gen-builders",
+            "--migrations",
+        ],
+    )
+}
+
+#[test]
+fn via_gen_manual_actions_cargo_check() -> Result<()> {
+    let app = app_with_fixture(
+        &PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_extra/manual_actions.via"),
+    )?;
+    assert_generated_crate_checks(app.path(), &["--gen-errors", "--tracing"])
+}
+
 #[test]
 fn via_gen_dry_run_lists_resources_without_writing_files() -> Result<()> {
     let tmp = tempdir()?;
@@ -109,12 +158,1032 @@ fn via_check_reports_success() -> Result<()> {
 #[test]
 fn via_check_surfaces_parse_errors() -> Result<()> {
     Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
         .arg("check")
         .arg("--app")
         .arg(invalid_fixtures_dir())
         .assert()
         .failure()
-        .stderr(predicate::str::contains("missing_colon.via"));
+        .stderr(predicate::str::contains("missing_colon.via:3:17"));
+
+    Ok(())
+}
+
+#[test]
+fn via_check_and_gen_read_a_document_from_stdin() -> Result<()> {
+    let valid = "resource Post {\n  model {\n    field title: String\n  }\n}\n";
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg("-")
+        .write_stdin(valid)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK: parsed 1 resource(s)"));
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg("-")
+        .write_stdin("resource Post {\n  model {\n    field title String\n  }\n}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("<stdin>:3:"));
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg("-")
+        .arg("--dry-run")
+        .write_stdin(valid)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Post (from <stdin>)"));
+
+    Ok(())
+}
+
+#[test]
+fn via_explain_prints_resolved_resource() -> Result<()> {
+    Command::cargo_bin("via")?
+        .arg("explain")
+        .arg("Article")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "published_at?: DateTime -> Rust `Option<String>`, TS `string`",
+        ))
+        .stdout(predicate::str::contains(
+            "editable: title, body, published_at, author_name",
+        ))
+        .stdout(predicate::str::contains("src/controllers/article.rs"));
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("explain")
+        .arg("Missing")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Resource `Missing` not found"));
+
+    Ok(())
+}
+
+// One representative case through the CLI; `tests/resolve.rs` checks the error of every fixture
+// in `fixtures_invalid/` in-process.
+#[test]
+fn via_check_rejects_rust_keyword_as_field_name() -> Result<()> {
+    let app = app_with_fixture(&invalid_fixtures_dir().join("reserved_field_name.via"))?;
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "field `type` of Payment is a Rust keyword and can't be a struct field; rename it \
+             (e.g. `payment_type`)",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn via_check_reports_every_broken_resource_up_to_max_errors() -> Result<()> {
+    let app = tempdir()?;
+    for name in ["Alpha", "Bravo", "Charlie", "Delta"] {
+        fs::write(
+            app.path().join(format!("{}.via", name.to_lowercase())),
+            format!("resource {} {{\n  model {{\n    field type: String\n  }}\n}}\n", name),
+        )?;
+    }
+
+    let output = Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--max-errors")
+        .arg("3")
+        .output()?;
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert_eq!(stderr.matches("is a Rust keyword").count(), 3);
+    assert!(stderr.contains("... and 1 more\n"));
+    assert!(stderr.contains("error: could not check the schema due to 4 errors"));
+
+    Ok(())
+}
+
+#[test]
+fn via_check_accepts_multiple_app_roots() -> Result<()> {
+    let shared = tempdir()?;
+    fs::write(
+        shared.path().join("tags.via"),
+        "resource Tag {\n  model {\n    field label: String\n  }\n}\n",
+    )?;
+
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--app")
+        .arg(shared.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK: parsed 2 resource(s)"));
+
+    let glob_root = shared.path().join("*");
+    fs::create_dir(shared.path().join("extra"))?;
+    fs::copy(
+        fixtures_dir().join("article.via"),
+        shared.path().join("extra/article.via"),
+    )?;
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--app")
+        .arg(glob_root)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate resource `Article`"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_skips_viaignore_matches_and_dot_directories() -> Result<()> {
+    let app = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_ignored");
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(app)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parsed 1 resource(s)"))
+        .stdout(predicate::str::contains(" - Post"))
+        .stdout(predicate::str::contains("Draft").not())
+        .stdout(predicate::str::contains("Scratch").not());
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_stats_prints_counts_per_generator() -> Result<()> {
+    let out = tempdir()?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(out.path())
+        .arg("--stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Codegen stats"))
+        .stdout(predicate::str::is_match(r"resources\s+1\n")?)
+        .stdout(predicate::str::is_match(r"typescript\s+2\s+\d+\n")?)
+        .stdout(predicate::str::contains("codegen"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_from_bundle_matches_gen_from_sources() -> Result<()> {
+    let dir = tempdir()?;
+    let bundle = dir.path().join("app.viabundle");
+
+    Command::cargo_bin("via")?
+        .arg("bundle")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&bundle)
+        .arg("--compress")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bundled 1 resource(s)"));
+
+    for (name, source) in [("from_sources", "--app"), ("from_bundle", "--from-ir")] {
+        let input = if name == "from_bundle" {
+            bundle.clone()
+        } else {
+            fixtures_dir()
+        };
+        Command::cargo_bin("via")?
+            .arg("gen")
+            .arg(source)
+            .arg(input)
+            .arg("--out")
+            .arg(dir.path().join(name))
+            .arg("--openapi")
+            .assert()
+            .success();
+    }
+    for file in ["src/models/article.rs", "openapi.json", "via.ir.json"] {
+        assert_eq!(
+            fs::read_to_string(dir.path().join("from_sources").join(file))?,
+            fs::read_to_string(dir.path().join("from_bundle").join(file))?,
+            "{} differs",
+            file
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn via_check_diff_ir_lists_breaking_changes_first() -> Result<()> {
+    let out = tempdir()?;
+    let ir = out.path().join("via.ir.json");
+    let output = Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--print-ir")
+        .arg("--dry-run")
+        .output()?;
+    fs::write(&ir, output.stdout)?;
+
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--diff-ir")
+        .arg(&ir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No schema changes"));
+
+    let extra_fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_extra");
+    let app = app_with_fixture(&extra_fixtures.join("allowed_values.via"))?;
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--diff-ir")
+        .arg(&ir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Breaking changes (1):\n  - removed resource Article\n\nNon-breaking changes (1):\n  - added resource Member\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_keeps_hand_written_manifest_unless_forced() -> Result<()> {
+    let out = tempdir()?;
+    let manifest = out.path().join("Cargo.toml");
+    fs::write(&manifest, "[package]\nname = \"mine\"\n")?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(out.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pass --force to overwrite"));
+    assert_eq!(fs::read_to_string(&manifest)?, "[package]\nname = \"mine\"\n");
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(out.path())
+        .arg("--force")
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&manifest)?.starts_with("# @generated by via-core"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_keeps_view_templates_once_their_header_is_removed() -> Result<()> {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_extra");
+    let app = app_with_fixture(&fixture.join("html_views.via"))?;
+    let out = tempdir()?;
+    let generate = || -> Result<()> {
+        Command::cargo_bin("via")?
+            .arg("gen")
+            .arg("--app")
+            .arg(app.path())
+            .arg("--out")
+            .arg(out.path())
+            .assert()
+            .success();
+        Ok(())
+    };
+
+    generate()?;
+    let list = out.path().join("assets/views/recipes/list.html");
+    let show = out.path().join("assets/views/recipes/show.html");
+    assert!(fs::read_to_string(&list)?.starts_with("{# @generated by via-core"));
+    fs::write(&list, "{% extends \"base.html\" %}\n<p>mine</p>\n")?;
+    fs::write(&show, "{# @generated by via-core. #}\nstale\n")?;
+
+    generate()?;
+    assert_eq!(
+        fs::read_to_string(&list)?,
+        "{% extends \"base.html\" %}\n<p>mine</p>\n"
+    );
+    assert!(fs::read_to_string(&show)?.contains("<dl>"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_since_rewrites_only_changed_and_related_resources() -> Result<()> {
+    let repo = tempdir()?;
+    let git = |args: &[&str]| -> Result<()> {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=via", "-c", "user.email=via@example.com"])
+            .args(args)
+            .current_dir(repo.path())
+            .status()?;
+        assert!(status.success(), "git {:?} failed", args);
+        Ok(())
+    };
+    let app = repo.path().join("app");
+    fs::create_dir(&app)?;
+    fs::write(
+        app.join("owner.via"),
+        "resource Owner {\n  model {\n    field name: String\n  }\n}\n",
+    )?;
+    fs::write(
+        app.join("pet.via"),
+        "resource Pet {\n  model {\n    field owner_id: Integer\n  }\n}\n",
+    )?;
+    fs::write(
+        app.join("invoice.via"),
+        "resource Invoice {\n  model {\n    field total: Decimal\n  }\n}\n",
+    )?;
+    git(&["init", "-q"])?;
+    git(&["add", "app"])?;
+    git(&["commit", "-q", "-m", "app"])?;
+
+    let generate = |extra: &[&str]| {
+        Command::cargo_bin("via")
+            .unwrap()
+            .current_dir(repo.path())
+            .args(["gen", "--app", "app", "--out", "generated"])
+            .args(extra)
+            .assert()
+            .success()
+    };
+    generate(&[]);
+    let models = repo.path().join("generated/src/models");
+    fs::write(models.join("invoice.rs"), "// untouched\n")?;
+    fs::write(models.join("pet.rs"), "// stale\n")?;
+    fs::write(
+        app.join("owner.via"),
+        "resource Owner {\n  model {\n    field name: String\n    field email?: String\n  }\n}\n",
+    )?;
+
+    generate(&["--since", "HEAD"]).stdout(predicate::str::contains(
+        "Regenerating 2 resource(s) changed since HEAD or related to one: Owner, Pet",
+    ));
+    assert!(fs::read_to_string(models.join("owner.rs"))?.contains("email"));
+    assert!(fs::read_to_string(models.join("pet.rs"))?.contains("pub struct Pet"));
+    assert_eq!(fs::read_to_string(models.join("invoice.rs"))?, "// untouched\n");
+    // Shared files are rendered from the whole program, so they keep listing Invoice.
+    assert!(fs::read_to_string(models.join("mod.rs"))?.contains("pub mod invoice;"));
+
+    generate(&["--since", "no-such-ref"])
+        .stdout(predicate::str::contains("Regenerating everything: `git diff"));
+    assert!(fs::read_to_string(models.join("invoice.rs"))?.contains("pub struct Invoice"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_fail_on_empty_exits_non_zero_without_via_files() -> Result<()> {
+    let app = tempdir()?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No .via files found"));
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("gen")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--dry-run")
+        .arg("--fail-on-empty")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No .via files found"));
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--fail-on-empty")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--fail-on-empty"));
+
+    Ok(())
+}
+
+#[test]
+fn via_graph_prints_dot_or_writes_it_with_out() -> Result<()> {
+    let app = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_extra/blog.via");
+    let app = app_with_fixture(&app)?;
+
+    Command::cargo_bin("via")?
+        .arg("graph")
+        .arg("--app")
+        .arg(app.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("// @generated by via-core"))
+        .stdout(predicate::str::contains(
+            "\"Comment\" -> \"Article\" [label=\"belongs_to (article_id)\"];",
+        ));
+
+    let dot_path = app.path().join("schema.dot");
+    Command::cargo_bin("via")?
+        .arg("graph")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--out")
+        .arg(&dot_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Graph written to"));
+    assert!(fs::read_to_string(&dot_path)?.contains("digraph via {"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_reads_json_case_from_config_file() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let config = tmp.path().join("via.toml");
+    fs::write(&config, "json_case = \"camel\"\n")?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    let model = fs::read_to_string(out_dir.join("src/models/article.rs"))?;
+    assert!(model.contains("#[serde(rename_all = \"camelCase\")]\npub struct Article {"));
+    let ts = fs::read_to_string(out_dir.join("ts/models/article.ts"))?;
+    assert!(ts.contains("  authorName: string;"));
+
+    fs::write(&config, "json_case = \"kebab\"\n")?;
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown variant `kebab`"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_reads_layout_dirs_from_config_file() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let config = tmp.path().join("via.toml");
+    fs::write(
+        &config,
+        "models_dir = \"src/domain\"\ncontrollers_dir = \"handlers\"\nts_dir = \"web/types\"\n",
+    )?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success();
+
+    assert!(out_dir.join("src/domain/article.rs").exists());
+    assert!(out_dir.join("handlers/mod.rs").exists());
+    assert!(out_dir.join("web/types/index.ts").exists());
+    assert!(!out_dir.join("src/models").exists());
+    let lib = fs::read_to_string(out_dir.join("src/lib.rs"))?;
+    assert!(lib.contains("#[path = \"../handlers/mod.rs\"]\npub mod controllers;"));
+
+    fs::write(&config, "models_dir = \"../elsewhere\"\n")?;
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`models_dir` must be a relative path inside the output directory",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn via_import_writes_checkable_via_files_without_clobbering() -> Result<()> {
+    let entities = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_entities");
+    let temp = tempdir()?;
+    let app = temp.path().join("app");
+
+    Command::cargo_bin("via")?
+        .arg("import")
+        .arg("--entities")
+        .arg(&entities)
+        .arg("--out")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 resource(s)"))
+        .stdout(predicate::str::contains("unmapped field(s): thumbnail"));
+
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK: parsed 2 resource(s)"));
+
+    Command::cargo_bin("via")?
+        .arg("import")
+        .arg("--entities")
+        .arg(&entities)
+        .arg("--out")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 0 resource(s)"))
+        .stdout(predicate::str::contains("pass --force to overwrite"));
+
+    Ok(())
+}
+
+#[test]
+fn via_import_openapi_writes_checkable_via_files() -> Result<()> {
+    let spec = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_openapi/spec.json");
+    let temp = tempdir()?;
+    let app = temp.path().join("app");
+
+    Command::cargo_bin("via")?
+        .arg("import")
+        .arg("--openapi")
+        .arg(&spec)
+        .arg("--out")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 4 resource(s)"))
+        .stdout(predicate::str::contains("unmapped field(s): payment, tags"));
+
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK: parsed 3 resource(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_emit_only_writes_matching_files_and_leaves_the_rest() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let lib_rs = out_dir.join("src/lib.rs");
+    fs::create_dir_all(lib_rs.parent().unwrap())?;
+    fs::write(&lib_rs, "// hand-edited\n")?;
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--emit-only")
+        .arg("**/*.ts")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 2 generated file(s)"));
+
+    assert!(out_dir.join("ts/models/article.ts").exists());
+    assert!(out_dir.join("ts/index.ts").exists());
+    assert!(!out_dir.join("src/models/article.rs").exists());
+    assert!(!out_dir.join("via.ir.json").exists());
+    assert_eq!(fs::read_to_string(&lib_rs)?, "// hand-edited\n");
+
+    Ok(())
+}
+
+#[test]
+fn via_check_warns_on_doubled_optional_marks_and_fails_under_strict() -> Result<()> {
+    let app = tempdir()?;
+    fs::write(
+        app.path().join("note.via"),
+        "resource Note {\n  model {\n    field body?: Text?\n  }\n}\n",
+    )?;
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: "))
+        .stderr(predicate::str::contains(
+            "note.via:3:5: field `body` of Note is marked optional on both the name and the type",
+        ));
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("note.via:3:5: field `body` of Note"));
+
+    Ok(())
+}
+
+#[test]
+fn via_check_warns_on_unused_enums_and_fails_under_strict() -> Result<()> {
+    let app = tempdir()?;
+    fs::write(
+        app.path().join("post.via"),
+        "enum Status { draft, published }\n\nenum Mood { happy, sad }\n\nresource Post {\n  model {\n    field status: Status\n  }\n}\n",
+    )?;
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "post.via:3:1: enum `Mood` is declared but no field uses it",
+        ))
+        .stderr(predicate::str::contains("`Status`").not());
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--strict")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("enum `Mood` is declared"));
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_keeps_manual_guard_edits_and_drops_stale_files() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures_extra");
+    let app = app_with_fixture(&fixture.join("authorize.via"))?;
+    let generate = || -> Result<()> {
+        Command::cargo_bin("via")?
+            .arg("gen")
+            .arg("--app")
+            .arg(app.path())
+            .arg("--out")
+            .arg(&out_dir)
+            .assert()
+            .success();
+        Ok(())
+    };
+
+    let removed = app.path().join("removed.via");
+    fs::write(&removed, "resource Removed {\n  model {\n    field name: String\n  }\n}\n")?;
+    generate()?;
+    let stale = out_dir.join("src/models/removed.rs");
+    assert!(stale.exists());
+    let controller = out_dir.join("src/controllers/document.rs");
+    let edited = fs::read_to_string(&controller)?.replace(
+        "    // TODO: authorize Document#create\n",
+        "    // only editors may create\n",
+    );
+    fs::write(&controller, edited)?;
+    fs::remove_file(&removed)?;
+
+    generate()?;
+    let regenerated = fs::read_to_string(&controller)?;
+    assert!(regenerated.contains("    // only editors may create\n"));
+    assert!(regenerated.contains("    // TODO: authorize Document#update\n"));
+    assert!(!stale.exists(), "files no longer generated should be removed");
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_safe_clean_keeps_hand_added_files_that_full_clean_removes() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let generate = |clean: Option<&str>| -> Result<()> {
+        let mut command = Command::cargo_bin("via")?;
+        command
+            .arg("gen")
+            .arg("--app")
+            .arg(fixtures_dir())
+            .arg("--out")
+            .arg(&out_dir);
+        if let Some(mode) = clean {
+            command.arg("--out-root-clean").arg(mode);
+        }
+        command.assert().success();
+        Ok(())
+    };
+
+    generate(None)?;
+    let manifest = fs::read_to_string(out_dir.join("via.manifest"))?;
+    assert!(manifest.lines().any(|line| line == "src/models/article.rs"));
+    let hand_added = out_dir.join("src/helpers.rs");
+    fs::write(&hand_added, "pub fn helper() {}\n")?;
+
+    generate(None)?;
+    assert!(hand_added.exists(), "safe cleaning is the default once a manifest exists");
+    generate(Some("safe"))?;
+    assert!(hand_added.exists());
+    generate(Some("full"))?;
+    assert!(!hand_added.exists(), "full cleaning removes everything not regenerated");
+
+    Ok(())
+}
+
+#[test]
+fn via_verify_skips_cargo_check_without_a_manifest() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+
+    Command::cargo_bin("via")?
+        .arg("verify")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--emit-only")
+        .arg("ts/**")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipping cargo check: no Cargo.toml"));
+
+    assert!(out_dir.join("ts/models/article.ts").exists());
+    assert!(!out_dir.join("Cargo.toml").exists());
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_emit_ir_only_writes_the_ir_and_no_sources() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--emit-ir-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("IR written to"));
+
+    let ir: serde_json::Value = serde_json::from_slice(&fs::read(out_dir.join("via.ir.json"))?)?;
+    assert_eq!(ir["resources"][0]["name"], "Article");
+    assert!(!out_dir.join("src").exists());
+    assert!(!out_dir.join("ts").exists());
+    assert!(!out_dir.join("Cargo.toml").exists());
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_print_ir_writes_only_the_ir_to_stdout() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+
+    let output = Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--print-ir")
+        .output()?;
+    assert!(output.status.success());
+
+    let ir: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(ir["resources"][0]["name"], "Article");
+    assert!(out_dir.join("src/models/article.rs").exists());
+    assert!(!out_dir.join("via.ir.json").exists());
+
+    Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--print-ir")
+        .arg("--ir-format")
+        .arg("yaml")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "ir_version: 2\nresources:\n- name: Article\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn via_check_colors_errors_only_when_asked() -> Result<()> {
+    let app = app_with_fixture(&invalid_fixtures_dir().join("missing_colon.via"))?;
+
+    Command::cargo_bin("via")?
+        .arg("--color")
+        .arg("always")
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with("\u{1b}[1m\u{1b}[31merror\u{1b}[0m:"))
+        .stderr(predicate::str::contains("\u{1b}[1m\u{1b}[31m"));
+
+    Command::cargo_bin("via")?
+        .arg("check")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--color")
+        .arg("never")
+        .env("CLICOLOR_FORCE", "1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with("error:\n"))
+        .stderr(predicate::str::contains("\u{1b}[").not());
+
+    Ok(())
+}
+
+#[test]
+fn via_gen_keeps_source_spans_in_ir_only_with_ir_spans() -> Result<()> {
+    let output = Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--print-ir")
+        .arg("--dry-run")
+        .output()?;
+    assert!(output.status.success());
+    assert!(!String::from_utf8(output.stdout)?.contains("\"span\""));
+
+    let output = Command::cargo_bin("via")?
+        .arg("gen")
+        .arg("--app")
+        .arg(fixtures_dir())
+        .arg("--print-ir")
+        .arg("--dry-run")
+        .arg("--ir-spans")
+        .output()?;
+    assert!(output.status.success());
+    let ir: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let resource = &ir["resources"][0];
+    assert_eq!(resource["span"]["start"]["line"], 1);
+    assert!(resource["span"]["file"].as_str().unwrap().ends_with("article.via"));
+    let field = &resource["model"]["fields"][0];
+    assert_eq!(field["span"]["start"]["line"], 3);
+    assert!(field["span"]["end"]["column"].as_u64().unwrap() > 1);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn via_watch_regenerates_on_change_and_stops_on_ctrl_c() -> Result<()> {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Command as StdCommand, Stdio},
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    let app = app_with_fixture(&fixtures_dir().join("article.via"))?;
+    let out = tempdir()?;
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("via"))
+        .arg("watch")
+        .arg("--app")
+        .arg(app.path())
+        .arg("--out")
+        .arg(out.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (lines, received) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            let _ = lines.send(line);
+        }
+    });
+    let wait_for = |needle: &str| -> Vec<String> {
+        let mut seen = Vec::new();
+        while let Ok(line) = received.recv_timeout(Duration::from_secs(20)) {
+            let found = line.contains(needle);
+            seen.push(line);
+            if found {
+                return seen;
+            }
+        }
+        panic!("`via watch` never printed {needle:?}; got {seen:#?}");
+    };
+
+    wait_for("IR written to");
+    let source = app.path().join("article.via");
+    let edited = fs::read_to_string(&source)?.replace("resource Article", "resource Story");
+    fs::write(&source, edited)?;
+    wait_for("Regenerating for");
+    wait_for("IR written to");
+    assert!(out.path().join("src/models/story.rs").is_file());
+
+    // A save that doesn't parse is reported without ending the watch.
+    fs::write(&source, "resource Story {")?;
+    wait_for("Regenerating for");
+
+    StdCommand::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    wait_for("Stopped watching");
+    assert!(child.wait()?.success());
 
     Ok(())
 }
@@ -1,15 +1,15 @@
 use std::path::Path;
 
 use anyhow::Result;
-use via_core::{codegen, parser};
+use via_core::{codegen, parser, resolve};
 
 #[test]
 fn generates_expected_outputs_for_article_fixture() -> Result<()> {
     let fixture = Path::new("tests/fixtures/article.via");
-    let resources = parser::parse_file(fixture)?;
-    assert_eq!(resources.len(), 1);
+    let program = parser::parse_file(fixture)?;
+    assert_eq!(program.resources.len(), 1);
 
-    let generation = codegen::generate(&resources)?;
+    let generation = codegen::generate(&program)?;
 
     let mut files = generation.files;
     files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
@@ -22,3 +22,1567 @@ fn generates_expected_outputs_for_article_fixture() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn generates_branded_ts_ids_for_blog_fixture() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        ts_branded_ids: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+
+    for file in generation.files {
+        if file.relative_path.starts_with("ts/models") {
+            let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+            let snapshot_name = format!("blog_branded__{}", path_str);
+            insta::assert_snapshot!(snapshot_name, file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn brands_ts_ids_with_the_id_fields_type() -> Result<()> {
+    let options = codegen::GenerateOptions {
+        ts_branded_ids: true,
+        ..Default::default()
+    };
+    // Article has no `id` field, so it gets the default integer key; blog's Comment uses UUIDs.
+    for (name, fixture, path) in [
+        (
+            "integer",
+            "tests/fixtures/article.via",
+            "ts/models/article.ts",
+        ),
+        (
+            "uuid",
+            "tests/fixtures_extra/blog.via",
+            "ts/models/comment.ts",
+        ),
+    ] {
+        let program = parser::parse_file(Path::new(fixture))?;
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let file = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path));
+        insta::assert_snapshot!(format!("branded_ids__{}", name), file.contents);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn expands_editable_wildcard_from_model_fields() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/editable_wildcard.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let model = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/models/post.rs"))
+        .expect("post model should be generated");
+
+    insta::assert_snapshot!("editable_wildcard__src__models__post.rs", model.contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_openapi_with_shared_error_responses() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let spec = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("openapi.json"))
+        .expect("openapi.json should be generated");
+
+    insta::assert_snapshot!("article__openapi.json", spec.contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_http_requests_file_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        http_file: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let requests = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("requests.http"))
+        .expect("requests.http should be generated");
+
+    insta::assert_snapshot!("blog__requests.http", requests.contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_allowed_values_validators_and_unions() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/allowed_values.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str.contains("models__member") {
+            insta::assert_snapshot!(format!("allowed_values__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_proto_messages_and_crud_service() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        proto: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let proto = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("proto/article.proto"))
+        .expect("proto/article.proto should be generated");
+
+    insta::assert_snapshot!("article__proto__article.proto", proto.contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_single_ts_schema_in_dependency_order() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let mut program = parser::parse_file(fixture)?;
+    // Comment references Article through `article_id`, so Article must still come first.
+    program.resources.reverse();
+    let options = codegen::GenerateOptions {
+        ts_branded_ids: true,
+        ts_single_file: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let ts_files: Vec<_> = generation
+        .files
+        .iter()
+        .filter(|file| file.relative_path.starts_with("ts"))
+        .collect();
+    assert_eq!(ts_files.len(), 1);
+    assert_eq!(ts_files[0].relative_path, Path::new("ts/schema.ts"));
+
+    insta::assert_snapshot!("blog_single_file__ts__schema.ts", ts_files[0].contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_computed_field_accessors() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/computed.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str.contains("models__author") {
+            insta::assert_snapshot!(format!("computed__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn wraps_controller_actions_in_tracing_spans() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        tracing: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let controller = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/article.rs"))
+        .expect("article controller should be generated");
+
+    insta::assert_snapshot!("tracing__src__controllers__article.rs", controller.contents);
+
+    Ok(())
+}
+
+#[test]
+fn generates_controller_error_enums_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        errors: true,
+        openapi: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let file = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    insta::assert_snapshot!(
+        "errors__src__controllers__article.rs",
+        file("src/controllers/article.rs").contents
+    );
+    assert!(
+        file("Cargo.toml")
+            .contents
+            .contains("axum = { version = \"0.8\" }")
+    );
+
+    let openapi: serde_json::Value = serde_json::from_str(&file("openapi.json").contents)?;
+    assert_eq!(
+        openapi["components"]["schemas"]["Error"]["properties"]["code"]["enum"],
+        serde_json::json!(["not_found", "validation_failed", "internal_error"])
+    );
+    assert_eq!(
+        openapi["paths"]["/articles/{id}"]["get"]["responses"]["500"]["$ref"],
+        "#/components/responses/InternalServerError"
+    );
+    insta::assert_snapshot!("errors__ts__errors.ts", file("ts/errors.ts").contents);
+    assert!(
+        file("ts/index.ts")
+            .contents
+            .contains("export * from './errors';")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn generates_model_builders_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        builders: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str.starts_with("src__models__") {
+            insta::assert_snapshot!(format!("builders__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_xml_negotiation_for_respond_with_xml() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/xml_responses.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__controllers__invoice.rs" || path_str == "Cargo.toml" {
+            insta::assert_snapshot!(format!("xml_responses__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_authorization_guards_for_listed_actions() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/authorize.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let controller = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/document.rs"))
+        .expect("document controller should be generated");
+
+    insta::assert_snapshot!(
+        "authorize__src__controllers__document.rs",
+        controller.contents
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lists_generated_paths_in_gitattributes_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        gitattributes: true,
+        openapi: true,
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let gitattributes = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new(".gitattributes"))
+        .expect(".gitattributes should be generated");
+
+    insta::assert_snapshot!("blog__gitattributes", gitattributes.contents);
+
+    Ok(())
+}
+
+#[test]
+fn derives_table_names_and_routes_with_irregular_plurals() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/irregular_tables.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let tables: Vec<String> = program.resources.iter().map(|r| r.table_name()).collect();
+    assert_eq!(tables, ["product_categories", "people"]);
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__models__person.rs" || path_str == "src__controllers__person.rs" {
+            insta::assert_snapshot!(format!("irregular_tables__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_encryption_helpers_for_encrypted_fields() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/encrypted.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__models__patient.rs"
+            || path_str == "ts__models__patient.ts"
+            || path_str == "src__entities__patient.rs"
+        {
+            insta::assert_snapshot!(format!("encrypted__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn redacts_sensitive_fields_in_generated_debug_impls() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/sensitive.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        builders: true,
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__models__customer.rs" || path_str == "src__entities__customer.rs" {
+            insta::assert_snapshot!(format!("sensitive__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keeps_transient_fields_in_responses_but_out_of_entity_columns() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/transient.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__models__store.rs" | "src__entities__store.rs" | "ts__models__store.ts"
+        ) {
+            insta::assert_snapshot!(format!("transient__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn summarizes_records_by_title_field_or_id() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/title.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__models__podcast.rs" || path_str == "ts__models__podcast.ts" {
+            insta::assert_snapshot!(format!("title__{}", path_str), file.contents);
+        }
+    }
+
+    // Without a string field the label falls back to the id.
+    let src =
+        "resource Reading {\n  model {\n    field id: Integer\n    field value: Float\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("reading.via"))?;
+    resolve::resolve(&mut program)?;
+    let generation = codegen::generate(&program)?;
+    let summaries: Vec<&str> = generation
+        .files
+        .iter()
+        .flat_map(|file| file.contents.lines())
+        .filter(|line| line.contains("Reading #"))
+        .collect();
+    assert_eq!(
+        summaries,
+        [
+            "        format!(\"Reading #{}\", self.id)",
+            "  return `Reading #${record.id}`;"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn gates_feature_fields_with_cfg_and_omits_them_from_inactive_schemas() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/feature_gated.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        builders: true,
+        proto: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__models__account.rs"
+                | "Cargo.toml"
+                | "ts__models__account.ts"
+                | "proto__account.proto"
+        ) {
+            insta::assert_snapshot!(format!("feature_gated__{}", path_str), file.contents);
+        }
+    }
+
+    let options = codegen::GenerateOptions {
+        features: vec!["enterprise".into()],
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let ts = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("ts/models/account.ts"))
+        .expect("account TS model should be generated");
+    assert!(ts.contents.contains("sso_domain"));
+    assert!(ts.contents.contains("seat_limit"));
+
+    Ok(())
+}
+
+#[test]
+fn writes_modules_under_configured_layout_dirs() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+    let options = codegen::GenerateOptions {
+        layout: codegen::Layout {
+            models_dir: "src/app/models".into(),
+            controllers_dir: "web/controllers".into(),
+            ts_dir: "frontend/types".into(),
+        },
+        ..Default::default()
+    };
+
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let paths: Vec<String> = generation
+        .files
+        .iter()
+        .map(|file| file.relative_path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "src/app/models/article.rs",
+            "web/controllers/article.rs",
+            "src/app/models/mod.rs",
+            "src/app/models/prelude.rs",
+            "web/controllers/mod.rs",
+            "src/lib.rs",
+            "Cargo.toml",
+            "frontend/types/models/article.ts",
+            "frontend/types/index.ts",
+        ]
+    );
+    let lib = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/lib.rs"))
+        .expect("lib.rs should be generated");
+    insta::assert_snapshot!("custom_layout__src__lib.rs", lib.contents);
+
+    Ok(())
+}
+
+#[test]
+fn flat_out_format_renames_files_without_changing_their_count() -> Result<()> {
+    let program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    let paths = |out_format| -> Result<Vec<String>> {
+        let options = codegen::GenerateOptions {
+            out_format,
+            ..Default::default()
+        };
+        Ok(codegen::generate_with_options(&program, &options)?
+            .files
+            .iter()
+            .map(|file| file.relative_path.to_string_lossy().replace('\\', "/"))
+            .collect())
+    };
+
+    let nested = paths(codegen::OutFormat::Nested)?;
+    let flat = paths(codegen::OutFormat::Flat)?;
+    assert_eq!(nested.len(), flat.len());
+    let shared: Vec<&String> = nested.iter().filter(|path| flat.contains(path)).collect();
+    assert_eq!(shared, ["src/lib.rs", "Cargo.toml", "ts/index.ts"]);
+    assert_eq!(
+        flat,
+        [
+            "src/article_model.rs",
+            "src/article_controller.rs",
+            "src/comment_model.rs",
+            "src/comment_controller.rs",
+            "src/models.rs",
+            "src/models_prelude.rs",
+            "src/controllers.rs",
+            "src/lib.rs",
+            "Cargo.toml",
+            "ts/article_model.ts",
+            "ts/comment_model.ts",
+            "ts/index.ts",
+        ]
+    );
+
+    let generation = codegen::generate_with_options(
+        &program,
+        &codegen::GenerateOptions {
+            out_format: codegen::OutFormat::Flat,
+            ..Default::default()
+        },
+    )?;
+    let models = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/models.rs"))
+        .expect("flat models aggregator should be generated");
+    insta::assert_snapshot!("flat_layout__src__models.rs", models.contents);
+
+    let relocated = codegen::GenerateOptions {
+        out_format: codegen::OutFormat::Flat,
+        layout: codegen::Layout {
+            models_dir: "src/app/models".into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(codegen::generate_with_options(&program, &relocated).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn generates_models_prelude_in_sorted_order() -> Result<()> {
+    let program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    let generation = codegen::generate(&program)?;
+    let prelude = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/models/prelude.rs"))
+        .expect("models prelude should be generated");
+    insta::assert_snapshot!("blog__src__models__prelude.rs", prelude.contents);
+
+    Ok(())
+}
+
+#[test]
+fn emits_explicit_sea_orm_entities_only_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let program = parser::parse_file(fixture)?;
+
+    let generation = codegen::generate(&program)?;
+    assert!(
+        generation
+            .files
+            .iter()
+            .all(|file| !file.relative_path.starts_with("src/entities"))
+    );
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        if file.relative_path.starts_with("src/entities") {
+            let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+            insta::assert_snapshot!(format!("explicit_entities__{}", path_str), file.contents);
+        }
+    }
+
+    let blog = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    let generation = codegen::generate_with_options(&blog, &options)?;
+    let comment = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/entities/comment.rs"))
+        .expect("comment entity should be generated");
+    insta::assert_snapshot!("explicit_entities_blog__comment.rs", comment.contents);
+
+    let mut shelters = parser::parse_file(Path::new("tests/fixtures_extra/on_delete.via"))?;
+    resolve::resolve(&mut shelters)?;
+    let generation = codegen::generate_with_options(&shelters, &options)?;
+    let pet = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/entities/pet.rs"))
+        .expect("pet entity should be generated");
+    insta::assert_snapshot!("explicit_entities_on_delete__pet.rs", pet.contents);
+
+    Ok(())
+}
+
+#[test]
+fn annotates_explicit_entities_for_seaography_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/seaography.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    // The source path in each header names the fixture, so only the manifest is checked for the
+    // dependency.
+    for file in &generation.files {
+        assert!(!file.contents.contains("RelatedEntity"));
+        if file.relative_path == Path::new("Cargo.toml") {
+            assert!(!file.contents.contains("seaography"));
+        }
+    }
+
+    let options = codegen::GenerateOptions {
+        seaography: true,
+        ..options
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__entities__notebook.rs" | "src__entities__note.rs" | "Cargo.toml"
+        ) {
+            insta::assert_snapshot!(format!("seaography__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn applies_field_defaults_to_models_params_and_entity_columns() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/defaults.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        builders: true,
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__models__ticket.rs" | "src__entities__ticket.rs" | "openapi.json"
+        ) {
+            insta::assert_snapshot!(format!("defaults__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn wires_declared_relations_into_explicit_entities() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/relations.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__entities__playlist.rs" | "src__entities__track.rs"
+        ) {
+            insta::assert_snapshot!(format!("relations__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn applies_default_scope_to_entity_finders_and_read_stubs() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/default_scope.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__entities__announcement.rs" | "src__controllers__announcement.rs"
+        ) {
+            insta::assert_snapshot!(format!("default_scope__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn foreign_key_columns_are_nullable_exactly_when_the_relation_is_optional() -> Result<()> {
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let cases = [
+        ("required_belongs_to", "field owner_id: Integer", false),
+        ("optional_belongs_to", "field owner_id?: Integer", true),
+        (
+            "required_reference",
+            "field owner: Owner\n    field owner_id: Integer",
+            false,
+        ),
+        (
+            "optional_reference",
+            "field owner?: Owner\n    field owner_id?: Integer",
+            true,
+        ),
+    ];
+    for (case, fields, nullable) in cases {
+        let src = format!(
+            "resource Owner {{\n  model {{\n    field name: String\n  }}\n}}\n\nresource Pet {{\n  model {{\n    {}\n  }}\n}}\n",
+            fields
+        );
+        let mut program = parser::parse_str(&src, Path::new("pet.via"))?;
+        resolve::resolve(&mut program)?;
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let pet = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new("src/entities/pet.rs"))
+            .expect("pet entity should be generated");
+        // The entity's column definitions are what migrations are written against.
+        let columns: Vec<&str> = pet
+            .contents
+            .lines()
+            .filter(|line| line.contains("owner_id") || line.contains("OwnerId"))
+            .collect();
+        assert_eq!(
+            pet.contents
+                .contains("Self::OwnerId => ColumnType::Integer.def().null()"),
+            nullable,
+            "{}",
+            case
+        );
+        insta::assert_snapshot!(format!("fk_nullability__{}", case), columns.join("\n"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn applies_per_action_respond_with_overrides() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/mixed_formats.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let controller = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/report.rs"))
+        .expect("report controller should be generated");
+    insta::assert_snapshot!(
+        "mixed_formats__src__controllers__report.rs",
+        controller.contents
+    );
+
+    Ok(())
+}
+
+#[test]
+fn renders_field_labels_in_html_views() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/labels.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for file in generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if path_str == "src__views__product.rs" || path_str == "assets__views__products__edit.html"
+        {
+            insta::assert_snapshot!(format!("labels__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn renders_html_views_and_wires_controllers_to_them() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/html_views.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let find = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    for path in [
+        "src/views/recipe.rs",
+        "src/controllers/recipe.rs",
+        "assets/views/recipes/list.html",
+        "assets/views/recipes/edit.html",
+    ] {
+        insta::assert_snapshot!(
+            format!("html_views__{}", path.replace('/', "__")),
+            find(path).contents
+        );
+    }
+    for template in ["show", "create"] {
+        find(&format!("assets/views/recipes/{}.html", template));
+    }
+    assert!(find("src/lib.rs").contents.contains("pub mod views;"));
+
+    // Without a `controller` block the default formats include HTML, but no views are emitted.
+    let implicit = parser::parse_str(
+        "resource Note {\n  model {\n    field body: Text\n  }\n}\n",
+        Path::new("note.via"),
+    )?;
+    let generation = codegen::generate(&implicit)?;
+    assert!(
+        !generation
+            .files
+            .iter()
+            .any(|file| file.relative_path.starts_with("src/views"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn renders_sti_resources_as_typescript_discriminated_unions() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/sti.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for name in ["media", "attachment"] {
+        let path = format!("ts/models/{}.ts", name);
+        let file = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(&path))
+            .expect("sti model declarations should be generated");
+        insta::assert_snapshot!(format!("sti__ts__models__{}.ts", name), file.contents);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn applies_default_order_to_index() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/default_order.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let tag = &program.resources[1];
+    let order = tag
+        .default_order
+        .as_ref()
+        .expect("Tag declares default_order");
+    assert_eq!((order.field.as_str(), order.direction()), ("name", "asc"));
+
+    let generation = codegen::generate(&program)?;
+    let controller = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/entry.rs"))
+        .expect("entry controller should be generated");
+    insta::assert_snapshot!(
+        "default_order__src__controllers__entry.rs",
+        controller.contents
+    );
+
+    Ok(())
+}
+
+#[test]
+fn embeds_ir_in_introspection_endpoint_behind_default_feature() -> Result<()> {
+    let fixture = Path::new("tests/fixtures/article.via");
+    let mut program = parser::parse_file(fixture)?;
+    // As `via gen` does without `--ir-spans`.
+    program.strip_spans();
+
+    let options = codegen::GenerateOptions {
+        introspection: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let file = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    insta::assert_snapshot!(
+        "introspection__src__introspection.rs",
+        file("src/introspection.rs").contents
+    );
+    assert!(
+        file("src/lib.rs")
+            .contents
+            .contains("#[cfg(feature = \"introspection\")]\npub mod introspection;\n")
+    );
+    assert!(
+        file("Cargo.toml")
+            .contents
+            .contains("[features]\ndefault = [\"introspection\"]\nintrospection = []\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn renders_typescript_enums_as_unions_or_runtime_objects() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/sti.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    for (name, ts_enum_runtime) in [("sti", false), ("sti_enum_runtime", true)] {
+        let options = codegen::GenerateOptions {
+            ts_enum_runtime,
+            ..Default::default()
+        };
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let enums = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new("ts/enums.ts"))
+            .expect("enum declarations should be generated");
+        insta::assert_snapshot!(format!("{}__ts__enums.ts", name), enums.contents);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn renders_rust_enums_with_display_and_from_str() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/sti.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    for (name, explicit_entities) in [("sti", false), ("sti_entities", true)] {
+        let options = codegen::GenerateOptions {
+            explicit_entities,
+            ..Default::default()
+        };
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let enums = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new("src/models/enums.rs"))
+            .expect("rust enums should be generated");
+        insta::assert_snapshot!(format!("{}__src__models__enums.rs", name), enums.contents);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn renders_conditional_requirements_as_schema_validators() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/required_when.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let model = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/models/delivery.rs"))
+        .expect("delivery model should be generated");
+    insta::assert_snapshot!("required_when__src__models__delivery.rs", model.contents);
+
+    let openapi = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("openapi.json"))
+        .expect("openapi document should be generated");
+    let openapi: serde_json::Value = serde_json::from_str(&openapi.contents)?;
+    let schemas = &openapi["components"]["schemas"];
+    assert_eq!(
+        schemas["DeliveryCreateParams"]["properties"]["shipping_address"]["description"],
+        "Required when `method` is \"ship\"."
+    );
+    assert_eq!(
+        schemas["Delivery"]["properties"]["pickup_code"]["description"],
+        "Required when `priority` is 2."
+    );
+    assert!(
+        schemas["DeliveryUpdateParams"]["properties"]["shipping_address"]
+            .get("description")
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn renders_length_and_value_bounds_as_distinct_validators() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/bounds.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let file = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    insta::assert_snapshot!(
+        "bounds__src__models__review.rs",
+        file("src/models/review.rs").contents
+    );
+
+    let openapi: serde_json::Value = serde_json::from_str(&file("openapi.json").contents)?;
+    let properties = &openapi["components"]["schemas"]["Review"]["properties"];
+    assert_eq!(properties["headline"]["minLength"], 3);
+    assert_eq!(properties["headline"]["maxLength"], 80);
+    assert_eq!(properties["rating"]["minimum"], 1);
+    assert_eq!(properties["rating"]["maximum"], 5);
+    assert!(properties["headline"].get("minimum").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn renders_field_formats_across_outputs() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/formats.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ts_factories: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let file = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    for path in [
+        "src/models/subscriber.rs",
+        "ts/models/subscriber.ts",
+        "ts/factories/subscriber.ts",
+    ] {
+        insta::assert_snapshot!(
+            format!("formats__{}", path.replace('/', "__")),
+            file(path).contents
+        );
+    }
+    let manifest = &file("Cargo.toml").contents;
+    assert!(manifest.contains("uuid = { version = \"1\" }"));
+    assert!(manifest.contains("chrono = { version = \"0.4\" }"));
+
+    let openapi: serde_json::Value = serde_json::from_str(&file("openapi.json").contents)?;
+    let properties = &openapi["components"]["schemas"]["Subscriber"]["properties"];
+    assert_eq!(properties["email"]["format"], "email");
+    assert_eq!(properties["email"]["maxLength"], 254);
+    assert_eq!(properties["homepage"]["format"], "uri");
+    assert_eq!(properties["external_ref"]["format"], "uuid");
+    assert_eq!(properties["confirmed_at"]["format"], "date-time");
+
+    Ok(())
+}
+
+#[test]
+fn json_case_renames_keys_consistently_across_outputs() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/json_case.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    for (name, json_case) in [
+        ("snake", codegen::JsonCase::Snake),
+        ("camel", codegen::JsonCase::Camel),
+    ] {
+        let options = codegen::GenerateOptions {
+            openapi: true,
+            json_case,
+            ..Default::default()
+        };
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let file = |path: &str| {
+            generation
+                .files
+                .iter()
+                .find(|file| file.relative_path == Path::new(path))
+                .unwrap_or_else(|| panic!("{} should be generated", path))
+        };
+        for path in ["src/models/shipment.rs", "ts/models/shipment.ts"] {
+            insta::assert_snapshot!(
+                format!("json_case_{}__{}", name, path.replace('/', "__")),
+                file(path).contents
+            );
+        }
+
+        let openapi: serde_json::Value = serde_json::from_str(&file("openapi.json").contents)?;
+        let schemas = &openapi["components"]["schemas"];
+        let keys = |schema: &str| -> Vec<String> {
+            let mut keys: Vec<String> = schemas[schema]["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect();
+            keys.sort();
+            keys
+        };
+        let fields = [
+            "address_line_2",
+            "delivered_at",
+            "signature_required",
+            "tracking_number",
+            "weight_kg",
+        ];
+        let mut expected: Vec<String> = fields.iter().map(|f| json_case.key(f)).collect();
+        expected.sort();
+        assert_eq!(keys("Shipment"), expected);
+        assert_eq!(keys("ShipmentCreateParams"), expected);
+        assert_eq!(
+            keys("ShipmentPage"),
+            ["items".to_owned(), json_case.key("next_cursor")]
+        );
+    }
+
+    // The TypeScript and OpenAPI keys are only right if they match what serde emits.
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Wire {
+        tracking_number: u8,
+        address_line_2: u8,
+        _leading: u8,
+    }
+    let wire = serde_json::to_value(Wire {
+        tracking_number: 0,
+        address_line_2: 0,
+        _leading: 0,
+    })?;
+    for field in ["tracking_number", "address_line_2", "_leading"] {
+        let key = codegen::JsonCase::Camel.key(field);
+        assert!(wire.get(&key).is_some(), "serde has no `{}` key", key);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn renders_polymorphic_relations_as_type_and_id_columns() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/polymorphic.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        explicit_entities: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for path in [
+        "src/models/like.rs",
+        "src/models/flag.rs",
+        "src/entities/like.rs",
+        "ts/models/like.ts",
+    ] {
+        let file = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path));
+        insta::assert_snapshot!(
+            format!("polymorphic__{}", path.replace('/', "__")),
+            file.contents
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn emits_typescript_factories_only_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let program = parser::parse_file(fixture)?;
+
+    let generation = codegen::generate(&program)?;
+    assert!(
+        generation
+            .files
+            .iter()
+            .all(|file| !file.relative_path.starts_with("ts/factories"))
+    );
+
+    let options = codegen::GenerateOptions {
+        ts_factories: true,
+        ts_branded_ids: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in generation.files {
+        if file.relative_path.starts_with("ts/factories") {
+            let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+            insta::assert_snapshot!(format!("blog_factories__{}", path_str), file.contents);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn marks_deprecated_fields_and_resources_in_every_output() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/deprecated.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in &generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if matches!(
+            path_str.as_str(),
+            "src__models__listing.rs"
+                | "src__models__classified.rs"
+                | "ts__models__listing.ts"
+                | "ts__models__classified.ts"
+        ) {
+            insta::assert_snapshot!(format!("deprecated__{}", path_str), file.contents);
+        }
+    }
+
+    let openapi = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("openapi.json"))
+        .expect("openapi.json should be generated");
+    let openapi: serde_json::Value = serde_json::from_str(&openapi.contents)?;
+    let schemas = &openapi["components"]["schemas"];
+    assert_eq!(
+        schemas["Listing"]["properties"]["price"]["deprecated"],
+        true
+    );
+    assert!(
+        schemas["Listing"]["properties"]["title"]
+            .get("deprecated")
+            .is_none()
+    );
+    assert_eq!(schemas["Classified"]["deprecated"], true);
+    assert_eq!(openapi["paths"]["/classifieds"]["get"]["deprecated"], true);
+    assert!(
+        openapi["paths"]["/listings"]["get"]
+            .get("deprecated")
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pages_index_by_cursor_when_requested() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/cursor_pagination.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    let file = |path: &str| {
+        generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path))
+    };
+    insta::assert_snapshot!(
+        "cursor_pagination__src__controllers__event.rs",
+        file("src/controllers/event.rs").contents
+    );
+    assert!(file("ts/models/event.ts").contents.contains(
+        "export interface EventPage {\n  items: Event[];\n  next_cursor: string | null;\n}"
+    ));
+
+    let openapi: serde_json::Value = serde_json::from_str(&file("openapi.json").contents)?;
+    let index = &openapi["paths"]["/events"]["get"];
+    assert_eq!(
+        index["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/EventPage"
+    );
+    assert_eq!(index["parameters"][0]["name"], "after");
+    assert_eq!(
+        openapi["components"]["schemas"]["EventPage"]["properties"]["next_cursor"]["nullable"],
+        true
+    );
+
+    Ok(())
+}
+
+#[test]
+fn emits_backfill_task_stubs_when_enabled() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/blog.via");
+    let program = parser::parse_file(fixture)?;
+
+    let generation = codegen::generate(&program)?;
+    assert!(
+        generation
+            .files
+            .iter()
+            .all(|file| !file.relative_path.starts_with("src/tasks"))
+    );
+
+    let options = codegen::GenerateOptions {
+        tasks: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+    for file in &generation.files {
+        let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+        if file.relative_path.starts_with("src/tasks") {
+            insta::assert_snapshot!(format!("blog_tasks__{}", path_str), file.contents);
+        }
+    }
+    let lib = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/lib.rs"))
+        .expect("lib.rs should be generated");
+    assert!(lib.contents.contains("pub mod tasks;"));
+
+    Ok(())
+}
+
+#[test]
+fn emits_loco_migrations_in_foreign_key_order() -> Result<()> {
+    // 2025-09-13 12:01:50 UTC, the stamp of loco's `m20250913_120150_images`.
+    assert_eq!(codegen::migration_stamp(1_757_764_910), "20250913_120150");
+
+    for fixture in ["on_delete", "relations", "defaults", "encrypted"] {
+        let path = format!("tests/fixtures_extra/{}.via", fixture);
+        let mut program = parser::parse_file(Path::new(&path))?;
+        resolve::resolve(&mut program)?;
+
+        let options = codegen::GenerateOptions {
+            migrations: true,
+            migration_names: [("clinics".to_owned(), "m20240101_000000_clinics".to_owned())].into(),
+            migration_time: 1_757_764_910,
+            skip_generators: vec!["rust".to_owned(), "typescript".to_owned()],
+            ..Default::default()
+        };
+        let generation = codegen::generate_with_options(&program, &options)?;
+        let paths: Vec<String> = generation
+            .files
+            .iter()
+            .map(|file| file.relative_path.to_string_lossy().into_owned())
+            .collect();
+        match fixture {
+            // Both tables Pet points at come first; Clinic keeps its existing migration.
+            "on_delete" => assert_eq!(
+                paths,
+                [
+                    "migration/src/m20240101_000000_clinics.rs",
+                    "migration/src/m20250913_120150_shelters.rs",
+                    "migration/src/m20250913_120150_pets.rs",
+                ]
+            ),
+            "relations" => assert_eq!(
+                paths,
+                [
+                    "migration/src/m20250913_120150_playlists.rs",
+                    "migration/src/m20250913_120150_tracks.rs",
+                ]
+            ),
+            _ => {}
+        }
+        for file in generation.files {
+            let path_str = file.relative_path.to_string_lossy().replace('/', "__");
+            if path_str.ends_with("_pets.rs")
+                || path_str.ends_with("_tracks.rs")
+                || path_str.ends_with("_tickets.rs")
+                || path_str.ends_with("_patients.rs")
+            {
+                insta::assert_snapshot!(format!("migrations__{}", path_str), file.contents);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn collects_every_controllers_routes_in_resource_name_order() -> Result<()> {
+    // Declared as Shelter, Clinic, Pet.
+    let fixture = Path::new("tests/fixtures_extra/on_delete.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let controllers = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/mod.rs"))
+        .expect("controllers/mod.rs should be generated");
+    assert!(
+        controllers.contents.contains(
+            "pub fn routes() -> Vec<Routes> {\n    vec![\n        clinic::routes(),\n        pet::routes(),\n        shelter::routes(),\n    ]\n}\n"
+        ),
+        "{}",
+        controllers.contents
+    );
+
+    Ok(())
+}
+
+#[test]
+fn renders_manual_actions_with_their_bodies_or_todo() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/manual_actions.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    let controller = generation
+        .files
+        .iter()
+        .find(|file| file.relative_path == Path::new("src/controllers/newsletter.rs"))
+        .expect("controller should be generated");
+    insta::assert_snapshot!("manual_actions__controller", controller.contents);
+
+    Ok(())
+}
+
+#[test]
+fn renders_inline_enums_like_declared_ones() -> Result<()> {
+    let fixture = Path::new("tests/fixtures_extra/inline_enum.via");
+    let mut program = parser::parse_file(fixture)?;
+    resolve::resolve(&mut program)?;
+
+    let generation = codegen::generate(&program)?;
+    for path in ["src/models/campaign.rs", "ts/enums.ts"] {
+        let file = generation
+            .files
+            .iter()
+            .find(|file| file.relative_path == Path::new(path))
+            .unwrap_or_else(|| panic!("{} should be generated", path));
+        insta::assert_snapshot!(
+            format!("inline_enum__{}", path.replace('/', "__")),
+            file.contents
+        );
+    }
+
+    Ok(())
+}
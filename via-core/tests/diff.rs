@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{
+    ast::Program,
+    diff::{self, Impact},
+    parser,
+};
+
+fn parse(src: &str) -> Result<Program> {
+    parser::parse_str(src, Path::new("app.via"))
+}
+
+#[test]
+fn classifies_field_and_resource_changes() -> Result<()> {
+    let old = parse(
+        "enum Status { draft, published }\n\
+         resource Article {\n  model {\n    field title: String\n    field views: Integer\n    field status: Status\n    field legacy: String\n  }\n}\n\
+         resource Tag {\n  model {\n    field label: String\n  }\n}\n",
+    )?;
+    let new = parse(
+        "enum Status { draft, published, archived }\n\
+         resource Article {\n  model {\n    field title: Text\n    field views?: BigInt\n    field status: Status\n    field author_id: Integer\n    field summary?: String\n    field rank: Integer = 0\n  }\n}\n\
+         resource Author {\n  model {\n    field name: String\n  }\n}\n",
+    )?;
+
+    let changes = diff::diff_programs(&old, &new);
+    let described = |impact: Impact| {
+        changes
+            .iter()
+            .filter(|change| change.impact == impact)
+            .map(|change| change.description.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        described(Impact::Breaking),
+        [
+            "Article: field `views` is now optional",
+            "Article: removed field `legacy`",
+            "Article: added required field `author_id: Integer`",
+            "removed resource Tag",
+        ]
+    );
+    assert_eq!(
+        described(Impact::NonBreaking),
+        [
+            "Article: field `title` changed type from String to Text",
+            "Article: field `views` changed type from Integer to BigInt",
+            "Article: added optional field `summary: String`",
+            "Article: added required field `rank: Integer`",
+            "added resource Author",
+            "added relation Article belongs_to Author (via `author_id`)",
+            "added relation Author has_many Article (via `author_id`)",
+            "enum Status: added variant `archived`",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn identical_programs_have_no_changes() -> Result<()> {
+    let src = "resource Tag {\n  model {\n    field label: String @in(\"a\", \"b\")\n  }\n}\n";
+    let changes = diff::diff_programs(&parse(src)?, &parse(src)?);
+    assert!(changes.is_empty());
+    assert_eq!(diff::render(&changes), "No schema changes\n");
+
+    let narrowed = "resource Tag {\n  model {\n    field label: String @in(\"a\")\n  }\n}\n";
+    let changes = diff::diff_programs(&parse(src)?, &parse(narrowed)?);
+    assert_eq!(
+        diff::render(&changes),
+        "Breaking changes (1):\n  - Tag: field `label` no longer allows \"b\"\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{
+    ast::Program,
+    codegen::{self, GenerateOptions, GeneratedFile, Generator},
+    parser,
+};
+
+/// Emits one line per resource, standing in for a user-defined artifact.
+struct ResourceListGenerator;
+
+impl Generator for ResourceListGenerator {
+    fn name(&self) -> &'static str {
+        "resource-list"
+    }
+
+    fn generate(&self, program: &Program) -> Result<Vec<GeneratedFile>> {
+        let contents = program
+            .resources
+            .iter()
+            .map(|resource| format!("{}\n", resource.name))
+            .collect();
+        Ok(vec![GeneratedFile {
+            relative_path: "resources.txt".into(),
+            contents,
+        }])
+    }
+}
+
+#[test]
+fn custom_generators_run_alongside_built_ins() -> Result<()> {
+    let program = parser::parse_file(Path::new("tests/fixtures/article.via"))?;
+    let options = GenerateOptions {
+        skip_generators: vec!["typescript".into()],
+        ..Default::default()
+    };
+
+    let mut generators = codegen::default_generators(&options);
+    generators.push(Box::new(ResourceListGenerator));
+    let generation = codegen::run_generators(&program, &generators)?;
+
+    let paths: Vec<_> = generation
+        .files
+        .iter()
+        .map(|file| file.relative_path.to_string_lossy().into_owned())
+        .collect();
+    assert!(paths.contains(&"src/models/article.rs".to_owned()));
+    assert!(paths.contains(&"resources.txt".to_owned()));
+    assert!(
+        !paths.iter().any(|path| path.starts_with("ts/")),
+        "skipped TypeScript generator should not emit files"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{graph, parser};
+
+#[test]
+fn renders_foreign_keys_as_belongs_to_with_has_many_inverse() -> Result<()> {
+    let program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+
+    let dot = graph::render_dot(&program);
+
+    assert!(dot.starts_with("// @generated by via-core"));
+    assert!(dot.contains("  \"Article\";\n  \"Comment\";\n"));
+    assert!(dot.contains("\"Comment\" -> \"Article\" [label=\"belongs_to (article_id)\"];"));
+    assert!(
+        dot.contains("\"Article\" -> \"Comment\" [label=\"has_many (article_id)\", style=dashed];")
+    );
+    Ok(())
+}
+
+#[test]
+fn resource_typed_fields_are_references() -> Result<()> {
+    let src = r#"
+resource Author {
+  model {
+    field name: String
+  }
+}
+
+resource Book {
+  model {
+    field author: Author
+    field publisher_id: UUID
+  }
+}
+"#;
+    let program = parser::parse_str(src, Path::new("library.via"))?;
+
+    let relations = graph::relations(&program.resources);
+
+    assert_eq!(relations.len(), 1);
+    assert_eq!(relations[0].from, "Book");
+    assert_eq!(relations[0].to, "Author");
+    assert_eq!(relations[0].kind, graph::RelationKind::References);
+    Ok(())
+}
+
+#[test]
+fn polymorphic_belongs_to_points_at_every_target() -> Result<()> {
+    let program = parser::parse_file(Path::new("tests/fixtures_extra/polymorphic.via"))?;
+
+    let dot = graph::render_dot(&program);
+
+    assert!(dot.contains("\"Like\" -> \"Photo\" [label=\"polymorphic (likeable)\"];"));
+    assert!(dot.contains("\"Like\" -> \"Video\" [label=\"polymorphic (likeable)\"];"));
+    // `likeable_id` names no resource, so it is not also read as a plain foreign key.
+    assert!(!dot.contains("likeable_id"));
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{import, parser};
+
+#[test]
+fn imports_sea_orm_entities_as_via_resources() -> Result<()> {
+    let imported = import::import_entities(Path::new("tests/fixtures_entities"))?;
+    let names: Vec<&str> = imported.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Article", "Category"]);
+
+    for resource in &imported {
+        // Imported files must be valid Via so `via check` works right after importing.
+        let parsed = parser::parse_str(&resource.contents, Path::new(&resource.file_name()))?;
+        assert_eq!(parsed.resources[0].name, resource.name);
+        insta::assert_snapshot!(
+            format!("import__{}", resource.file_name()),
+            resource.contents
+        );
+    }
+
+    assert_eq!(imported[0].unmapped, ["thumbnail"]);
+    assert!(imported[1].unmapped.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn imports_openapi_component_schemas_as_via_resources() -> Result<()> {
+    let imported = import::import_openapi(Path::new("tests/fixtures_openapi/spec.json"))?;
+    let names: Vec<&str> = imported.iter().map(|r| r.name.as_str()).collect();
+    // `OrderCreateParams` and `OrderPage` only wrap `Order`, so they are skipped.
+    assert_eq!(names, ["Customer", "Order", "OrderStatus", "Refund"]);
+
+    for resource in &imported {
+        parser::parse_str(&resource.contents, Path::new(&resource.file_name()))?;
+        insta::assert_snapshot!(
+            format!("import__openapi__{}", resource.file_name()),
+            resource.contents
+        );
+    }
+
+    assert_eq!(imported[1].unmapped, ["payment", "tags"]);
+    assert!(
+        imported[3]
+            .contents
+            .contains("# TODO: `allOf` isn't supported")
+    );
+
+    Ok(())
+}
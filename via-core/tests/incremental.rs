@@ -0,0 +1,148 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use via_core::{
+    ast::Program,
+    codegen,
+    incremental::{self, Plan},
+    parser,
+};
+
+/// Owner <- Pet (via `owner_id`), plus an unrelated Invoice, each in its own file.
+fn write_app(dir: &Path) -> Result<Program> {
+    let sources = [
+        (
+            "owner.via",
+            "resource Owner {\n  model {\n    field name: String\n  }\n}\n",
+        ),
+        (
+            "pet.via",
+            "resource Pet {\n  model {\n    field name: String\n    field owner_id: Integer\n  }\n}\n",
+        ),
+        (
+            "invoice.via",
+            "resource Invoice {\n  model {\n    field total: Decimal\n  }\n}\n",
+        ),
+    ];
+    let mut program = Program::default();
+    for (name, source) in sources {
+        let path = dir.join(name);
+        fs::write(&path, source)?;
+        program.extend(parser::parse_file(&path)?);
+    }
+    Ok(program)
+}
+
+fn names(resources: &[&str]) -> BTreeSet<String> {
+    resources.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn plans_changed_resources_and_their_direct_relations() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let program = write_app(dir.path())?;
+
+    let plan = incremental::plan(&program, &[dir.path().join("owner.via")]);
+    assert_eq!(plan, Plan::Partial(names(&["Owner", "Pet"])));
+
+    let plan = incremental::plan(
+        &program,
+        &[PathBuf::from("README.md"), dir.path().join("invoice.via")],
+    );
+    assert_eq!(plan, Plan::Partial(names(&["Invoice"])));
+
+    Ok(())
+}
+
+#[test]
+fn falls_back_to_full_generation_when_partial_would_be_unsafe() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut program = write_app(dir.path())?;
+
+    let deleted = dir.path().join("cat.via");
+    let Plan::Full(reason) = incremental::plan(&program, &[deleted]) else {
+        panic!("a deleted .via file should force full generation");
+    };
+    assert!(reason.ends_with("cat.via was deleted"), "{}", reason);
+
+    assert!(matches!(
+        incremental::plan(&program, &[PathBuf::from("via.toml")]),
+        Plan::Full(_)
+    ));
+
+    let enums = dir.path().join("status.via");
+    fs::write(&enums, "enum Status { draft, published }\n")?;
+    program.extend(parser::parse_file(&enums)?);
+    assert!(matches!(
+        incremental::plan(&program, &[enums]),
+        Plan::Full(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn partial_generation_renders_own_files_and_full_shared_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let program = write_app(dir.path())?;
+
+    let full_options = codegen::GenerateOptions {
+        explicit_entities: true,
+        migrations: true,
+        proto: true,
+        ts_factories: true,
+        tasks: true,
+        ..Default::default()
+    };
+    let options = codegen::GenerateOptions {
+        only: Some(names(&["Invoice"])),
+        ..full_options.clone()
+    };
+    let partial = codegen::generate_with_options(&program, &options)?.files;
+    let paths: BTreeSet<&Path> = partial
+        .iter()
+        .map(|file| file.relative_path.as_path())
+        .collect();
+    for path in [
+        "src/models/invoice.rs",
+        "src/controllers/invoice.rs",
+        "src/entities/invoice.rs",
+        "proto/invoice.proto",
+        "src/models/mod.rs",
+        "src/entities/mod.rs",
+        "src/lib.rs",
+        "ts/index.ts",
+    ] {
+        assert!(paths.contains(Path::new(path)), "{}", path);
+    }
+    for path in [
+        "src/models/pet.rs",
+        "src/entities/pet.rs",
+        "ts/models/pet.ts",
+        "ts/factories/pet.ts",
+        "proto/pet.proto",
+    ] {
+        assert!(!paths.contains(Path::new(path)), "{}", path);
+    }
+
+    // Whatever is rendered matches a full run, so shared files still list every resource.
+    let full = codegen::generate_with_options(&program, &full_options)?.files;
+    for file in &partial {
+        let whole = full
+            .iter()
+            .find(|whole| whole.relative_path == file.relative_path)
+            .expect("a full run renders every file a partial one does");
+        assert_eq!(
+            file.contents,
+            whole.contents,
+            "{}",
+            file.relative_path.display()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use via_core::inflect;
+
+#[test]
+fn pluralizes_regular_and_irregular_resource_names() {
+    let cases = [
+        ("Article", "articles"),
+        ("Category", "categories"),
+        ("Status", "statuses"),
+        ("Box", "boxes"),
+        ("Person", "people"),
+        ("Child", "children"),
+        ("Mouse", "mice"),
+        ("Sheep", "sheep"),
+        ("BlogPost", "blog_posts"),
+        ("SalesPerson", "sales_people"),
+    ];
+    for (name, plural) in cases {
+        assert_eq!(inflect::pluralize(name), plural, "pluralize({name})");
+    }
+}
+
+#[test]
+fn singularizes_table_names() {
+    let cases = [
+        ("articles", "article"),
+        ("categories", "category"),
+        ("statuses", "status"),
+        ("people", "person"),
+        ("children", "child"),
+        ("blog_posts", "blog_post"),
+    ];
+    for (table, singular) in cases {
+        assert_eq!(
+            inflect::singularize(table),
+            singular,
+            "singularize({table})"
+        );
+    }
+}
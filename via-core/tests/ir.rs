@@ -0,0 +1,62 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use via_core::{
+    ir::{self, IR_VERSION},
+    parser, resolve,
+};
+
+#[test]
+fn loads_a_v1_ir_by_migrating_it_forward() -> Result<()> {
+    let program = ir::load(Path::new("tests/fixtures_ir/blog.v1.ir.json"))?;
+
+    // v1 kept declaration order (Post, then Author); v2 orders resources by name.
+    let names: Vec<&str> = program
+        .resources
+        .iter()
+        .map(|resource| resource.name.as_str())
+        .collect();
+    assert_eq!(names, ["Author", "Post"]);
+
+    let rewritten: serde_json::Value = serde_json::from_str(&ir::to_json(&program)?)?;
+    assert_eq!(rewritten["ir_version"], IR_VERSION);
+    Ok(())
+}
+
+#[test]
+fn round_trips_the_current_version_in_json_and_yaml() -> Result<()> {
+    let mut program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    resolve::resolve(&mut program)?;
+    let dir = tempfile::tempdir()?;
+
+    let json = dir.path().join("via.ir.json");
+    fs::write(&json, ir::to_json(&program)?)?;
+    let yaml = dir.path().join("via.ir.yaml");
+    fs::write(&yaml, ir::to_yaml(&program)?)?;
+    assert!(fs::read_to_string(&yaml)?.starts_with(&format!("ir_version: {IR_VERSION}\n")));
+
+    for path in [json, yaml] {
+        let loaded = ir::load(&path)?;
+        assert_eq!(ir::to_json(&loaded)?, ir::to_json(&program)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn rejects_newer_ir_versions_with_a_regenerate_hint() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("via.ir.json");
+    fs::write(
+        &path,
+        format!(r#"{{"ir_version": {}, "resources": []}}"#, IR_VERSION + 1),
+    )?;
+
+    let err = ir::load(&path).unwrap_err().to_string();
+    assert!(err.starts_with(&path.display().to_string()), "{err}");
+    assert!(
+        err.contains(&format!("IR version {} is not supported", IR_VERSION + 1)),
+        "{err}"
+    );
+    assert!(err.contains("regenerate your IR"), "{err}");
+    Ok(())
+}
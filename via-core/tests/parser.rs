@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{
+    ast::{ControllerActions, TypeKind},
+    parser::{self, ParseLimits},
+};
+
+#[test]
+fn deeply_nested_input_is_a_clean_error() {
+    let depth = 100_000;
+    let src = format!("resource Post {}{}", "{".repeat(depth), "}".repeat(depth));
+
+    let err = parser::parse_str(&src, Path::new("nested.via")).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "nested.via:1:47: nesting is deeper than 32 levels"
+    );
+}
+
+#[test]
+fn oversized_input_is_rejected_before_parsing() -> Result<()> {
+    let src = "resource Post {\n  model {\n    field title: String\n  }\n}\n";
+    let limits = ParseLimits {
+        max_bytes: 16,
+        ..Default::default()
+    };
+
+    let err = parser::parse_str_with_limits(src, Path::new("post.via"), &limits).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "post.via: input is {} bytes, over the 16 byte limit",
+            src.len()
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn brackets_in_comments_and_strings_do_not_count_toward_depth() -> Result<()> {
+    let limits = ParseLimits {
+        max_depth: 2,
+        ..Default::default()
+    };
+    let with_comment = "# {{{{ ignored\nresource Post {\n  table \"((((\"\n  model {\n    field title: String\n  }\n}\n";
+    let program = parser::parse_str_with_limits(with_comment, Path::new("post.via"), &limits)?;
+    assert_eq!(program.resources.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn action_bodies_count_brackets_the_way_rust_does() {
+    let limits = ParseLimits {
+        max_depth: 5,
+        ..Default::default()
+    };
+    let parse = |body: &str| {
+        let src = format!(
+            "resource Post {{\n  controller {{\n    actions {{\n      publish {{ {} }}\n    }}\n  }}\n}}\n",
+            body
+        );
+        parser::parse_str_with_limits(&src, Path::new("post.via"), &limits).map(|_| ())
+    };
+    assert!(parse("let s = \"\\\"(((\"; let c = '[';").is_ok());
+    assert_eq!(
+        parse("#[allow(unused)] let v = 1;")
+            .unwrap_err()
+            .to_string(),
+        "post.via:4:24: nesting is deeper than 5 levels"
+    );
+}
+
+#[test]
+fn length_ranges_set_both_bounds_and_reject_inverted_ranges() -> Result<()> {
+    let field = |attr: &str| {
+        format!(
+            "resource Post {{\n  model {{\n    field title: String {}\n  }}\n}}\n",
+            attr
+        )
+    };
+    let bounds = |attr: &str| -> Result<(Option<u64>, Option<u64>)> {
+        let program = parser::parse_str(&field(attr), Path::new("post.via"))?;
+        let attrs = &program.resources[0].model.as_ref().unwrap().fields[0].attributes;
+        Ok((attrs.min_len, attrs.max_len))
+    };
+    assert_eq!(bounds("@length(1..120)")?, (Some(1), Some(120)));
+    assert_eq!(bounds("@length(..120)")?, (None, Some(120)));
+    assert_eq!(bounds("@min_len(2) @length(..120)")?, (Some(2), Some(120)));
+
+    let err = parser::parse_str(&field("@length(120..1)"), Path::new("post.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "post.via:3:25: `@length(120..1)` on field `title` has its minimum above its maximum"
+    );
+    let err = parser::parse_str(&field("@length(..)"), Path::new("post.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "post.via:3:25: `@length(..)` on field `title` has neither a minimum nor a maximum"
+    );
+    Ok(())
+}
+
+#[test]
+fn attribute_errors_carry_the_line_and_column() {
+    let field = |attr: &str| {
+        format!(
+            "resource Post {{\n  model {{\n    field title: String {}\n  }}\n}}\n",
+            attr
+        )
+    };
+    let error = |attr: &str| {
+        parser::parse_str(&field(attr), Path::new("post.via"))
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("@min_len(99999999999999999999)"),
+        "post.via:3:25: @min_len length 99999999999999999999 is out of range"
+    );
+    assert_eq!(
+        error("@label(en: \"Title\", en: \"Heading\")"),
+        "post.via:3:45: duplicate @label locale `en`"
+    );
+}
+
+#[test]
+fn manual_actions_keep_their_bodies_verbatim() -> Result<()> {
+    let src = "resource Post {\n  controller {\n    actions {\n      publish { format::json(json!({ \"ok\": true })) }\n      archive\n    }\n  }\n}\n";
+    let program = parser::parse_str(src, Path::new("post.via"))?;
+    let controller = program.resources[0].controller.as_ref().unwrap();
+    let ControllerActions::Manual(actions) = &controller.actions else {
+        panic!("expected manual actions, got {:?}", controller.actions);
+    };
+    let listed: Vec<(&str, Option<&str>)> = actions
+        .iter()
+        .map(|action| (action.name.as_str(), action.body.as_deref()))
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("publish", Some(" format::json(json!({ \"ok\": true })) ")),
+            ("archive", None)
+        ]
+    );
+
+    let body = r#" let open = "{\"{"; let close = '}'; let quote = '\''; // {
+ fn first<'a>(tags: &'a [String]) -> &'a str { &tags[0] } format::json(json!({ "open": open })) "#;
+    let src = format!(
+        "resource Post {{\n  controller {{\n    actions {{\n      publish {{{}}}\n    }}\n  }}\n}}\n",
+        body
+    );
+    let program = parser::parse_str(&src, Path::new("post.via"))?;
+    let ControllerActions::Manual(actions) =
+        &program.resources[0].controller.as_ref().unwrap().actions
+    else {
+        panic!("expected manual actions");
+    };
+    assert_eq!(actions[0].body.as_deref(), Some(body));
+
+    let src = "resource Post {\n  controller {\n    actions { archive archive }\n  }\n}\n";
+    let err = parser::parse_str(src, Path::new("post.via")).unwrap_err();
+    assert_eq!(err.to_string(), "post.via:3:23: duplicate action `archive`");
+    Ok(())
+}
+
+#[test]
+fn inline_enums_are_named_after_their_field_and_declared() -> Result<()> {
+    let field = |ty: &str| {
+        format!(
+            "resource Article {{\n  model {{\n    field status: {}\n  }}\n}}\n",
+            ty
+        )
+    };
+    let program = parser::parse_str(
+        &field("enum(draft, published, archived)"),
+        Path::new("article.via"),
+    )?;
+    let ty = &program.resources[0].model.as_ref().unwrap().fields[0].ty;
+    assert_eq!(ty.name, "ArticleStatus");
+    assert_eq!(
+        ty.kind,
+        TypeKind::Enum(vec!["draft".into(), "published".into(), "archived".into()])
+    );
+    assert_eq!(program.enums.len(), 1);
+    assert_eq!(program.enums[0].name, "ArticleStatus");
+    assert_eq!(
+        program.enums[0].variants,
+        ["draft", "published", "archived"]
+    );
+
+    let err = parser::parse_str(&field("enum()"), Path::new("article.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "article.via:3:19: enum(...) needs at least one variant"
+    );
+    let err =
+        parser::parse_str(&field("enum(draft, draft)"), Path::new("article.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "article.via:3:31: enum variant `draft` is listed more than once"
+    );
+    Ok(())
+}
@@ -0,0 +1,581 @@
+use std::path::Path;
+
+use anyhow::Result;
+use via_core::{ast::Literal, parser, resolve};
+
+#[test]
+fn enums_no_field_uses_are_reported() -> Result<()> {
+    let src = "enum Tier { free, pro }\n\nenum Plan { basic }\n\nresource Account {\n  model {\n    field plan: Plan\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("account.via"))?;
+
+    let warnings = resolve::resolve_with_options(&mut program, &Default::default())?;
+
+    assert_eq!(
+        warnings,
+        ["account.via:1:1: enum `Tier` is declared but no field uses it"]
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_sql_reserved_words_as_columns_and_tables() -> Result<()> {
+    let src = "resource Purchase {\n  model {\n    field order: Integer\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("purchase.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "purchase.via: field `order` of Purchase is a reserved SQL word and can't be an unquoted \
+         column; rename it (e.g. `purchase_order`)"
+    );
+
+    let src =
+        "resource Purchase {\n  table \"user\"\n\n  model {\n    field total: Integer\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("purchase.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "purchase.via: table name `user` of Purchase is a reserved SQL word; pick another with \
+         `table \"...\"`"
+    );
+    Ok(())
+}
+
+#[test]
+fn cursor_pagination_needs_a_required_order_field() -> Result<()> {
+    let src = "resource Event {\n  pagination: cursor\n  default_order: occurred_at\n\n  model {\n    field occurred_at?: DateTime\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("event.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "event.via: cursor pagination on Event orders by `occurred_at`, which must be a required \
+         field"
+    );
+
+    let src =
+        "resource Event {\n  pagination: offset\n\n  model {\n    field name: String\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("event.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "event.via: unknown pagination `offset` of Event (expected `cursor`)"
+    );
+    Ok(())
+}
+
+#[test]
+fn on_delete_needs_a_known_foreign_key_and_action() -> Result<()> {
+    let src =
+        "resource Pet {\n  model {\n    field owner_id: Integer @on_delete(cascade)\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("pet.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pet.via: @on_delete on Pet.owner_id needs a foreign key named `<resource>_id`"
+    );
+
+    let src = "resource Owner {\n  model {\n    field name: String\n  }\n}\n\nresource Pet {\n  model {\n    field owner_id: Integer @on_delete(nullify)\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("pet.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pet.via: unknown @on_delete action `nullify` on Pet.owner_id (expected one of: cascade, \
+         restrict, set_null)"
+    );
+    Ok(())
+}
+
+#[test]
+fn required_when_rules_need_known_optional_fields() -> Result<()> {
+    let src = "resource Delivery {\n  validate {\n    require address when method == \"ship\"\n  }\n\n  model {\n    field method: String\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("delivery.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "delivery.via: validate rule on Delivery refers to unknown field `address`"
+    );
+
+    let src = "resource Delivery {\n  validate {\n    require address when method == \"ship\"\n  }\n\n  model {\n    field method: String\n    field address: Text\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("delivery.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "delivery.via: `require address` on Delivery has no effect because the field is always \
+         required; make it optional (`address?`)"
+    );
+    Ok(())
+}
+
+#[test]
+fn relations_and_their_foreign_keys_agree_on_optionality() -> Result<()> {
+    let owner = "resource Owner {\n  model {\n    field name: String\n  }\n}\n\n";
+    let src = format!(
+        "{}resource Pet {{\n  model {{\n    field owner?: Owner\n    field owner_id: Integer\n  }}\n}}\n",
+        owner
+    );
+    let mut program = parser::parse_str(&src, Path::new("pet.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pet.via: Pet.owner is optional but its foreign key `owner_id` is required, so its column \
+         would be NOT NULL; mark the key optional (`owner_id?`)"
+    );
+
+    let src = format!(
+        "{}resource Pet {{\n  model {{\n    field owner: Owner\n    field owner_id?: Integer\n  }}\n}}\n",
+        owner
+    );
+    let mut program = parser::parse_str(&src, Path::new("pet.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pet.via: Pet.owner is required but its foreign key `owner_id` is optional, so its column \
+         would be nullable; mark the relation optional (`owner?`) or the key required"
+    );
+    Ok(())
+}
+
+#[test]
+fn formats_need_a_known_name_and_a_string_field() -> Result<()> {
+    let src = "resource Subscriber {\n  model {\n    field email: String @format(mail)\n  }\n}\n";
+    let err = parser::parse_str(src, Path::new("subscriber.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "subscriber.via:3:33: unknown @format `mail` (expected one of: uuid, email, uri, \
+         date-time)"
+    );
+
+    let src = "resource Subscriber {\n  model {\n    field rank: Integer @format(uuid)\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("subscriber.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "subscriber.via: @format(uuid) on field `rank` of Subscriber requires a String or Text \
+         type, found Integer"
+    );
+    Ok(())
+}
+
+#[test]
+fn derives_must_be_known_complete_and_supported_by_every_field() -> Result<()> {
+    let resolve_err = |derives: &str, ty: &str| {
+        let src = format!(
+            "resource Point {{\n  derives: [{}]\n  model {{\n    field x: {}\n  }}\n}}\n",
+            derives, ty
+        );
+        let mut program = parser::parse_str(&src, Path::new("point.via")).unwrap();
+        resolve::resolve(&mut program).unwrap_err().to_string()
+    };
+
+    assert_eq!(
+        resolve_err("Copy", "Integer"),
+        "point.via: unknown derive `Copy` on Point (expected one of: PartialEq, Eq, PartialOrd, \
+         Ord, Hash)"
+    );
+    assert_eq!(
+        resolve_err("Eq", "Integer"),
+        "point.via: derive `Eq` on Point also needs `PartialEq`"
+    );
+    assert_eq!(
+        resolve_err("Hash, Hash", "Integer"),
+        "point.via: derive `Hash` listed twice on Point"
+    );
+    assert_eq!(
+        resolve_err("Hash", "Float"),
+        "point.via: derive `Hash` on Point is not implemented by field `x` (Float)"
+    );
+
+    let src = "resource Point {\n  derives: [PartialEq, Eq, PartialOrd, Ord, Hash]\n  model {\n    field x: Integer\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("point.via"))?;
+    resolve::resolve(&mut program)?;
+    Ok(())
+}
+
+#[test]
+fn polymorphic_targets_are_resources_with_integer_ids() -> Result<()> {
+    let photo = "resource Photo {\n  model {\n    field url: String\n  }\n}\n\n";
+    let src = format!(
+        "{}resource Like {{\n  model {{\n    belongs_to likeable: (Photo | Video)\n  }}\n}}\n",
+        photo
+    );
+    let mut program = parser::parse_str(&src, Path::new("like.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "like.via: `belongs_to likeable` on Like targets `Video`, which is not a resource with a \
+         model"
+    );
+
+    let src = format!(
+        "{}resource Video {{\n  model {{\n    field id: UUID\n  }}\n}}\n\nresource Like {{\n  model {{\n    belongs_to likeable: (Photo | Video)\n  }}\n}}\n",
+        photo
+    );
+    let mut program = parser::parse_str(&src, Path::new("like.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "like.via: `belongs_to likeable` on Like targets `Video`, whose id is UUID; `likeable_id` \
+         holds Integer ids"
+    );
+    Ok(())
+}
+
+#[test]
+fn declared_relations_point_at_resources_and_type_their_keys() -> Result<()> {
+    let post = "resource Post {\n  model {\n    field id: UUID\n    has_many comments\n  }\n}\n\n";
+    let src = format!(
+        "{}resource Comment {{\n  model {{\n    belongs_to post?\n  }}\n}}\n",
+        post
+    );
+    let mut program = parser::parse_str(&src, Path::new("blog.via"))?;
+    resolve::resolve(&mut program)?;
+    let model = program.resources[0].model.as_ref().unwrap();
+    assert_eq!(program.resources[0].name, "Comment");
+    assert_eq!(model.fields[0].name, "post_id");
+    assert_eq!(model.fields[0].ty.name, "UUID");
+    assert!(model.fields[0].optional);
+
+    let src = "resource Comment {\n  model {\n    belongs_to post\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("comment.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "comment.via: `belongs_to post` on Comment points at `Post`, which is not a resource with \
+         a model"
+    );
+
+    let src = format!(
+        "{}resource Comment {{\n  model {{\n    field body: Text\n  }}\n}}\n",
+        post
+    );
+    let mut program = parser::parse_str(&src, Path::new("blog.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "blog.via: `has_many comments` on Post needs Comment to `belongs_to post` (or declare a \
+         `post_id` field)"
+    );
+
+    let src = format!(
+        "{}resource Comment {{\n  model {{\n    field post_id: UUID\n    belongs_to post\n  }}\n}}\n",
+        post
+    );
+    let mut program = parser::parse_str(&src, Path::new("blog.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "blog.via: Comment declares `post_id` both as a field and through `belongs_to post`"
+    );
+    Ok(())
+}
+
+#[test]
+fn defaults_match_their_field_type_and_make_the_column_required() -> Result<()> {
+    let resolve_err = |field: &str| {
+        let src = format!(
+            "enum Priority {{ low, high }}\n\nresource Ticket {{\n  model {{\n    {}\n  }}\n}}\n",
+            field
+        );
+        let mut program = parser::parse_str(&src, Path::new("ticket.via")).unwrap();
+        resolve::resolve(&mut program).unwrap_err().to_string()
+    };
+    assert_eq!(
+        resolve_err("field votes: Integer = \"many\""),
+        "ticket.via: default `\"many\"` for `votes` of Ticket does not match its type Integer"
+    );
+    assert_eq!(
+        resolve_err("field votes: Integer = 3000000000"),
+        "ticket.via: default `3000000000` for `votes` of Ticket does not match its type Integer"
+    );
+    assert_eq!(
+        resolve_err("field due_on: Date = now()"),
+        "ticket.via: default `now()` for `due_on` of Ticket does not match its type Date"
+    );
+    assert_eq!(
+        resolve_err("field priority: Priority = \"urgent\""),
+        "ticket.via: default `\"urgent\"` for `priority` of Ticket does not match its type \
+         Priority"
+    );
+    assert_eq!(
+        resolve_err("field status: String = \"new\" @in(\"open\", \"closed\")"),
+        "ticket.via: default `\"new\"` for `status` of Ticket is not one of its `@in` values"
+    );
+    assert_eq!(
+        resolve_err("field rank: Integer = 0 @transient"),
+        "ticket.via: default `0` for `rank` of Ticket is on a transient field, which is never \
+         stored"
+    );
+
+    let src = "resource Ticket {\n  model {\n    field votes: BigInt = 3000000000\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("ticket.via"))?;
+    resolve::resolve(&mut program)?;
+
+    let src = "resource Ticket {\n  model {\n    field status?: String = \"open\"\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("ticket.via"))?;
+    resolve::resolve(&mut program)?;
+    let field = &program.resources[0].model.as_ref().unwrap().fields[0];
+    assert!(!field.optional);
+    assert_eq!(
+        field.attributes.default,
+        Some(Literal::String("open".into()))
+    );
+    Ok(())
+}
+
+#[test]
+fn relation_cycles_are_allowed_through_an_optional_key() -> Result<()> {
+    let src = "resource Employee {\n  model {\n    field team_id: Integer\n  }\n}\n\nresource Team {\n  model {\n    field lead_id?: Integer\n  }\n}\n\nresource Lead {\n  model {\n    field employee_id: Integer\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("team.via"))?;
+    resolve::resolve(&mut program)?;
+
+    let src = "resource Node {\n  model {\n    field node_id: Integer\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("node.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "node.via: required foreign keys form a cycle (Node.node_id -> Node), so no row can be \
+         inserted first; make one of them optional (e.g. `node_id?` on Node)"
+    );
+    Ok(())
+}
+
+#[test]
+fn labels_reject_a_repeated_locale() {
+    let src = "resource Product {\n  model {\n    field name: String @label(en: \"Name\", en: \"Title\")\n  }\n}\n";
+    let err = parser::parse_str(src, Path::new("product.via")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "product.via:3:43: duplicate @label locale `en`"
+    );
+}
+
+#[test]
+fn transient_fields_are_skipped_by_wildcards_and_rejected_as_columns() -> Result<()> {
+    let src = "resource Store {\n  model {\n    field name: String\n    field distance_km?: Float @transient\n  }\n\n  controller {\n    params {\n      editable: *\n    }\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("store.via"))?;
+    resolve::resolve(&mut program)?;
+    let editable: Vec<&str> = program.resources[0].controller.as_ref().unwrap().params[0]
+        .entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect();
+    assert_eq!(editable, ["name"]);
+
+    let src = "resource Store {\n  model {\n    field distance_km?: Float @transient\n  }\n\n  controller {\n    params {\n      editable { distance_km }\n    }\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("store.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "store.via: transient field `distance_km` of Store cannot be listed in editable params"
+    );
+
+    let src = "resource Region {\n  model {\n    field name: String\n  }\n}\n\nresource Store {\n  model {\n    field region_id: Integer @transient\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("store.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "store.via: foreign key Store.region_id can't be @transient; it needs a column for the \
+         relation to Region"
+    );
+    Ok(())
+}
+
+#[test]
+fn resources_are_ordered_by_name_regardless_of_declaring_file() -> Result<()> {
+    let order = |first: &str, second: &str| -> Result<Vec<String>> {
+        let mut program = parser::parse_str(
+            &format!("resource {first} {{\n  model {{\n    field name: String\n  }}\n}}\n"),
+            Path::new("a.via"),
+        )?;
+        program.extend(parser::parse_str(
+            &format!("resource {second} {{\n  model {{\n    field name: String\n  }}\n}}\n"),
+            Path::new("b.via"),
+        )?);
+        resolve::resolve(&mut program)?;
+        Ok(program.resources.into_iter().map(|r| r.name).collect())
+    };
+
+    assert_eq!(order("Zone", "Area")?, ["Area", "Zone"]);
+    assert_eq!(order("Area", "Zone")?, ["Area", "Zone"]);
+    Ok(())
+}
+
+#[test]
+fn default_scope_values_must_match_the_field_type() {
+    let resolve_scope = |condition: &str| {
+        let src = format!(
+            "enum Audience {{ everyone, staff }}\n\nresource Announcement {{\n  default_scope {{\n    {condition}\n  }}\n\n  model {{\n    field published: Boolean\n    field priority: Integer\n    field audience: Audience\n    field teaser?: String @transient\n  }}\n}}\n"
+        );
+        let mut program = parser::parse_str(&src, Path::new("announcement.via")).unwrap();
+        resolve::resolve(&mut program).map_err(|err| err.to_string())
+    };
+
+    assert!(resolve_scope("where published == false").is_ok());
+    assert!(resolve_scope("where priority == 2").is_ok());
+    assert_eq!(
+        resolve_scope("where published == \"yes\"").unwrap_err(),
+        "announcement.via: `where published == \"yes\"` in default_scope of Announcement must \
+         compare with `true` or `false`"
+    );
+    assert_eq!(
+        resolve_scope("where priority == \"high\"").unwrap_err(),
+        "announcement.via: `where priority == \"high\"` in default_scope of Announcement must \
+         compare with an integer"
+    );
+    assert_eq!(
+        resolve_scope("where audience == \"admins\"").unwrap_err(),
+        "announcement.via: `where audience == \"admins\"` in default_scope of Announcement must \
+         compare with one of Audience (everyone, staff)"
+    );
+    assert_eq!(
+        resolve_scope("where archived == true").unwrap_err(),
+        "announcement.via: default_scope of Announcement names unknown field `archived`"
+    );
+    assert_eq!(
+        resolve_scope("where teaser == \"\"").unwrap_err(),
+        "announcement.via: default_scope of Announcement can't filter on `teaser` because it is \
+         @transient"
+    );
+}
+
+#[test]
+fn title_must_be_one_serialized_string_field() {
+    let resolve_fields = |fields: &str| {
+        let src = format!("resource Podcast {{\n  model {{\n{fields}  }}\n}}\n");
+        let mut program = parser::parse_str(&src, Path::new("podcast.via")).unwrap();
+        resolve::resolve(&mut program).map_err(|err| err.to_string())
+    };
+
+    assert!(resolve_fields("    field headline?: Text @title\n").is_ok());
+    assert_eq!(
+        resolve_fields("    field host: String @title\n    field headline: String @title\n")
+            .unwrap_err(),
+        "podcast.via: Podcast marks more than one field @title (host, headline); keep one"
+    );
+    assert_eq!(
+        resolve_fields("    field episodes: Integer @title\n").unwrap_err(),
+        "podcast.via: @title field `episodes` of Podcast is Integer, not String or Text"
+    );
+    assert_eq!(
+        resolve_fields("    field host: String @encrypted @title\n").unwrap_err(),
+        "podcast.via: @title field `host` of Podcast isn't serialized"
+    );
+}
+
+#[test]
+fn inline_enums_cannot_reuse_a_declared_enum_name() -> Result<()> {
+    let src = "enum ArticleStatus { open }\n\nresource Article {\n  model {\n    field status: enum(draft, published)\n  }\n}\n";
+    let mut program = parser::parse_str(src, Path::new("article.via"))?;
+    let err = resolve::resolve(&mut program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "article.via: duplicate enum `ArticleStatus` (first defined in article.via)"
+    );
+    Ok(())
+}
+
+#[test]
+fn every_invalid_fixture_fails_with_its_error() -> Result<()> {
+    let cases = [
+        (
+            "computed_editable.via",
+            "computed field `full_name` of Author cannot be listed in editable params",
+        ),
+        (
+            "default_order_direction.via",
+            "default_order direction `descending` of Post must be `asc` or `desc`",
+        ),
+        (
+            "duplicate_enum_variant.via",
+            "enum `Status` lists variant `draft` more than once",
+        ),
+        (
+            "duplicate_field.via",
+            "duplicate_field.via:5:5: duplicate field `title` in resource Article",
+        ),
+        (
+            "duplicate_param.via",
+            "duplicate_param.via:9:31: duplicate param `title` in params profile editable",
+        ),
+        ("empty_enum.via", "enum `Empty` has no variants"),
+        (
+            "encrypted_in.via",
+            "`@encrypted` field `tier` of Account also uses `@in`",
+        ),
+        (
+            "enum_case_collision.via",
+            "enum `Visibility` variants `Public` and `public` both become `Public`",
+        ),
+        (
+            "invalid_feature.via",
+            "feature `enterprise edition` on field `sso_domain` of Account must be a cargo \
+             feature name",
+        ),
+        (
+            "invalid_table.via",
+            "table name `tags; drop` of Tag must be a plain identifier",
+        ),
+        (
+            "max_on_string.via",
+            "@max on field `headline` of Review requires a numeric type, found String",
+        ),
+        (
+            "min_len_on_integer.via",
+            "@min_len on field `rating` of Review requires a String or Text type, found Integer",
+        ),
+        ("missing_colon.via", "missing_colon.via:3:17"),
+        (
+            "on_delete_set_null_required.via",
+            "@on_delete(set_null) on Dog.owner_id needs an optional field (`owner_id?`)",
+        ),
+        (
+            "required_relation_cycle.via",
+            "required foreign keys form a cycle (Household.tenant_id -> Tenant, \
+             Tenant.household_id -> Household), so no row can be inserted first; make one of \
+             them optional (e.g. `household_id?` on Tenant)",
+        ),
+        (
+            "reserved_field_name.via",
+            "field `type` of Payment is a Rust keyword and can't be a struct field; rename it \
+             (e.g. `payment_type`)",
+        ),
+        (
+            "sti_optional.via",
+            "sti field `kind` of Article must be required and serialized",
+        ),
+        (
+            "unknown_action_override.via",
+            "action block names unknown action `publish` in Report",
+        ),
+        (
+            "unsupported_format.via",
+            "unsupported respond_with format `yaml` in Report (expected one of: html, json, xml)",
+        ),
+        (
+            "unsupported_in_type.via",
+            "@in on field `enabled` of Flag requires a String, Text, Integer, or BigInt type",
+        ),
+    ];
+
+    let dir = Path::new("tests/fixtures_invalid");
+    let mut fixtures: Vec<String> = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<_>>()?;
+    fixtures.sort();
+    let listed: Vec<&str> = cases.iter().map(|(fixture, _)| *fixture).collect();
+    assert_eq!(
+        fixtures, listed,
+        "every invalid fixture needs a case, in name order"
+    );
+
+    for (fixture, message) in cases {
+        let err = parser::parse_file(&dir.join(fixture))
+            .and_then(|mut program| resolve::resolve(&mut program).map(|_| ()))
+            .expect_err(fixture)
+            .to_string();
+        assert!(
+            err.contains(message),
+            "{fixture}: expected {message:?}, got {err:?}"
+        );
+    }
+    Ok(())
+}
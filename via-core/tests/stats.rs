@@ -0,0 +1,32 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use via_core::{codegen, parser, resolve, stats::GenStats};
+
+#[test]
+fn counts_ir_and_attributes_files_to_generators() -> Result<()> {
+    let mut program = parser::parse_file(Path::new("tests/fixtures_extra/blog.via"))?;
+    resolve::resolve(&mut program)?;
+    let options = codegen::GenerateOptions {
+        openapi: true,
+        ..Default::default()
+    };
+    let generation = codegen::generate_with_options(&program, &options)?;
+
+    let mut stats = GenStats::new(&program, Duration::ZERO);
+    stats.record_generation(&generation, Duration::ZERO);
+
+    assert_eq!(stats.resources, program.resources.len());
+    assert_eq!(stats.relations, 2);
+    let names: Vec<&str> = stats.generators.iter().map(|g| g.name).collect();
+    assert_eq!(names, ["rust", "typescript", "openapi"]);
+    assert_eq!(stats.total_files(), generation.files.len());
+    let bytes: usize = generation
+        .files
+        .iter()
+        .map(|file| file.contents.len())
+        .sum();
+    assert_eq!(stats.total_bytes(), bytes);
+    assert_eq!(stats.generators[2].files, 1);
+    Ok(())
+}
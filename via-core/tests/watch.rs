@@ -0,0 +1,88 @@
+use std::{fs, sync::atomic::AtomicBool, thread, time::Duration};
+
+use anyhow::Result;
+use via_core::watch::{Snapshot, Watcher};
+
+#[test]
+fn snapshot_changes_list_added_removed_and_modified_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let kept = dir.path().join("kept.via");
+    let edited = dir.path().join("edited.via");
+    let removed = dir.path().join("removed.via");
+    for path in [&kept, &edited, &removed] {
+        fs::write(path, "resource A {}\n")?;
+    }
+    let before = Snapshot::capture(&[kept.clone(), edited.clone(), removed.clone()]);
+    assert!(before.changes(&before).is_empty());
+
+    let added = dir.path().join("added.via");
+    fs::write(&added, "resource B {}\n")?;
+    fs::write(&edited, "resource A { model {} }\n")?;
+    let after = Snapshot::capture(&[added.clone(), edited.clone(), kept.clone()]);
+
+    assert_eq!(after.changes(&before), vec![added, edited, removed]);
+    Ok(())
+}
+
+#[test]
+fn wait_for_change_returns_once_an_edit_settles() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("a.via");
+    fs::write(&path, "resource A {}\n")?;
+    let files = vec![path.clone()];
+    let last = Snapshot::capture(&files);
+    let watcher = Watcher::new(&[dir.path().to_path_buf()])?;
+
+    let writer = {
+        let path = path.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            fs::write(&path, "resource A { model {} }\n")
+        })
+    };
+    let (snapshot, changed) = watcher
+        .wait_for_change(&last, &AtomicBool::new(false), || Ok(files.clone()))?
+        .expect("nothing raised the stop flag");
+    writer.join().unwrap()?;
+
+    assert_eq!(changed, vec![path]);
+    assert_eq!(snapshot, Snapshot::capture(&files));
+    Ok(())
+}
+
+#[test]
+fn wait_for_change_returns_none_once_stopped() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("a.via");
+    fs::write(&path, "resource A {}\n")?;
+    let files = vec![path];
+    let last = Snapshot::capture(&files);
+    let watcher = Watcher::new(&[dir.path().to_path_buf()])?;
+
+    let stop = AtomicBool::new(true);
+    assert!(
+        watcher
+            .wait_for_change(&last, &stop, || Ok(files.clone()))?
+            .is_none()
+    );
+    Ok(())
+}
+
+#[test]
+fn wait_for_change_notices_an_edit_made_before_it_was_called() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("a.via");
+    fs::write(&path, "resource A {}\n")?;
+    let files = vec![path.clone()];
+    let last = Snapshot::capture(&files);
+    let watcher = Watcher::new(&[dir.path().to_path_buf()])?;
+
+    // Saved while the previous run was still generating.
+    fs::write(&path, "resource A { model {} }\n")?;
+    let (_, changed) = watcher
+        .wait_for_change(&last, &AtomicBool::new(false), || Ok(files.clone()))?
+        .expect("nothing raised the stop flag");
+
+    assert_eq!(changed, vec![path]);
+    Ok(())
+}
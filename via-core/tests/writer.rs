@@ -0,0 +1,243 @@
+use std::{fs, num::NonZeroUsize};
+
+use anyhow::Result;
+use tempfile::tempdir;
+use via_core::{codegen::GeneratedFile, writer};
+
+fn generated(path: &str, contents: &str) -> GeneratedFile {
+    GeneratedFile {
+        relative_path: path.into(),
+        contents: contents.to_owned(),
+    }
+}
+
+#[test]
+fn write_files_replaces_contents_without_leaving_temp_files() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+
+    writer::write_files(&out_dir, &[generated("src/lib.rs", "// first\n")])?;
+    writer::write_files(&out_dir, &[generated("src/lib.rs", "// second\n")])?;
+
+    assert_eq!(
+        fs::read_to_string(out_dir.join("src/lib.rs"))?,
+        "// second\n"
+    );
+    let entries: Vec<_> = fs::read_dir(out_dir.join("src"))?
+        .map(|entry| entry.map(|e| e.file_name()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        entries,
+        vec!["lib.rs"],
+        "temp files should be renamed into place"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn write_files_keeps_manual_region_edits() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let original = "fn a() {}\n// via:manual-begin guard\nfn guard() {}\n// via:manual-end guard\n";
+    writer::write_files(&out_dir, &[generated("src/lib.rs", original)])?;
+
+    let edited = original.replace("fn guard() {}", "fn guard() { deny() }");
+    fs::write(out_dir.join("src/lib.rs"), &edited)?;
+
+    let regenerated = "fn b() {}\n// via:manual-begin guard\nfn guard() {}\n// via:manual-end guard\n// via:manual-begin other\nfn other() {}\n// via:manual-end other\n";
+    writer::write_files(&out_dir, &[generated("src/lib.rs", regenerated)])?;
+
+    assert_eq!(
+        fs::read_to_string(out_dir.join("src/lib.rs"))?,
+        "fn b() {}\n// via:manual-begin guard\nfn guard() { deny() }\n// via:manual-end guard\n// via:manual-begin other\nfn other() {}\n// via:manual-end other\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn clean_output_root_drops_generated_gitattributes_no_longer_emitted() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let gitattributes =
+        "# @generated by via-core. DO NOT EDIT BY HAND.\n/src/lib.rs linguist-generated=true\n";
+    writer::write_files(
+        &out_dir,
+        &[
+            generated(".gitattributes", gitattributes),
+            generated("src/lib.rs", "// lib\n"),
+        ],
+    )?;
+
+    writer::clean_output_root(&out_dir, &[generated("src/lib.rs", "// lib\n")])?;
+    assert!(!out_dir.join(".gitattributes").exists());
+
+    fs::write(out_dir.join(".gitattributes"), "*.rs text\n")?;
+    writer::clean_output_root(&out_dir, &[generated("src/lib.rs", "// lib\n")])?;
+    assert!(
+        out_dir.join(".gitattributes").exists(),
+        "a hand-written .gitattributes is left alone"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parallel_writes_match_sequential_writes() -> Result<()> {
+    let tmp = tempdir()?;
+    let files: Vec<GeneratedFile> = (0..40)
+        .map(|i| {
+            generated(
+                &format!("src/models/m{}.rs", i),
+                &format!("// model {}\n", i),
+            )
+        })
+        .collect();
+    let threads = NonZeroUsize::new(4).unwrap();
+
+    let sequential = tmp.path().join("sequential");
+    let parallel = tmp.path().join("parallel");
+    let summary = writer::write_files(&sequential, &files)?;
+    assert_eq!(
+        summary,
+        writer::write_files_parallel(&parallel, &files, threads)?
+    );
+    assert_eq!(summary.written, 40);
+    for file in &files {
+        assert_eq!(
+            fs::read_to_string(parallel.join(&file.relative_path))?,
+            fs::read_to_string(sequential.join(&file.relative_path))?
+        );
+    }
+
+    // Unchanged files are skipped, and the counts don't depend on how the work was split.
+    let mut changed = files.clone();
+    changed[7].contents = "// edited\n".to_owned();
+    let summary = writer::write_files_parallel(&parallel, &changed, threads)?;
+    assert_eq!((summary.written, summary.unchanged), (1, 39));
+
+    // With several failures, the first file's error is reported.
+    for blocker in ["src/blocked_a", "src/blocked_b"] {
+        fs::write(parallel.join(blocker), "")?;
+    }
+    let failing = [
+        generated("src/ok.rs", "// ok\n"),
+        generated("src/blocked_a/a.rs", "// a\n"),
+        generated("src/blocked_b/b.rs", "// b\n"),
+    ];
+    for _ in 0..5 {
+        let err = writer::write_files_parallel(&parallel, &failing, threads).unwrap_err();
+        assert!(err.to_string().contains("blocked_a"), "{}", err);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn clean_listed_files_removes_only_files_no_longer_generated() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path();
+    let previous = [
+        generated("src/models/kept.rs", "// kept\n"),
+        generated("src/models/dropped/mod.rs", "// dropped\n"),
+    ];
+    writer::write_files(out_dir, &previous)?;
+    writer::write_manifest(
+        out_dir,
+        &previous
+            .iter()
+            .map(|file| file.relative_path.clone())
+            .collect(),
+    )?;
+    fs::write(out_dir.join("src/models/notes.rs"), "// hand-added\n")?;
+
+    let listed = writer::read_manifest(out_dir).expect("manifest should be readable");
+    writer::clean_listed_files(out_dir, &listed, &previous[..1])?;
+
+    assert!(out_dir.join("src/models/kept.rs").exists());
+    assert!(out_dir.join("src/models/notes.rs").exists());
+    assert!(!out_dir.join("src/models/dropped").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn format_typescript_pipes_only_ts_files_through_prettier() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempdir()?;
+    let out_dir = tmp.path().join("generated");
+    let mut files = vec![
+        generated("ts/models/article.ts", "export interface Article {}\n"),
+        generated("src/models/article.rs", "pub struct Article;\n"),
+        generated("openapi.json", "{}\n"),
+    ];
+
+    let missing = tmp.path().join("no-such-prettier");
+    assert!(!writer::format_typescript(&out_dir, &mut files, &missing)?);
+    assert_eq!(files[0].contents, "export interface Article {}\n");
+
+    // Stands in for prettier: tags the output with the `--stdin-filepath` it was given.
+    let prettier = tmp.path().join("prettier");
+    fs::write(&prettier, "#!/bin/sh\necho \"// formatted $2\"\ncat\n")?;
+    fs::set_permissions(&prettier, fs::Permissions::from_mode(0o755))?;
+    assert!(writer::format_typescript(&out_dir, &mut files, &prettier)?);
+
+    assert_eq!(
+        files[0].contents,
+        format!(
+            "// formatted {}\nexport interface Article {{}}\n",
+            out_dir.join("ts/models/article.ts").display()
+        )
+    );
+    assert_eq!(files[1].contents, "pub struct Article;\n");
+    assert_eq!(files[2].contents, "{}\n");
+    Ok(())
+}
+
+#[test]
+fn register_migrations_injects_above_the_marker_and_drops_deleted_ones() -> Result<()> {
+    let tmp = tempdir()?;
+    let out_dir = tmp.path();
+    let migrations = [
+        generated(
+            "migration/src/m20250913_120150_shelters.rs",
+            "// shelters\n",
+        ),
+        generated("migration/src/m20250913_120150_pets.rs", "// pets\n"),
+    ];
+    writer::write_files(out_dir, &migrations)?;
+    let modules = ["m20250913_120150_shelters", "m20250913_120150_pets"].map(String::from);
+
+    // A missing crate is scaffolded; registering twice lists each module once.
+    writer::register_migrations(out_dir, &modules)?;
+    writer::register_migrations(out_dir, &modules)?;
+    assert!(out_dir.join("migration/Cargo.toml").is_file());
+    let lib = fs::read_to_string(out_dir.join("migration/src/lib.rs"))?;
+    assert_eq!(lib.matches("mod m20250913_120150_pets;").count(), 1);
+    let shelters = lib
+        .find("            Box::new(m20250913_120150_shelters::Migration),\n")
+        .expect("shelters should be registered");
+    let pets = lib
+        .find("            Box::new(m20250913_120150_pets::Migration),\n")
+        .expect("pets should be registered");
+    let marker = lib.find("// inject-above").expect("marker should be kept");
+    assert!(shelters < pets && pets < marker, "{lib}");
+
+    assert_eq!(
+        writer::existing_migrations(out_dir)?
+            .get("pets")
+            .map(String::as_str),
+        Some("m20250913_120150_pets")
+    );
+
+    fs::remove_file(out_dir.join("migration/src/m20250913_120150_pets.rs"))?;
+    writer::register_migrations(out_dir, &modules[..1])?;
+    let lib = fs::read_to_string(out_dir.join("migration/src/lib.rs"))?;
+    assert!(!lib.contains("m20250913_120150_pets"), "{lib}");
+    assert!(lib.contains("Box::new(m20250913_120150_shelters::Migration),"));
+
+    Ok(())
+}